@@ -0,0 +1,151 @@
+//! Copying text to the system clipboard
+//!
+//! Two ways of copying are supported. The OSC 52 escape sequence asks the terminal itself to put
+//! the text on the clipboard, which also works over SSH as long as the terminal supports it. The
+//! native way pipes the text into the clipboard program of the platform, like `pbcopy` or
+//! `xclip`. Neither of them needs any extra dependencies.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use crate::Clipboard;
+
+/// Copy `text` to the clipboard using the given method
+///
+/// The OSC 52 sequence is written to `out`. Nothing is done if `clipboard` is
+/// [`Clipboard::Disabled`].
+///
+/// # Errors
+/// This returns an error if the sequence could not be written or if no clipboard program could
+/// be run
+pub fn copy(out: &mut impl Write, clipboard: Clipboard, text: &str) -> io::Result<()> {
+    match clipboard {
+        Clipboard::Osc52 => {
+            write!(out, "{}", osc52(text))?;
+            out.flush()?;
+        }
+        Clipboard::Native => copy_native(text)?,
+        Clipboard::Disabled => {}
+    }
+    Ok(())
+}
+
+/// Make the OSC 52 sequence that sets the clipboard to `text`
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+/// Pipe `text` into the first clipboard program of the platform that works
+fn copy_native(text: &str) -> io::Result<()> {
+    let programs: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    };
+
+    for (program, args) in programs {
+        let child = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        // Try the next program if this one isn't installed
+        if let Ok(mut child) = child {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(text.as_bytes())?;
+            }
+            if child.wait()?.success() {
+                return Ok(());
+            }
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no working clipboard program found",
+    ))
+}
+
+/// Remove the ANSI escape sequences from `line`, so that only the text is copied
+pub fn strip_escapes(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        // Skip a CSI sequence upto its final byte
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+/// Encode `data` as standard base64 with padding
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len() / 3 * 4 + 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64, copy, osc52, strip_escapes};
+    use crate::Clipboard;
+
+    #[test]
+    fn encode_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn osc52_sequence() {
+        assert_eq!(osc52("hello"), "\x1b]52;c;aGVsbG8=\x07");
+
+        let mut out = Vec::new();
+        copy(&mut out, Clipboard::Osc52, "hello").unwrap();
+        assert_eq!(out, b"\x1b]52;c;aGVsbG8=\x07");
+
+        let mut out = Vec::new();
+        copy(&mut out, Clipboard::Disabled, "hello").unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn strip_ansi_escapes() {
+        assert_eq!(strip_escapes("\x1b[1;31mred\x1b[0m text"), "red text");
+        assert_eq!(strip_escapes("plain"), "plain");
+    }
+}
@@ -7,7 +7,9 @@ use crossterm::{
 
 use std::{cmp::Ordering, convert::TryInto, io::Write};
 
-use super::term::move_cursor;
+#[cfg(feature = "search")]
+use super::search;
+use super::term::{move_cursor, MIN_ROWS};
 use crate::{error::MinusError, PagerState};
 
 /// Handles drawing of screen based on movement
@@ -21,13 +23,33 @@ pub fn draw_for_change(
     p: &mut PagerState,
     new_upper_mark: &mut usize,
 ) -> Result<(), MinusError> {
+    // There is nothing to scroll if the terminal is too small, just show the message
+    if p.rows < MIN_ROWS {
+        return draw_full(out, p);
+    }
+
+    // Scrolling the terminal would move the pinned header or the other pane of a split view
+    // along with the text, so redraw everything instead
+    if p.pinned_rows() > 0 || p.split.is_some() {
+        p.upper_mark = *new_upper_mark;
+        draw_full(out, p)?;
+        *new_upper_mark = p.upper_mark;
+        return Ok(());
+    }
+
     let line_count = p.num_lines();
 
-    // Reduce one row for prompt/messages
+    // Reduce the rows for prompt/messages
     //
     // NOTE This should be the value of rows that should be used throughout this function.
     // Don't use PagerState::rows, it might lead to wrong output
-    let writable_rows = p.rows.saturating_sub(1);
+    let writable_rows = p.content_rows();
+
+    // Never scroll past the last line, unless overscroll is allowed. This is done before anything
+    // else so that the screen is scrolled by exactly as much as the upper mark moves
+    *new_upper_mark = (*new_upper_mark)
+        .min(p.max_upper_mark())
+        .max(p.min_upper_mark());
 
     let delta = new_upper_mark.abs_diff(p.upper_mark);
 
@@ -36,12 +58,6 @@ pub fn draw_for_change(
     let lower_bound = p.upper_mark.saturating_add(writable_rows.min(line_count));
     let new_lower_bound = new_upper_mark.saturating_add(writable_rows.min(line_count));
 
-    // If the lower_bound is greater than the avilable line count, we set it to such a value
-    // so that the last page can be displayed entirely, i.e never scroll past the last line
-    if new_lower_bound > line_count {
-        *new_upper_mark = line_count.saturating_sub(writable_rows);
-    }
-
     // Sometimes the value of delta is too large that we can rather use the value of the writable rows to
     // achieve the same effect with better performance. This means that we have to less lines to the terminal
     //
@@ -56,7 +72,7 @@ pub fn draw_for_change(
     // need this value whatever the value of delta be.
     let normalized_delta = delta.min(writable_rows);
 
-    let lines = match (*new_upper_mark).cmp(&p.upper_mark) {
+    let (start, end) = match (*new_upper_mark).cmp(&p.upper_mark) {
         Ordering::Greater => {
             // Scroll down `normalized_delta` lines, and put the cursor one line above, where the old prompt would present.
             // Clear it off and start displaying new dta.
@@ -67,18 +83,24 @@ pub fn draw_for_change(
             move_cursor(
                 out,
                 0,
-                p.rows
-                    .saturating_sub(normalized_delta + 1)
+                writable_rows
+                    .saturating_sub(normalized_delta)
                     .try_into()
                     .unwrap(),
                 false,
             )?;
-            queue!(out, Clear(ClearType::CurrentLine))?;
+            // The rows of the old message log and prompt scrolled along with the text, so they
+            // need to be cleared too
+            if p.message_log_rows() == 0 && p.padding_rows() == 0 && p.prompt_rows_shown() == 1 {
+                queue!(out, Clear(ClearType::CurrentLine))?;
+            } else {
+                queue!(out, Clear(ClearType::FromCursorDown))?;
+            }
 
             if delta < writable_rows {
-                p.get_flattened_lines_with_bounds(lower_bound, new_lower_bound)
+                (lower_bound, new_lower_bound)
             } else {
-                p.get_flattened_lines_with_bounds(
+                (
                     *new_upper_mark,
                     new_upper_mark.saturating_add(normalized_delta),
                 )
@@ -91,7 +113,7 @@ pub fn draw_for_change(
             )?;
             move_cursor(out, 0, 0, false)?;
 
-            p.get_flattened_lines_with_bounds(
+            (
                 *new_upper_mark,
                 new_upper_mark.saturating_add(normalized_delta),
             )
@@ -99,10 +121,12 @@ pub fn draw_for_change(
         Ordering::Equal => return Ok(()),
     };
 
-    for line in lines {
-        writeln!(out, "\r{}", line)?;
-    }
+    write_lines_in_range(out, p, start, end)?;
+    write_scrollbar(out, p, *new_upper_mark)?;
 
+    // Scrolling moves the message log and padding along with the text, so put them back in their
+    // place
+    write_message_log(out, p)?;
     super::display::write_prompt(out, &p.displayed_prompt, p.rows.try_into().unwrap())?;
     out.flush()?;
 
@@ -110,15 +134,153 @@ pub fn draw_for_change(
 }
 
 /// Write given text at the prompt site
+///
+/// The text is written as is, any styling of the prompt is expected to be part of the text. See
+/// [`PagerState::format_prompt`]. If the text has several lines, they are written in the last
+/// rows of the terminal, one line per row.
 pub fn write_prompt(out: &mut impl Write, text: &str, rows: u16) -> Result<(), MinusError> {
-    write!(
-        out,
-        "{mv}\r{rev}{prompt}{reset}",
-        mv = MoveTo(0, rows),
-        rev = Attribute::Reverse,
-        prompt = text,
-        reset = Attribute::Reset,
-    )?;
+    // The screen is taken up by the message from draw_full when the terminal is too small
+    if usize::from(rows) < MIN_ROWS {
+        return Ok(());
+    }
+    let num_rows = text.lines().count().try_into().unwrap_or(u16::MAX);
+    for (row, line) in (rows.saturating_sub(num_rows)..rows).zip(text.lines()) {
+        write!(out, "{mv}\r{prompt}", mv = MoveTo(0, row), prompt = line)?;
+    }
+    Ok(())
+}
+
+/// Redraw only the prompt, leaving the text on the screen as it is
+///
+/// This is for changes that don't affect anything but the prompt, so that the text doesn't
+/// flicker. The prompt of the list of keybindings stays until it is closed, so nothing is
+/// drawn while it is shown.
+pub fn draw_prompt_only(out: &mut impl Write, pager: &PagerState) -> Result<(), MinusError> {
+    if !pager.show_help {
+        let rows = pager.rows.try_into().map_err(|_| MinusError::Conversion)?;
+        write_prompt(out, &pager.displayed_prompt, rows)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Write the text exactly as it was given to the pager
+///
+/// This is for when the output is not a terminal but is piped into another program. Nothing is
+/// wrapped, numbered or styled, and the text ends with a newline only if it was given one. The
+/// lines of a [`PagerState::text_source`] all end with a newline.
+#[cfg(feature = "static_output")]
+pub fn dump_text(out: &mut impl Write, pager: &PagerState) -> Result<(), MinusError> {
+    if pager.text_source.is_some() {
+        for line in pager.source_lines() {
+            writeln!(out, "{}", line)?;
+        }
+    }
+    out.write_all(pager.lines.as_bytes())?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Write the [`PagerState::empty_placeholder`] in the middle of the rows for the text
+///
+/// This is only done while there is no text. The placeholder is cut off if it is wider than the
+/// screen.
+pub fn write_placeholder(out: &mut impl Write, pager: &PagerState) -> Result<(), MinusError> {
+    let text = match &pager.empty_placeholder {
+        Some(text) if pager.num_lines() == 0 => crate::truncate_str(text, pager.cols, None),
+        _ => return Ok(()),
+    };
+    let width = textwrap::core::display_width(&text);
+    let col = (pager.cols.saturating_sub(width) / 2)
+        .try_into()
+        .map_err(|_| MinusError::Conversion)?;
+    let row = (pager.content_rows() / 2)
+        .try_into()
+        .map_err(|_| MinusError::Conversion)?;
+    write!(out, "{}{}", MoveTo(col, row), text)?;
+    Ok(())
+}
+
+/// Write the scrollbar in the last column of the rows in which the text scrolls
+///
+/// The thumb shows which part of the text is displayed when the upper mark is at `upper_mark`.
+/// If a search is active, the parts of the bar that correspond to rows with a match are marked.
+/// Nothing is written if the scrollbar is turned off or if there is nothing to scroll.
+pub fn write_scrollbar(
+    out: &mut impl Write,
+    p: &PagerState,
+    upper_mark: usize,
+) -> Result<(), MinusError> {
+    let top = p.pinned_rows();
+    let bar_len = p.page_rows();
+    let total = p.num_lines().saturating_sub(top);
+    if !p.show_scrollbar || p.cols == 0 || bar_len == 0 || total <= bar_len {
+        return Ok(());
+    }
+
+    let thumb_len = (bar_len * bar_len / total).max(1);
+    let max_pos = p.max_upper_mark().saturating_sub(p.min_upper_mark());
+    let pos = upper_mark.saturating_sub(top).min(max_pos);
+    let thumb_start = (pos * (bar_len - thumb_len))
+        .checked_div(max_pos)
+        .unwrap_or(0);
+
+    #[cfg(feature = "search")]
+    let ticks = if p.search_term.is_some() && !p.highlights_hidden {
+        p.search_idx
+            .iter()
+            .filter(|&&row| row >= top)
+            .map(|&row| (row - top) * bar_len / total)
+            .collect()
+    } else {
+        std::collections::BTreeSet::new()
+    };
+
+    let col = (p.cols - 1)
+        .try_into()
+        .map_err(|_| MinusError::Conversion)?;
+    for idx in 0..bar_len {
+        let ch = if (thumb_start..thumb_start + thumb_len).contains(&idx) {
+            '█'
+        } else {
+            #[cfg(feature = "search")]
+            if ticks.contains(&idx) {
+                '─'
+            } else {
+                '│'
+            }
+            #[cfg(not(feature = "search"))]
+            '│'
+        };
+        let row = (top + idx).try_into().map_err(|_| MinusError::Conversion)?;
+        write!(out, "{}{}", MoveTo(col, row), ch)?;
+    }
+    Ok(())
+}
+
+/// Write the message log in the rows above the prompt
+///
+/// The messages are written oldest first and are cut off at the terminal width. Each row is
+/// cleared before writing, hence this also clears any leftovers from scrolling. The padding rows
+/// between the log and the prompt are cleared too.
+pub fn write_message_log(out: &mut impl Write, p: &PagerState) -> Result<(), MinusError> {
+    let log_rows = p.message_log_rows();
+    let skip = p.message_log.len().saturating_sub(log_rows);
+    let mut messages = p.message_log.iter().skip(skip);
+
+    // Also clear the padding rows below the log, so that no text is left in them after scrolling
+    for row in p.content_rows()..p.rows.saturating_sub(p.prompt_rows_shown()) {
+        move_cursor(
+            out,
+            0,
+            row.try_into().map_err(|_| MinusError::Conversion)?,
+            false,
+        )?;
+        queue!(out, Clear(ClearType::CurrentLine))?;
+        if let Some(msg) = messages.next() {
+            write!(out, "{}", msg.chars().take(p.cols).collect::<String>())?;
+        }
+    }
     Ok(())
 }
 
@@ -130,14 +292,37 @@ pub fn write_prompt(out: &mut impl Write, text: &str, rows: u16) -> Result<(), M
 /// The function will first print out the lines from the current upper_mark. This is handled inside the [`write_lines`]
 /// function.
 ///
+/// Then it writes the message log, if there is one, see [`write_message_log`].
+///
 /// Then it wil check if there is any message to display.
 ///   - If there is one, it will display it at the prompt site
 ///   - If there isn't one, it will display the prompt in place of it
+///
+/// If the terminal has less than [`MIN_ROWS`] rows, a message telling that the terminal is too
+/// small is displayed instead. While the list of keybindings is shown, only that is drawn, see
+/// [`write_help`].
 pub fn draw_full(out: &mut impl Write, pager: &mut PagerState) -> Result<(), MinusError> {
     super::term::move_cursor(out, 0, 0, false)?;
     queue!(out, Clear(ClearType::All))?;
 
-    write_lines(out, pager)?;
+    if pager.rows < MIN_ROWS {
+        let msg = "Terminal too small".chars().take(pager.cols);
+        write!(out, "\r{}", msg.collect::<String>())?;
+        return out.flush().map_err(MinusError::Draw);
+    }
+
+    if pager.show_help {
+        return write_help(out, pager);
+    }
+
+    if pager.split.is_some() {
+        write_split_lines(out, pager)?;
+    } else {
+        write_lines(out, pager)?;
+    }
+    write_placeholder(out, pager)?;
+    write_scrollbar(out, pager, pager.upper_mark)?;
+    write_message_log(out, pager)?;
 
     let pager_rows: u16 = pager.rows.try_into().map_err(|_| MinusError::Conversion)?;
 
@@ -146,6 +331,20 @@ pub fn draw_full(out: &mut impl Write, pager: &mut PagerState) -> Result<(), Min
     out.flush().map_err(MinusError::Draw)
 }
 
+/// Write the list of keybindings in place of the text
+///
+/// The list is formatted ahead of time by [`PagerState::format_help`], so the state of the text,
+/// like its position, is left untouched. The list is cut off if it doesn't fit on the screen.
+pub fn write_help(out: &mut impl Write, pager: &PagerState) -> Result<(), MinusError> {
+    for line in pager.help_rows.iter().take(pager.rows.saturating_sub(1)) {
+        writeln!(out, "\r{}", line)?;
+    }
+
+    let rows = pager.rows.try_into().map_err(|_| MinusError::Conversion)?;
+    write_prompt(out, &pager.help_prompt, rows)?;
+    out.flush().map_err(MinusError::Draw)
+}
+
 /// Write the lines to the terminal
 ///
 /// Draws (at most) `rows -1` lines, where the first line to display is
@@ -153,15 +352,35 @@ pub fn draw_full(out: &mut impl Write, pager: &mut PagerState) -> Result<(), Min
 /// possible within `rows -1`.
 ///
 /// It always skips one row at the botton as a site for the prompt or any message that may be sent.
+/// The rows of the message log are skipped as well, see [`PagerState::content_rows`].
 ///
 /// This function ensures that upper mark never exceeds a value such that adding upper mark and available rows exceeds
 /// the number of lines of text data. This rule is disobeyed in only one special case which is if number of lines of
-/// text is less than available rows. In this situation, upper mark is always 0.
+/// text is less than available rows. In this situation, upper mark is always 0. With
+/// [`PagerState::overscroll`], the upper mark can go up to the last line instead.
+///
+/// If there is a pinned header, it is written first and the upper mark is kept below it.
 pub fn write_lines(out: &mut impl Write, pager: &mut PagerState) -> Result<(), MinusError> {
     let line_count = pager.num_lines();
 
-    // Reduce one row for prompt/messages
-    let writable_rows = pager.rows.saturating_sub(1);
+    // All rows are taken up by the prompt, or there are none at all
+    if pager.content_rows() == 0 {
+        return Ok(());
+    }
+
+    // The pinned header is always displayed first
+    let header_rows = pager.pinned_rows();
+    write_lines_in_range(out, pager, 0, header_rows)?;
+
+    // Reduce the rows for prompt/messages and the header
+    let writable_rows = pager.page_rows();
+
+    // Never scroll past the last line, i.e the last page is always displayed entirely, unless
+    // overscroll is allowed. Also never scroll into the header
+    pager.upper_mark = pager
+        .upper_mark
+        .min(pager.max_upper_mark())
+        .max(pager.min_upper_mark());
 
     // Calculate the lower_mark by adding either the rows or line_count depending
     // on the minimality
@@ -169,16 +388,99 @@ pub fn write_lines(out: &mut impl Write, pager: &mut PagerState) -> Result<(), M
         .upper_mark
         .saturating_add(writable_rows.min(line_count));
 
-    // If the lower_bound is greater than the avilable line count, we set it to such a value
-    // so that the last page can be displayed entirely, i.e never scroll past the last line
-    if lower_mark > line_count {
-        pager.upper_mark = line_count.saturating_sub(writable_rows);
-    }
+    write_lines_in_range(out, pager, pager.upper_mark, lower_mark)
+}
+
+/// Write the two panes of a split view side by side
+///
+/// Both panes display the same text, each from its own upper mark, and are separated by a
+/// vertical line. The upper marks are kept within the same bounds as in [`write_lines`]. The
+/// pane that doesn't have the focus is styled with [`PagerState::inactive_pane_style`].
+pub fn write_split_lines(out: &mut impl Write, pager: &mut PagerState) -> Result<(), MinusError> {
+    let (min, max) = (pager.min_upper_mark(), pager.max_upper_mark());
+    pager.upper_mark = pager.upper_mark.min(max).max(min);
+    let split = match &mut pager.split {
+        Some(split) => {
+            split.other_upper_mark = split.other_upper_mark.min(max).max(min);
+            *split
+        }
+        None => return write_lines(out, pager),
+    };
 
-    // Add \r to ensure cursor is placed at the beginning of each row
-    let lines = pager.get_flattened_lines_with_bounds(pager.upper_mark, lower_mark);
+    let (left, right) = if split.right_focused {
+        (split.other_upper_mark, pager.upper_mark)
+    } else {
+        (pager.upper_mark, split.other_upper_mark)
+    };
+    let separator: u16 = pager
+        .pane_cols()
+        .try_into()
+        .map_err(|_| MinusError::Conversion)?;
+    let reset = if pager.use_colors {
+        Attribute::Reset.to_string()
+    } else {
+        String::new()
+    };
 
-    for line in lines {
+    // The pane without the focus can be styled differently
+    let inactive_style = pager.inactive_pane_style.filter(|_| pager.use_colors);
+    let (left_style, right_style) = if split.right_focused {
+        (inactive_style, None)
+    } else {
+        (None, inactive_style)
+    };
+    let pane_row = |row, style| {
+        let line = pager.render_row(row, &pager.row(row)).into_owned();
+        match style {
+            Some(style) => crate::apply_style_to_row(style, &line),
+            None => line,
+        }
+    };
+
+    let mut right_rows = pager.visible_row_indices_from(right);
+    for (row, line) in pager.visible_row_indices_from(left).enumerate() {
+        let row = row.try_into().map_err(|_| MinusError::Conversion)?;
+        write!(
+            out,
+            "{}{}{}",
+            MoveTo(0, row),
+            pane_row(line, left_style),
+            reset
+        )?;
+        write!(out, "{}│", MoveTo(separator, row))?;
+        if let Some(line) = right_rows.next() {
+            write!(out, "{}", pane_row(line, right_style))?;
+        }
+        write!(out, "{}", reset)?;
+    }
+    Ok(())
+}
+
+/// Write the rows of the text that lie between `start` and `end`
+///
+/// Each line is prefixed with a `\r` to ensure that the cursor is placed at the beginning of the row.
+/// If a search is active and [`PagerState::dim_non_matches`] is set, the lines which do not
+/// contain a match are dimmed, unless the highlights of the search have been cleared. The [`PagerState::render_transform`] is applied to the lines after
+/// the search matches are highlighted and before they are dimmed.
+fn write_lines_in_range(
+    out: &mut impl Write,
+    pager: &PagerState,
+    start: usize,
+    end: usize,
+) -> Result<(), MinusError> {
+    for idx in start..end.min(pager.num_lines()) {
+        let row = pager.row(idx);
+        let line = pager.render_row(idx, &row);
+        #[cfg(feature = "search")]
+        if pager.dim_non_matches
+            && pager.use_colors
+            && pager.search_term.is_some()
+            && !pager.highlights_hidden
+            && !pager.line_has_match(idx)
+        {
+            writeln!(out, "\r{}", search::dim_line(&line))?;
+            continue;
+        }
         writeln!(out, "\r{}", line)?;
     }
     Ok(())
@@ -432,6 +432,118 @@ fn draw_help_message() {
     assert!(res.contains("minus"));
 }
 
+#[test]
+fn draw_terminal_too_small() {
+    const TEXT: &str = "This is a line of text to the pager";
+    let mut out = Vec::with_capacity(TEXT.len());
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 1;
+    pager.lines = TEXT.to_string();
+    pager.format_lines();
+    draw_full(&mut out, &mut pager).unwrap();
+
+    let res = String::from_utf8(out).expect("Should have written valid UTF-8");
+    assert!(res.contains("Terminal too small"));
+    assert!(!res.contains(TEXT));
+}
+
+#[test]
+fn no_rows_for_text() {
+    for rows in 0..2 {
+        let mut pager = PagerState::new().unwrap();
+        pager.rows = rows;
+        pager.lines = "First line\nSecond line\nThird line\n".to_string();
+        pager.format_lines();
+        pager.upper_mark = 1;
+
+        let mut out = Vec::new();
+        write_lines(&mut out, &mut pager).unwrap();
+        assert!(out.is_empty());
+
+        let mut out = Vec::new();
+        draw_full(&mut out, &mut pager).unwrap();
+        let res = String::from_utf8(out).expect("Should have written valid UTF-8");
+        assert!(res.contains("Terminal too small"));
+    }
+}
+
+#[test]
+fn upper_mark_near_max() {
+    // `G` sets the upper mark to usize::MAX - 1, which must not overflow with a short text
+    for overscroll in [false, true] {
+        let mut pager = PagerState::new().unwrap();
+        pager.rows = 4;
+        pager.overscroll = overscroll;
+        pager.lines = "First line\nSecond line\nThird line\n".to_string();
+        pager.format_lines();
+
+        let mut out = Vec::new();
+        pager.upper_mark = usize::MAX - 1;
+        write_lines(&mut out, &mut pager).unwrap();
+        assert_eq!(pager.upper_mark, if overscroll { 2 } else { 0 });
+
+        let mut out = Vec::new();
+        let mut upper_mark = usize::MAX - 1;
+        draw_for_change(&mut out, &mut pager, &mut upper_mark).unwrap();
+        assert_eq!(upper_mark, pager.upper_mark);
+    }
+}
+
+#[test]
+fn overscroll() {
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 4;
+    pager.lines = "First line\nSecond line\nThird line\nFourth line\nFifth line\n".to_string();
+    pager.format_lines();
+    pager.overscroll = true;
+
+    // The last line can be at the top, but not any further
+    let mut out = Vec::new();
+    pager.upper_mark = 10;
+    write_lines(&mut out, &mut pager).unwrap();
+    assert_eq!(
+        "\rFifth line\n",
+        String::from_utf8(out).expect("Should have written valid UTF-8")
+    );
+    assert_eq!(pager.upper_mark, 4);
+
+    let mut out = Vec::new();
+    pager.overscroll = false;
+    write_lines(&mut out, &mut pager).unwrap();
+    assert_eq!(
+        "\rThird line\n\rFourth line\n\rFifth line\n",
+        String::from_utf8(out).expect("Should have written valid UTF-8")
+    );
+    assert_eq!(pager.upper_mark, 2);
+}
+
+#[test]
+fn draw_message_log() {
+    let mut out = Vec::new();
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 6;
+    pager.lines = "one\ntwo\nthree\nfour\nfive\n".to_string();
+    pager.format_lines();
+    pager.message_lines = 2;
+    pager.log_message("first message".to_string());
+    pager.log_message("second message".to_string());
+    pager.log_message("third message".to_string());
+    draw_full(&mut out, &mut pager).unwrap();
+
+    let res = String::from_utf8(out).expect("Should have written valid UTF-8");
+    // Two rows are taken by the log and one by the prompt
+    assert!(res.contains("three"));
+    assert!(!res.contains("four"));
+    assert!(!res.contains("first message"));
+    assert!(res.contains("second message"));
+    assert!(res.contains("third message"));
+
+    // At least one row is left for the text on small terminals
+    pager.rows = 3;
+    assert_eq!(pager.message_log_rows(), 1);
+    assert_eq!(pager.content_rows(), 1);
+}
+
 #[test]
 fn test_draw_no_overflow() {
     const TEXT: &str = "This is a line of text to the pager";
@@ -445,6 +557,80 @@ fn test_draw_no_overflow() {
         .contains(TEXT));
 }
 
+#[test]
+#[cfg(feature = "search")]
+#[allow(clippy::trivial_regex)]
+fn dim_non_matches() {
+    use crossterm::style::Attribute;
+
+    let lines = "A line\nA matching line\nAnother line";
+    let mut out = Vec::with_capacity(lines.len());
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = lines.to_string();
+    pager.search_term = Some(regex::Regex::new("matching").unwrap());
+    pager.dim_non_matches = true;
+    pager.format_lines();
+
+    write_lines(&mut out, &mut pager).unwrap();
+
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    let dim = |line: &str| {
+        format!(
+            "\r{}{}{}\n",
+            Attribute::Dim,
+            line,
+            Attribute::NormalIntensity
+        )
+    };
+    assert_eq!(
+        written,
+        format!(
+            "{}\r{}\n{}",
+            dim("A line"),
+            pager.formatted_lines[1],
+            dim("Another line")
+        )
+    );
+
+    // Nothing should be dimmed when no search is active
+    let mut out = Vec::with_capacity(lines.len());
+    pager.search_term = None;
+    pager.format_lines();
+    write_lines(&mut out, &mut pager).unwrap();
+    assert_eq!(
+        String::from_utf8(out).expect("Should have written valid UTF-8"),
+        "\rA line\n\rA matching line\n\rAnother line\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "search")]
+#[allow(clippy::trivial_regex)]
+fn dim_non_matches_wrapped() {
+    use crossterm::style::Attribute;
+
+    let mut pager = PagerState::new().unwrap();
+    pager.cols = 10;
+    // The matching line wraps into three rows, with the match split across the last two
+    pager.lines = "A line\nfirst row matchingrow\nlast".to_string();
+    pager.search_term = Some(regex::Regex::new("matchingrow").unwrap());
+    pager.dim_non_matches = true;
+    pager.format_lines();
+    assert_eq!(pager.num_lines(), 5);
+    assert_eq!(pager.search_idx.iter().collect::<Vec<_>>(), vec![&2]);
+
+    let mut out = Vec::new();
+    write_lines(&mut out, &mut pager).unwrap();
+    let written = String::from_utf8(out).unwrap();
+    let dimmed = written
+        .lines()
+        .filter(|row| row.contains(&Attribute::Dim.to_string()))
+        .count();
+    // Only the rows of the other lines are dimmed
+    assert_eq!(dimmed, 2);
+    assert!(!written.contains(&format!("{}first", Attribute::Dim)));
+}
+
 #[cfg(test)]
 mod draw_for_change_tests {
     use super::{draw_for_change, write_prompt};
@@ -576,3 +762,419 @@ mod draw_for_change_tests {
         assert_eq!(out, res);
     }
 }
+
+#[test]
+fn pinned_header() {
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 5;
+    pager.lines = "Header\nOne\nTwo\nThree\nFour\nFive\nSix\n".to_string();
+    pager.format_lines();
+    pager.pinned_header_lines = 1;
+
+    // The header is not displayed a second time at the top
+    let mut out = Vec::new();
+    write_lines(&mut out, &mut pager).unwrap();
+    assert_eq!(
+        "\rHeader\n\rOne\n\rTwo\n\rThree\n",
+        String::from_utf8(out).expect("Should have written valid UTF-8")
+    );
+    assert_eq!(pager.upper_mark, 1);
+
+    // The header stays at the top while scrolling, and the last page is still fully displayed
+    let mut out = Vec::new();
+    pager.upper_mark = 10;
+    write_lines(&mut out, &mut pager).unwrap();
+    assert_eq!(
+        "\rHeader\n\rFour\n\rFive\n\rSix\n",
+        String::from_utf8(out).expect("Should have written valid UTF-8")
+    );
+    assert_eq!(pager.upper_mark, 4);
+}
+
+#[test]
+fn prompt_bottom_padding() {
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 5;
+    pager.lines = "one\ntwo\nthree\nfour\nfive\n".to_string();
+    pager.format_lines();
+    pager.prompt_bottom_padding = 2;
+
+    let mut out = Vec::new();
+    write_lines(&mut out, &mut pager).unwrap();
+    assert_eq!(
+        "\rone\n\rtwo\n",
+        String::from_utf8(out).expect("Should have written valid UTF-8")
+    );
+
+    // Atleast one row is left for the text
+    pager.prompt_bottom_padding = 10;
+    assert_eq!(pager.content_rows(), 1);
+}
+
+#[test]
+fn scrollbar() {
+    use crossterm::cursor::MoveTo;
+
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 5;
+    pager.cols = 10;
+    pager.show_scrollbar = true;
+    pager.lines = "1\n2\n3\n4\n5\n6\n7\n8\n".to_string();
+    pager.format_lines();
+    assert_eq!(pager.wrap_cols(), 9);
+
+    let bar = |thumb: std::ops::Range<u16>| {
+        (0..4_u16).fold(String::new(), |mut s, row| {
+            let ch = if thumb.contains(&row) { '█' } else { '│' };
+            write!(s, "{}{}", MoveTo(9, row), ch).unwrap();
+            s
+        })
+    };
+
+    let mut out = Vec::new();
+    write_scrollbar(&mut out, &pager, 0).unwrap();
+    assert_eq!(bar(0..2), String::from_utf8(out).unwrap());
+
+    let mut out = Vec::new();
+    write_scrollbar(&mut out, &pager, 4).unwrap();
+    assert_eq!(bar(2..4), String::from_utf8(out).unwrap());
+
+    // Nothing is drawn if the text fits on the screen
+    pager.lines = "1\n2\n".to_string();
+    pager.format_lines();
+    let mut out = Vec::new();
+    write_scrollbar(&mut out, &pager, 0).unwrap();
+    assert!(out.is_empty());
+}
+
+#[test]
+fn split_view() {
+    use crate::state::Split;
+    use crossterm::cursor::MoveTo;
+
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 3;
+    pager.cols = 9;
+    pager.use_colors = false;
+    pager.split = Some(Split {
+        other_upper_mark: 3,
+        right_focused: false,
+        scroll_lock: false,
+    });
+    pager.lines = "1\n2\n3\n4\n5\n".to_string();
+    pager.format_lines();
+    assert_eq!(pager.wrap_cols(), 4);
+
+    let mut out = Vec::new();
+    write_split_lines(&mut out, &mut pager).unwrap();
+    let mut expected = String::new();
+    for (row, (left, right)) in [("1", "4"), ("2", "5")].iter().enumerate() {
+        let row = row as u16;
+        write!(
+            expected,
+            "{}{}{}│{}",
+            MoveTo(0, row),
+            left,
+            MoveTo(4, row),
+            right
+        )
+        .unwrap();
+    }
+    assert_eq!(expected, String::from_utf8(out).unwrap());
+}
+
+#[test]
+fn inactive_pane_style() {
+    use crate::state::Split;
+    use crossterm::{
+        cursor::MoveTo,
+        style::{Attribute, ContentStyle},
+    };
+
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 2;
+    pager.cols = 9;
+    pager.split = Some(Split {
+        other_upper_mark: 0,
+        right_focused: false,
+        scroll_lock: false,
+    });
+    pager.upper_mark = 1;
+    pager.inactive_pane_style = Some(ContentStyle {
+        attributes: Attribute::Dim.into(),
+        ..ContentStyle::default()
+    });
+    pager.lines = "\x1b[1m1\x1b[0m1\n2\n".to_string();
+    pager.format_lines();
+
+    let mut out = Vec::new();
+    write_split_lines(&mut out, &mut pager).unwrap();
+    // The reset in the text of the right pane doesn't turn off its styling
+    let (dim, reset) = (Attribute::Dim, Attribute::Reset);
+    assert_eq!(
+        format!(
+            "{}2{reset}{}│{dim}\x1b[1m1{reset}{dim}1{reset}{reset}",
+            MoveTo(0, 0),
+            MoveTo(4, 0),
+            dim = dim,
+            reset = reset
+        ),
+        String::from_utf8(out).unwrap()
+    );
+}
+
+#[test]
+fn render_transform() {
+    let mut pager = PagerState::new().unwrap();
+    pager.cols = 8;
+    pager.lines = "a\nbbbb cccc\n".to_string();
+    pager.format_lines();
+    pager.render_transform = Some(Box::new(|row, idx| format!("{}>{}", idx, row.repeat(2))));
+
+    let mut out = Vec::new();
+    write_lines(&mut out, &mut pager).unwrap();
+    // The rows of a wrapped line get the same index and the transformed rows are cut off at
+    // the edge of the screen
+    assert_eq!(
+        "\r0>aa\n\r1>bbbbbb\n\r1>cccccc\n",
+        String::from_utf8(out).unwrap()
+    );
+}
+
+#[test]
+fn empty_placeholder() {
+    use crossterm::cursor::MoveTo;
+
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 5;
+    pager.cols = 20;
+    pager.empty_placeholder = Some("Waiting".to_string());
+
+    let mut out = Vec::new();
+    write_placeholder(&mut out, &pager).unwrap();
+    assert_eq!(
+        format!("{}Waiting", MoveTo(6, 2)),
+        String::from_utf8(out).unwrap()
+    );
+
+    // The placeholder disappears once there is text
+    pager.lines = "text\n".to_string();
+    pager.format_lines();
+    let mut out = Vec::new();
+    write_placeholder(&mut out, &pager).unwrap();
+    assert!(out.is_empty());
+}
+
+#[test]
+fn help() {
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 30;
+    pager.cols = 80;
+    pager.lines = "some text\n".to_string();
+    pager.format_lines();
+    pager.show_help = true;
+    pager.format_help();
+
+    let mut out = Vec::new();
+    draw_full(&mut out, &mut pager).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(!out.contains("some text"));
+    assert!(out.contains("Ctrl+W") && out.contains("Toggle line wrapping"));
+    assert!(out.contains("Keybindings, press any key to close"));
+    // The text is left as it was
+    assert_eq!(pager.formatted_lines, vec!["some text"]);
+
+    pager.show_help = false;
+    let mut out = Vec::new();
+    draw_full(&mut out, &mut pager).unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("some text"));
+}
+
+#[test]
+fn custom_help() {
+    use crate::input::{default_help, DefaultInputClassifier, InputClassifier, InputEvent};
+    use crossterm::event::Event;
+
+    struct HelpClassifier;
+    impl InputClassifier for HelpClassifier {
+        fn classify_input(&self, ev: Event, ps: &PagerState) -> Option<InputEvent> {
+            DefaultInputClassifier.classify_input(ev, ps)
+        }
+        fn help(&self, ps: &PagerState) -> Vec<(String, String)> {
+            let mut help = default_help(ps);
+            help.push(("x".to_string(), "Do something custom".to_string()));
+            help
+        }
+    }
+
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 40;
+    pager.cols = 80;
+    pager.input_classifier = Box::new(HelpClassifier);
+    pager.show_help = true;
+    pager.format_help();
+
+    let mut out = Vec::new();
+    draw_full(&mut out, &mut pager).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("Do something custom"));
+    assert!(out.contains("Toggle line wrapping"));
+}
+
+#[test]
+fn prompt_rows() {
+    use crossterm::cursor::MoveTo;
+
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 10;
+    pager.cols = 10;
+    pager.use_colors = false;
+    pager.prompt_rows = 2;
+    pager.prompt = "a long prompt text".to_string();
+    pager.format_prompt();
+    assert_eq!(pager.content_rows(), 8);
+    // What doesn't fit into the rows is cut off
+    assert_eq!(pager.displayed_prompt, "a long    \nprompt te…");
+
+    let mut out = Vec::new();
+    write_prompt(&mut out, &pager.displayed_prompt, 10).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!("{}\ra long    {}\rprompt te…", MoveTo(0, 8), MoveTo(0, 9))
+    );
+
+    // Atleast one row is left for the text
+    pager.prompt_rows = 20;
+    assert_eq!(pager.content_rows(), 1);
+}
+
+#[test]
+#[cfg(feature = "static_output")]
+fn dump_text() {
+    let mut pager = PagerState::new().unwrap();
+    pager.cols = 5;
+    pager.rows = 3;
+    pager.line_numbers = LineNumbers::Enabled;
+
+    // The text is neither wrapped nor cut to the screen, and a missing newline at the end isn't
+    // added
+    for text in &["a long line\n\n", "1\n2\n3\n4\n5", "\x1b[1mbold\x1b[0m\r\n"] {
+        pager.lines = (*text).to_string();
+        pager.format_lines();
+        let mut out = Vec::new();
+        super::dump_text(&mut out, &pager).unwrap();
+        assert_eq!(out, text.as_bytes());
+    }
+}
+
+#[test]
+fn draw_prompt_only() {
+    use crossterm::cursor::MoveTo;
+
+    let mut pager = PagerState::new().unwrap();
+    pager.cols = 10;
+    pager.use_colors = false;
+    pager.lines = "some text\n".to_string();
+    pager.format_lines();
+    pager.prompt = "prompt".to_string();
+    pager.format_prompt();
+
+    // The text is left alone
+    let mut out = Vec::new();
+    super::draw_prompt_only(&mut out, &pager).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!("{}\rprompt    ", MoveTo(0, 9))
+    );
+
+    // The list of keybindings keeps its own prompt
+    pager.show_help = true;
+    let mut out = Vec::new();
+    super::draw_prompt_only(&mut out, &pager).unwrap();
+    assert!(out.is_empty());
+}
+
+#[test]
+fn over_wide_prompt() {
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 10;
+    pager.cols = 10;
+    pager.use_colors = false;
+    pager.prompt = "a prompt that is too wide".to_string();
+    pager.format_prompt();
+    assert_eq!(pager.displayed_prompt, "a prompt …");
+
+    // The prompt is cut by width, not by bytes, and leaves room for the indicators
+    pager.prompt = "ääääääääääää".to_string();
+    pager.prefix_num = "12".to_string();
+    pager.format_prompt();
+    assert_eq!(pager.displayed_prompt, "äääää… 12 ");
+
+    // Prompts that fit are left as they are
+    pager.prefix_num.clear();
+    pager.prompt = "short".to_string();
+    pager.format_prompt();
+    assert_eq!(pager.displayed_prompt, "short     ");
+}
+
+#[test]
+fn message_style() {
+    let mut pager = PagerState::new().unwrap();
+    pager.cols = 20;
+    pager.use_colors = false;
+    pager.prompt = "file.txt".to_string();
+    pager.message = Some("Copied".to_string());
+    pager.format_prompt();
+    assert_eq!(pager.displayed_prompt, "Copied              ");
+
+    pager.message_style = crate::MessageStyle::Append;
+    pager.format_prompt();
+    assert_eq!(pager.displayed_prompt, "file.txt - Copied   ");
+
+    // Without a prompt there is nothing to append to
+    pager.prompt.clear();
+    pager.format_prompt();
+    assert_eq!(pager.displayed_prompt, "Copied              ");
+}
+
+#[test]
+fn text_source() {
+    use crate::TextSource;
+
+    struct Lines(Vec<String>);
+    impl TextSource for Lines {
+        fn line_count(&self) -> usize {
+            self.0.len()
+        }
+        fn line(&self, idx: usize) -> &str {
+            &self.0[idx]
+        }
+    }
+
+    let mut pager = PagerState::new().unwrap();
+    pager.cols = 20;
+    let mut lines: Vec<String> = (1..=50).map(|n| format!("line {}", n)).collect();
+    lines[20] = "a long line that wraps".to_string();
+    pager.set_text_source(Box::new(Lines(lines)));
+
+    // Only the rows of each line are counted, the rows themselves are made when drawn
+    assert!(pager.formatted_lines.is_empty());
+    assert_eq!(pager.num_lines(), 51);
+    assert_eq!(pager.row(20), "a long line that");
+    assert_eq!(pager.row(21), "wraps");
+    assert_eq!(pager.row(22), "line 22");
+
+    pager.upper_mark = 19;
+    let mut out = Vec::new();
+    draw_full(&mut out, &mut pager).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("\rline 20\n\ra long line that\n\rwraps\n\rline 22\n"));
+    assert!(!out.contains("line 19\n"));
+
+    // Appending takes over the lines of the source
+    pager.append_str("line 51\n");
+    assert!(pager.text_source.is_none());
+    assert_eq!(pager.num_lines(), 52);
+    assert_eq!(pager.formatted_lines[21], "wraps");
+    assert_eq!(pager.formatted_lines[51], "line 51");
+}
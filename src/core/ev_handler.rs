@@ -9,8 +9,8 @@ use parking_lot::{Condvar, Mutex};
 use super::display::{self};
 #[cfg(feature = "search")]
 use super::search;
-use super::{events::Event, term};
-use crate::{error::MinusError, input::InputEvent, PagerState};
+use super::{clipboard, events::Event, term};
+use crate::{error::MinusError, input::InputEvent, state::Split, Clipboard, PagerState};
 
 /// Respond based on the type of event
 ///
@@ -28,70 +28,181 @@ pub fn handle_event(
     is_exitted: &Arc<AtomicBool>,
     #[cfg(feature = "search")] user_input_active: &Arc<(Mutex<bool>, Condvar)>,
 ) -> Result<(), MinusError> {
+    // Where the user was before the input, in case it is a jump that can be gone back from.
+    // Formatting the text again moves the rows while the same text stays on the screen, which
+    // isn't a jump. Scrolling after restoring the prompt is handled as a move of its own, which
+    // records the jump itself
+    let jump_from = match ev {
+        Event::UserInput(
+            InputEvent::UpdateTermArea(..)
+            | InputEvent::UpdateLineNumber(_)
+            | InputEvent::ToggleWrap
+            | InputEvent::RestorePromptAndScroll(_),
+        ) => None,
+        Event::UserInput(_) => Some(p.clamped_upper_mark()),
+        _ => None,
+    };
+    // Where the user was before the input, to tell whether it moved the text
+    let moved_from = match ev {
+        Event::UserInput(_) => Some(p.upper_mark),
+        _ => None,
+    };
+    let rebuild_help = ev.changes_help();
     match ev {
-        Event::SetData(text) => {
-            p.lines = text;
-            p.format_lines();
+        Event::SetData(text) => p.set_text(text),
+        Event::SetTextSource(source) => p.set_text_source(source),
+        Event::Batch(events) => {
+            for ev in events {
+                handle_event(
+                    ev,
+                    out,
+                    p,
+                    is_exitted,
+                    #[cfg(feature = "search")]
+                    user_input_active,
+                )?;
+            }
         }
         Event::UserInput(InputEvent::Exit) => {
             p.exit();
             is_exitted.store(true, std::sync::atomic::Ordering::SeqCst);
             term::cleanup(&mut out, &p.exit_strategy, true)?;
         }
+        Event::UserInput(InputEvent::Interrupt) => {
+            p.interrupted = true;
+            p.exit();
+            is_exitted.store(true, std::sync::atomic::Ordering::SeqCst);
+            term::cleanup(&mut out, &crate::ExitStrategy::PagerQuit, true)?;
+            // Let the signal take its usual course, so that the handlers of the application run.
+            // If the application survives it, the pager stops and reports the interrupt
+            #[cfg(unix)]
+            // SAFETY: raise has no preconditions, it only sends the signal to this thread
+            unsafe {
+                libc::raise(libc::SIGINT);
+            }
+        }
         Event::UserInput(InputEvent::UpdateUpperMark(mut um)) => {
+            scroll_locked_pane(p, um);
             display::draw_for_change(out, p, &mut um)?;
             p.upper_mark = um;
+            p.check_reached_bottom();
+        }
+        Event::UserInput(InputEvent::Copy) if p.clipboard != Clipboard::Disabled => {
+            let line = p.text_line(p.source_line(p.upper_mark));
+            let text = p.source_lines().nth(line).unwrap_or_default();
+            let text = clipboard::strip_escapes(text);
+            p.message = Some(match clipboard::copy(out, p.clipboard, &text) {
+                Ok(()) => format!("Copied line {}", p.line_number_base() + line + 1),
+                Err(_) => "Failed to copy to the clipboard".to_owned(),
+            });
+            p.format_prompt();
+        }
+        Event::UserInput(InputEvent::ReachedEnd) => {
+            // Display the entire last page, even with overscroll
+            p.upper_mark = p
+                .num_lines()
+                .saturating_sub(p.page_rows())
+                .max(p.min_upper_mark());
+            p.message = Some("Reached end of text".to_owned());
+            p.format_prompt();
         }
         Event::UserInput(InputEvent::RestorePrompt) => {
             // Set the message to None and new messages to false as all messages have been shown
             p.message = None;
             p.format_prompt();
         }
+        Event::UserInput(InputEvent::RestorePromptAndScroll(um)) => {
+            p.message = None;
+            p.format_prompt();
+            // Scroll like any other move, so that the upper mark is kept within the text
+            handle_event(
+                Event::UserInput(InputEvent::UpdateUpperMark(um)),
+                out,
+                p,
+                is_exitted,
+                #[cfg(feature = "search")]
+                user_input_active,
+            )?;
+        }
         Event::UserInput(InputEvent::UpdateTermArea(c, r)) => {
             p.rows = r;
             p.cols = c;
-            // Readjust the text wrapping for the new number of columns
-            p.format_lines();
+            // Readjust the text wrapping for the new number of columns. Otherwise the rows are
+            // cut off while drawing them, which only needs the prompt to be fit again
+            if p.rewrap_on_resize {
+                p.format_lines_on_resize();
+            } else {
+                p.format_prompt();
+            }
+        }
+        Event::UserInput(InputEvent::SetHalfPage(rows, down)) => {
+            p.half_page = Some(rows);
+            let mut um = if down {
+                p.upper_mark.saturating_add(rows)
+            } else {
+                p.upper_mark.saturating_sub(rows)
+            };
+            scroll_locked_pane(p, um);
+            display::draw_for_change(out, p, &mut um)?;
+            p.upper_mark = um;
+        }
+        Event::UserInput(InputEvent::SwitchPane) => {
+            if let Some(split) = &mut p.split {
+                std::mem::swap(&mut p.upper_mark, &mut split.other_upper_mark);
+                split.right_focused = !split.right_focused;
+            }
+        }
+        Event::UserInput(InputEvent::ToggleScrollLock) => {
+            if let Some(split) = &mut p.split {
+                split.scroll_lock = !split.scroll_lock;
+            }
+        }
+        Event::UserInput(InputEvent::ShowHelp) => p.show_help = true,
+        Event::UserInput(InputEvent::HideHelp) => p.show_help = false,
+        Event::UserInput(InputEvent::ToggleWrap) => {
+            p.line_wrapping = !p.line_wrapping;
+            p.format_lines_anchored();
+            let wrapping = p.line_wrapping;
+            if let Some(cb) = &mut p.on_wrap_changed {
+                cb(wrapping);
+            }
         }
         Event::UserInput(InputEvent::UpdateLineNumber(l)) => {
             p.line_numbers = l;
-            p.format_lines();
+            p.format_lines_anchored();
+            if let Some(cb) = &mut p.on_line_numbers_changed {
+                cb(l);
+            }
         }
         #[cfg(feature = "search")]
         Event::UserInput(InputEvent::Search(m)) => {
             p.search_mode = m;
+            // Every search starts out case sensitive
+            p.search_ignore_case = false;
             // Pause the main user input thread, read search query and then restart the main input thread
             let (lock, cvar) = (&user_input_active.0, &user_input_active.1);
             let mut active = lock.lock();
             *active = false;
             drop(active);
-            let string = search::fetch_input(&mut out, p.search_mode, p.rows)?;
+            let string =
+                search::fetch_input(&mut out, p.search_mode, p.rows, &mut p.search_ignore_case)?;
             let mut active = lock.lock();
             *active = true;
             drop(active);
             cvar.notify_one();
 
-            if !string.is_empty() {
-                let regex = regex::Regex::new(string.as_str());
-                if let Ok(r) = regex {
-                    p.search_term = Some(r);
-                    // Format the lines, this will automatically generate the PagerState.search_idx
-                    p.format_lines();
-                    // Reset search mark so it won't be out of bounds if we have
-                    // less matches in this search than last time
-                    p.search_mark = 0;
-                    // Move to next search match after the current upper_mark
-                    search::next_nth_match(p, 1);
-                    p.format_prompt();
-                    display::draw_full(&mut out, p)?;
-                } else {
-                    // Send invalid regex message at the prompt if invalid regex is given
-                    p.message = Some("Invalid regular expression. Press Enter".to_owned());
-                    p.format_prompt();
-                }
+            if !string.is_empty() && apply_search(p, &string) {
+                display::draw_full(&mut out, p)?;
             }
         }
         #[cfg(feature = "search")]
+        Event::UserInput(InputEvent::ClearHighlights) => {
+            // Only the highlights go away, the matches can still be moved between. The upper mark
+            // is left as is so the view stays where it is
+            p.highlights_hidden = true;
+            p.format_lines();
+        }
+        #[cfg(feature = "search")]
         Event::UserInput(InputEvent::NextMatch | InputEvent::MoveToNextMatch(1))
             if p.search_term.is_some() =>
         {
@@ -143,29 +254,276 @@ pub fn handle_event(
             p.prompt = prompt;
             p.format_prompt();
         }
+        Event::SendMessage(message) if p.message_lines > 0 => p.log_message(message),
         Event::SendMessage(message) => {
             p.message = Some(message);
             p.format_prompt();
         }
+        Event::SetOverscroll(val) => p.overscroll = val,
+        Event::SetMaxPrefixDigits(n) => p.max_prefix_digits = n,
+        // This gets clamped to the available text when it's drawn
+        Event::SetUpperMark(um) => p.upper_mark = um,
+        Event::ScrollBy(delta) => {
+            let current = p.clamped_upper_mark();
+            let um = if delta < 0 {
+                current.saturating_sub(delta.unsigned_abs())
+            } else {
+                current.saturating_add(delta.unsigned_abs())
+            };
+            // Scroll like the user would have, so that the scroll lock and the callbacks apply
+            let um = um.min(p.max_upper_mark()).max(p.min_upper_mark());
+            handle_event(
+                Event::UserInput(InputEvent::UpdateUpperMark(um)),
+                out,
+                p,
+                is_exitted,
+                #[cfg(feature = "search")]
+                user_input_active,
+            )?;
+        }
+        Event::SetMessageLines(lines) => {
+            p.message_lines = lines;
+            let excess = p.message_log.len().saturating_sub(lines);
+            p.message_log.drain(..excess);
+        }
         Event::SetLineNumbers(ln) => {
             p.line_numbers = ln;
+            p.format_lines_anchored();
+        }
+        Event::SetColorOutput(co) => {
+            p.color_output = co;
+            p.detect_colors();
+        }
+        Event::SetLineNumberStyle(style) => {
+            p.line_number_style = style;
+            p.format_lines();
+        }
+        Event::SetTrailingWhitespaceStyle(style) => {
+            p.trailing_whitespace_style = style;
+            // The whitespace that is kept can take up rows of its own
+            p.format_lines_anchored();
+        }
+        Event::SetLineNumberFormatter(formatter) => {
+            p.line_number_formatter = Some(formatter);
+            p.format_lines();
+        }
+        Event::SetRenderTransform(transform) => p.render_transform = Some(transform),
+        Event::SetLineNumberOffset(offset) => {
+            p.line_number_offset = offset;
+            // The width of the line numbers can change, which changes the wrapping
+            p.format_lines_anchored();
+        }
+        Event::SetMaxLines(max) => {
+            p.max_lines = max;
+            p.format_lines_within_max();
+        }
+        Event::SetPinnedHeaderLines(n) => p.pinned_header_lines = n,
+        Event::SetPromptBottomPadding(n) => p.prompt_bottom_padding = n,
+        Event::SetPromptRows(n) => {
+            p.prompt_rows = n;
+            p.format_prompt();
+        }
+        Event::SetClipboard(clipboard) => p.clipboard = clipboard,
+        Event::SetCtrlCBehavior(behavior) => p.ctrl_c_behavior = behavior,
+        Event::SetMessageStyle(style) => {
+            p.message_style = style;
+            p.format_prompt();
+        }
+        Event::SetAutoScroll(interval) => {
+            p.auto_scroll = interval;
+            p.auto_scroll_paused = false;
+        }
+        Event::SetLineWrapping(val) => {
+            p.line_wrapping = val;
+            p.format_lines_anchored();
+        }
+        Event::SetWrapWidth(width) => {
+            p.wrap_width = width;
+            // Changing the width rewraps the text just like resizing the terminal
+            p.format_lines_on_resize();
+        }
+        Event::SetWrapIndicator(indicator) => {
+            p.wrap_indicator = indicator;
+            // The indicator takes up space, so the lines can wrap into more rows
+            p.format_lines_anchored();
+        }
+        Event::SetEmptyPlaceholder(text) => p.empty_placeholder = text,
+        Event::SetReverseLines(val) => {
+            // Keep the same line at the top of the screen, which moves to a different row
+            let top_line = p.text_line(p.source_line(p.upper_mark));
+            p.reverse_lines = val;
+            p.format_lines();
+            p.upper_mark = p.first_row_of(p.text_line(top_line));
+        }
+        Event::SetSplit(val) if val != p.split.is_some() => {
+            p.split = if val {
+                Some(Split {
+                    other_upper_mark: 0,
+                    right_focused: false,
+                    scroll_lock: false,
+                })
+            } else {
+                None
+            };
+            // The panes are narrower than the terminal, so the text has to be rewrapped
+            p.format_lines_anchored();
+            // Both panes start at the same position
+            if let Some(split) = &mut p.split {
+                split.other_upper_mark = p.upper_mark;
+            }
+        }
+        Event::SetSplit(_) => {}
+        Event::SetInactivePaneStyle(style) => p.inactive_pane_style = style,
+        Event::SetShowScrollbar(show) => {
+            p.show_scrollbar = show;
+            // The scrollbar takes up the last column, so the text has to be rewrapped
+            p.format_lines_anchored();
+        }
+        Event::SetTruncationMarker(marker) => {
+            p.truncation_marker = marker;
+            p.format_lines_anchored();
+        }
+        Event::SetLineNumberToggleKey(key) => p.line_number_toggle_key = key,
+        Event::SetRedrawKey(key) => p.redraw_key = key,
+        Event::SetWrapToggleKey(key) => p.wrap_toggle_key = key,
+        Event::SetHelpKey(key) => p.help_key = key,
+        Event::AddExitKey(key) => {
+            if !p.exit_keys.contains(&key) {
+                p.exit_keys.push(key);
+            }
+        }
+        Event::RemoveExitKey(key) => p.exit_keys.retain(|&k| k != key),
+        #[cfg(feature = "search")]
+        Event::SetClearHighlightsKey(key) => p.clear_highlights_key = key,
+        Event::SetGutterMarker(idx, marker) => {
+            if let Some(marker) = marker {
+                p.gutter_markers.insert(idx, marker);
+            } else {
+                p.gutter_markers.remove(&idx);
+            }
             p.format_lines();
         }
         Event::SetExitStrategy(es) => p.exit_strategy = es,
         #[cfg(feature = "static_output")]
         Event::SetRunNoOverflow(val) => p.run_no_overflow = val,
+        #[cfg(feature = "search")]
+        Event::SetDimNonMatches(val) => p.dim_non_matches = val,
+        #[cfg(feature = "dynamic_output")]
+        Event::SetInlineUntil(val) => p.inline_until = Some(val),
+        #[cfg(feature = "dynamic_output")]
+        Event::SetExitWhenFits(val) => p.exit_when_fits = val,
+        #[cfg(feature = "dynamic_output")]
+        Event::SetExitOnDisconnect(val) => p.exit_on_disconnect = val,
         Event::SetInputClassifier(clf) => p.input_classifier = clf,
+        Event::SetPageOverlap(rows) => p.page_overlap = rows,
+        Event::SetManagedInput(val) => p.managed_input = val,
+        Event::SetRewrapOnResize(val) => p.rewrap_on_resize = val,
+        Event::SetResizeDebounce(interval) => p.resize_debounce = interval,
+        Event::SetKeySequenceTimeout(timeout) => p.key_sequence_timeout = timeout,
+        Event::SetEnterDismissAlsoScrolls(val) => p.enter_dismiss_also_scrolls = val,
+        Event::SetScrollAnchorOnSetData(anchor) => p.scroll_anchor_on_setdata = anchor,
         Event::AddExitCallback(cb) => p.exit_callbacks.push(cb),
+        Event::SetWrapProgressCallback(cb) => p.wrap_progress_callback = Some(cb),
+        Event::SetOnReachBottom(cb) => p.on_reach_bottom = Some(cb),
+        Event::SetOnLineNumbersChanged(cb) => p.on_line_numbers_changed = Some(cb),
+        Event::SetOnWrapChanged(cb) => p.on_wrap_changed = Some(cb),
+        Event::SetInputTrace(trace) => p.input_trace = Some(trace),
+        Event::SetLineFormatter(formatter) => p.set_line_formatter(formatter),
         Event::UserInput(_) => {}
     }
+    if rebuild_help && p.show_help {
+        p.format_help();
+    }
+    if let Some(from) = jump_from {
+        p.record_jump(from);
+    }
+    // Any move, like a half page or a jump to a search match, can bring the last line onto the
+    // screen
+    if matches!(moved_from, Some(from) if from != p.upper_mark) {
+        p.check_reached_bottom();
+    }
     Ok(())
 }
 
+/// Search for `query` and move to the first match after the upper mark
+///
+/// If nothing matches, the upper mark is left unchanged and a message tells the user so. Returns
+/// whether the screen has to be redrawn, which isn't the case if `query` is an invalid regex as
+/// only a message is shown then.
+#[cfg(feature = "search")]
+fn apply_search(p: &mut PagerState, query: &str) -> bool {
+    let regex = regex::RegexBuilder::new(query)
+        .case_insensitive(p.search_ignore_case)
+        .build();
+    if let Ok(r) = regex {
+        p.search_term = Some(r);
+        p.highlights_hidden = false;
+        // Format the lines, this will automatically generate the PagerState.search_idx
+        p.format_lines();
+        // Reset search mark so it won't be out of bounds if we have
+        // less matches in this search than last time
+        p.search_mark = 0;
+        if p.search_idx.is_empty() {
+            p.message = Some("Pattern not found".to_owned());
+        } else {
+            // Move to next search match after the current upper_mark
+            search::next_nth_match(p, 1);
+        }
+        p.format_prompt();
+        true
+    } else {
+        // Send invalid regex message at the prompt if invalid regex is given
+        p.message = Some("Invalid regular expression. Press Enter".to_owned());
+        p.format_prompt();
+        false
+    }
+}
+
+/// Scroll the other pane of a split view along with the focused one, if their scrolling is locked
+///
+/// The other pane moves by as many rows as the focused pane will move to get to `upper_mark`.
+fn scroll_locked_pane(p: &mut PagerState, upper_mark: usize) {
+    let (min, max) = (p.min_upper_mark(), p.max_upper_mark());
+    let (from, to) = (p.upper_mark.min(max).max(min), upper_mark.min(max).max(min));
+    if let Some(split) = &mut p.split {
+        if split.scroll_lock {
+            let other = split.other_upper_mark.min(max).max(min);
+            split.other_upper_mark = if to >= from {
+                other.saturating_add(to - from).min(max)
+            } else {
+                other.saturating_sub(from - to).max(min)
+            };
+        }
+    }
+}
+
+/// Scroll down by one line, or turn off auto scroll if the bottom has been reached
+pub fn auto_scroll_step(
+    out: &mut impl Write,
+    p: &mut PagerState,
+    is_exitted: &Arc<AtomicBool>,
+    #[cfg(feature = "search")] input_thread_running: &Arc<(Mutex<bool>, Condvar)>,
+) -> Result<(), MinusError> {
+    if p.upper_mark >= p.max_upper_mark() {
+        p.auto_scroll = None;
+        return Ok(());
+    }
+    handle_event(
+        Event::UserInput(InputEvent::UpdateUpperMark(p.upper_mark + 1)),
+        out,
+        p,
+        is_exitted,
+        #[cfg(feature = "search")]
+        input_thread_running,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::events::Event;
-    use super::handle_event;
-    use crate::{ExitStrategy, PagerState};
+    use super::{auto_scroll_step, handle_event};
+    use crate::input::InputEvent;
+    use crate::{Clipboard, ColorOutput, ExitStrategy, LineNumbers, PagerState};
     use std::sync::{atomic::AtomicBool, Arc};
     #[cfg(feature = "search")]
     use {
@@ -319,4 +677,721 @@ mod tests {
         .unwrap();
         assert_eq!(ps.exit_callbacks.len(), 1);
     }
+
+    #[test]
+    #[cfg_attr(feature = "search", allow(clippy::trivial_regex))]
+    fn set_color_output() {
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = "foo\nbar\n".repeat(20);
+        #[cfg(feature = "search")]
+        {
+            ps.search_term = Some(regex::Regex::new("bar").unwrap());
+        }
+        ps.format_lines();
+        ps.format_prompt();
+        let ev = Event::SetColorOutput(ColorOutput::Never);
+        let mut out = Vec::new();
+
+        handle_event(
+            ev,
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(!ps.use_colors);
+        assert!(!ps.displayed_prompt.contains('\x1b'));
+        assert!(ps.formatted_lines.iter().all(|l| !l.contains('\x1b')));
+        // Matches are still found and marked with brackets
+        #[cfg(feature = "search")]
+        {
+            assert_eq!(ps.search_idx.len(), 20);
+            assert_eq!(ps.formatted_lines[1], "[bar]");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    #[allow(clippy::trivial_regex)]
+    fn clear_highlights() {
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = "foo\nbar\n".repeat(20);
+        ps.search_term = Some(regex::Regex::new("bar").unwrap());
+        ps.format_lines();
+        ps.upper_mark = 5;
+        let ev = Event::UserInput(InputEvent::ClearHighlights);
+        let mut out = Vec::new();
+
+        handle_event(
+            ev,
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // The search is kept without its highlights
+        assert!(ps.search_term.is_some());
+        assert_eq!(ps.search_idx.len(), 20);
+        assert_eq!(ps.formatted_lines[1], "bar");
+        assert_eq!(ps.upper_mark, 5);
+
+        // The matches can still be moved between
+        handle_event(
+            Event::UserInput(InputEvent::NextMatch),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.upper_mark, 7);
+        assert_eq!(ps.formatted_lines[7], "bar");
+
+        // The next search is highlighted again
+        assert!(super::apply_search(&mut ps, "foo"));
+        assert!(!ps.highlights_hidden);
+        assert_ne!(ps.formatted_lines[0], "foo");
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn search_without_matches() {
+        let mut ps = PagerState::with_dimensions(5, 80);
+        ps.lines = "one\ntwo\nthree\nfour\nfive\nsix\n".to_string();
+        ps.format_lines();
+        ps.upper_mark = 2;
+
+        assert!(super::apply_search(&mut ps, "seven"));
+        assert_eq!(ps.message.as_deref(), Some("Pattern not found"));
+        assert_eq!(ps.upper_mark, 2);
+
+        ps.message = None;
+        assert!(super::apply_search(&mut ps, "six"));
+        assert_eq!(ps.message, None);
+        assert_eq!(ps.upper_mark, 5);
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn search_ignore_case() {
+        let mut ps = PagerState::with_dimensions(5, 80);
+        ps.lines = "one\ntwo\nthree\nfour\nfive\nSix\n".to_string();
+        ps.format_lines();
+
+        assert!(super::apply_search(&mut ps, "six"));
+        assert!(ps.search_idx.is_empty());
+
+        ps.message = None;
+        ps.search_ignore_case = true;
+        assert!(super::apply_search(&mut ps, "six"));
+        assert_eq!(ps.search_idx.iter().copied().collect::<Vec<_>>(), vec![5]);
+        assert_eq!(ps.message, None);
+    }
+
+    #[test]
+    fn reverse_lines() {
+        let mut ps = PagerState::with_dimensions(3, 80);
+        ps.lines = "1\n2\n3\n4\n5\n".to_string();
+        ps.format_lines();
+        ps.upper_mark = 1;
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::SetReverseLines(true),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.formatted_lines, vec!["5", "4", "3", "2", "1"]);
+        // The line "2" stays at the top
+        assert_eq!(ps.upper_mark, 3);
+        assert_eq!(ps.text_line(ps.source_line(ps.upper_mark)), 1);
+
+        // New lines show up at the top, while the screen keeps showing the same lines
+        ps.append_str("6\n");
+        assert_eq!(ps.formatted_lines, vec!["6", "5", "4", "3", "2", "1"]);
+        assert_eq!(ps.upper_mark, 4);
+        ps.upper_mark = 0;
+        ps.append_str("7\n");
+        assert_eq!(ps.formatted_lines[0], "7");
+        assert_eq!(ps.upper_mark, 0);
+    }
+
+    #[test]
+    #[allow(clippy::trivial_regex)]
+    fn reverse_lines_append_matches_full_format() {
+        let mut ps = PagerState::with_dimensions(5, 12);
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.reverse_lines = true;
+        #[cfg(feature = "search")]
+        {
+            ps.search_term = Some(regex::Regex::new("match").unwrap());
+        }
+        ps.lines = "1 match\n2 a line that wraps\n".to_string();
+        ps.format_lines();
+
+        // Whole lines, a line that is continued, and a wrapped line with a match
+        for text in ["3 unterminated", " continued\n4\n", "5 match that wraps\n6"] {
+            ps.append_str(text);
+            let (rows, line_map) = (ps.formatted_lines.clone(), ps.line_map.clone());
+            #[cfg(feature = "search")]
+            let search_idx = ps.search_idx.clone();
+            ps.format_lines();
+            assert_eq!(rows, ps.formatted_lines);
+            assert_eq!(line_map, ps.line_map);
+            #[cfg(feature = "search")]
+            assert_eq!(search_idx, ps.search_idx);
+        }
+        assert!(ps.formatted_lines[0].ends_with(". 6"));
+    }
+
+    #[test]
+    fn toggle_wrap() {
+        let mut ps = PagerState::with_dimensions(10, 20);
+        ps.lines = format!("short\n{}\nlast\n", "x".repeat(50));
+        ps.format_lines();
+        ps.upper_mark = 4;
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::UserInput(InputEvent::ToggleWrap),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["short", &format!("{}…", "x".repeat(19)), "last"]
+        );
+        // Still at the line that was at the top
+        assert_eq!(ps.upper_mark, 2);
+    }
+
+    #[test]
+    fn set_line_wrapping() {
+        let mut ps = PagerState::with_dimensions(10, 20);
+        ps.lines = format!("short\n{}\nlast\n", "x".repeat(50));
+        ps.format_lines();
+        let mut out = Vec::new();
+        let mut handle = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        handle(Event::SetLineWrapping(false), &mut ps);
+        assert_eq!(ps.formatted_lines.len(), 3);
+        // Setting it again doesn't toggle it back
+        handle(Event::SetLineWrapping(false), &mut ps);
+        assert_eq!(ps.formatted_lines.len(), 3);
+        handle(Event::SetLineWrapping(true), &mut ps);
+        assert_eq!(ps.formatted_lines.len(), 5);
+    }
+
+    #[test]
+    fn scroll_by() {
+        let mut ps = PagerState::with_dimensions(5, 80);
+        ps.lines = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n".to_string();
+        ps.format_lines();
+        let mut out = Vec::new();
+        let mut handle = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        handle(Event::ScrollBy(3), &mut ps);
+        assert_eq!(ps.upper_mark, 3);
+        handle(Event::ScrollBy(-1), &mut ps);
+        assert_eq!(ps.upper_mark, 2);
+        handle(Event::ScrollBy(-10), &mut ps);
+        assert_eq!(ps.upper_mark, 0);
+        handle(Event::ScrollBy(isize::MAX), &mut ps);
+        assert_eq!(ps.upper_mark, ps.max_upper_mark());
+
+        // The move starts from where the text is displayed, not from a mark past its end
+        ps.upper_mark = usize::MAX;
+        handle(Event::ScrollBy(-1), &mut ps);
+        assert_eq!(ps.upper_mark, ps.max_upper_mark() - 1);
+    }
+
+    #[test]
+    fn restore_prompt_and_scroll() {
+        let mut ps = PagerState::with_dimensions(5, 80);
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        ps.message = Some("message".to_owned());
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::UserInput(InputEvent::RestorePromptAndScroll(100)),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.message, None);
+        // The upper mark is kept within the text and the jump is recorded once
+        assert_eq!(ps.upper_mark, ps.max_upper_mark());
+        assert_eq!(ps.jump_history, vec![0]);
+    }
+
+    #[test]
+    fn on_reach_bottom() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut ps = PagerState::with_dimensions(5, 80);
+        ps.lines = "1\n2\n3\n4\n5\n6\n7\n8\n".to_string();
+        ps.format_lines();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        ps.on_reach_bottom = Some(Box::new(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+        let mut out = Vec::new();
+        let mut handle = |input, ps: &mut PagerState| {
+            handle_event(
+                Event::UserInput(input),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        handle(InputEvent::UpdateUpperMark(3), &mut ps);
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+        // The last line comes onto the screen, staying there doesn't count again
+        handle(InputEvent::UpdateUpperMark(4), &mut ps);
+        handle(InputEvent::UpdateUpperMark(5), &mut ps);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        // Reaching the bottom again after scrolling away
+        handle(InputEvent::UpdateUpperMark(0), &mut ps);
+        handle(InputEvent::UpdateUpperMark(4), &mut ps);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        // More text was added while staying at the bottom
+        ps.append_str("9\n");
+        handle(InputEvent::UpdateUpperMark(5), &mut ps);
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+        // Other ways of moving to the bottom count as well
+        handle(InputEvent::UpdateUpperMark(0), &mut ps);
+        handle(InputEvent::SetHalfPage(5, true), &mut ps);
+        assert_eq!(calls.load(Ordering::Relaxed), 4);
+        handle(InputEvent::UpdateUpperMark(0), &mut ps);
+        handle(InputEvent::ReachedEnd, &mut ps);
+        assert_eq!(calls.load(Ordering::Relaxed), 5);
+        #[cfg(feature = "search")]
+        {
+            assert!(super::apply_search(&mut ps, "9"));
+            handle(InputEvent::UpdateUpperMark(0), &mut ps);
+            handle(InputEvent::NextMatch, &mut ps);
+            assert_eq!(calls.load(Ordering::Relaxed), 6);
+        }
+    }
+
+    #[test]
+    fn change_callbacks() {
+        let mut ps = PagerState::with_dimensions(10, 20);
+        let changes = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let line_number_changes = changes.clone();
+        ps.on_line_numbers_changed = Some(Box::new(move |ln| {
+            line_number_changes.lock().push(format!("{:?}", ln));
+        }));
+        let wrap_changes = changes.clone();
+        ps.on_wrap_changed = Some(Box::new(move |wrapping| {
+            wrap_changes.lock().push(wrapping.to_string());
+        }));
+        let mut out = Vec::new();
+        let mut handle = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        handle(
+            Event::UserInput(InputEvent::UpdateLineNumber(LineNumbers::Enabled)),
+            &mut ps,
+        );
+        handle(Event::UserInput(InputEvent::ToggleWrap), &mut ps);
+        // Changes made by the application are not reported
+        handle(Event::SetLineWrapping(true), &mut ps);
+        assert_eq!(*changes.lock(), vec!["Enabled", "false"]);
+    }
+
+    #[test]
+    fn copy_line() {
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = "first\n\x1b[1msecond\x1b[0m\nthird\n".to_string();
+        ps.format_lines();
+        ps.upper_mark = 1;
+        ps.clipboard = Clipboard::Osc52;
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::UserInput(InputEvent::Copy),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // "second" in base64, without the escapes
+        assert_eq!(out, b"\x1b]52;c;c2Vjb25k\x07");
+        assert_eq!(ps.message, Some("Copied line 2".to_owned()));
+    }
+
+    #[test]
+    fn reached_end() {
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = "line\n".repeat(50);
+        ps.format_lines();
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::UserInput(InputEvent::ReachedEnd),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // The last line is at the bottom of the screen
+        assert_eq!(ps.upper_mark + ps.content_rows(), 50);
+        assert_eq!(ps.message, Some("Reached end of text".to_owned()));
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    #[allow(clippy::trivial_regex)]
+    fn resize_keeps_search_match_in_view() {
+        let mut ps = PagerState::with_dimensions(10, 80);
+        ps.lines = format!(
+            "{}{} needle\n{}",
+            "filler\n".repeat(20),
+            "a".repeat(30),
+            "filler\n".repeat(20)
+        );
+        ps.search_term = Some(regex::Regex::new("needle").unwrap());
+        ps.format_lines();
+        assert_eq!(ps.search_idx.iter().next(), Some(&20));
+        // The match is near the bottom of the screen
+        ps.upper_mark = 12;
+        let mut out = Vec::new();
+
+        // The line with the match wraps into four rows, pushing the match down by three rows
+        handle_event(
+            Event::UserInput(InputEvent::UpdateTermArea(10, 10)),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.search_idx.iter().next(), Some(&23));
+        assert_eq!(ps.search_mark, 0);
+        assert_eq!(ps.upper_mark, 15);
+    }
+
+    #[test]
+    fn resize_without_rewrap() {
+        let mut ps = PagerState::with_dimensions(10, 80);
+        ps.lines = format!("{}\nshort\n", "x".repeat(30));
+        ps.format_lines();
+        ps.rewrap_on_resize = false;
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::UserInput(InputEvent::UpdateTermArea(10, 10)),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // The rows are kept and cut off when drawing them
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["x".repeat(30), "short".to_string()]
+        );
+        // The marker shows where a row is cut off
+        assert_eq!(
+            ps.clip_row(&ps.formatted_lines[0]),
+            format!("{}…", "x".repeat(9))
+        );
+        assert_eq!(ps.clip_row(&ps.formatted_lines[1]), "short");
+        ps.truncation_marker = None;
+        assert_eq!(ps.clip_row(&ps.formatted_lines[0]), "x".repeat(10));
+    }
+
+    #[test]
+    fn help_is_formatted_when_needed() {
+        let mut ps = PagerState::with_dimensions(10, 80);
+        let mut out = Vec::new();
+        let mut handle = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Nothing is formatted while the list isn't shown
+        handle(Event::SetSplit(true), &mut ps);
+        assert!(ps.help_rows.is_empty());
+
+        handle(Event::UserInput(InputEvent::ShowHelp), &mut ps);
+        let rows = ps.help_rows.len();
+        assert!(ps.help_rows.iter().any(|row| row.contains("Tab")));
+
+        // The rows are wrapped again to the new width
+        handle(
+            Event::UserInput(InputEvent::UpdateTermArea(20, 10)),
+            &mut ps,
+        );
+        assert!(ps.help_rows.len() > rows);
+        assert!(ps.help_prompt.contains("Keybindings"));
+
+        handle(Event::SetSplit(false), &mut ps);
+        assert!(!ps.help_rows.iter().any(|row| row.contains("Tab")));
+    }
+
+    #[test]
+    fn toggle_line_numbers_keeps_position() {
+        let mut ps = PagerState::with_dimensions(10, 20);
+        // Each line fits in a row, but wraps into two rows once line numbers are shown
+        ps.lines = format!("{}\n", "x".repeat(18)).repeat(20);
+        ps.format_lines();
+        ps.upper_mark = 10;
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::UserInput(InputEvent::UpdateLineNumber(LineNumbers::Enabled)),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.upper_mark, 20);
+
+        handle_event(
+            Event::SetLineNumbers(LineNumbers::Disabled),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.upper_mark, 10);
+    }
+
+    #[test]
+    fn set_wrap_width_keeps_position() {
+        let mut ps = PagerState::with_dimensions(10, 40);
+        // Each line fits in a row, but wraps into two rows at a width of 10
+        ps.lines = format!("{}\n", "x".repeat(18)).repeat(20);
+        ps.format_lines();
+        ps.upper_mark = 10;
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::SetWrapWidth(Some(10)),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.upper_mark, 20);
+
+        handle_event(
+            Event::SetWrapWidth(None),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.upper_mark, 10);
+    }
+
+    #[test]
+    fn split_panes() {
+        use crate::state::Split;
+
+        let mut out = Vec::new();
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        ps.upper_mark = 2;
+        ps.split = Some(Split {
+            other_upper_mark: 10,
+            right_focused: false,
+            scroll_lock: false,
+        });
+
+        let mut handle = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        handle(Event::UserInput(InputEvent::SwitchPane), &mut ps);
+        assert_eq!(ps.upper_mark, 10);
+        assert_eq!(ps.split.unwrap().other_upper_mark, 2);
+        assert!(ps.split.unwrap().right_focused);
+
+        // Only the focused pane scrolls
+        handle(Event::UserInput(InputEvent::UpdateUpperMark(12)), &mut ps);
+        assert_eq!(ps.upper_mark, 12);
+        assert_eq!(ps.split.unwrap().other_upper_mark, 2);
+
+        // Both panes scroll by the same amount once they are locked
+        handle(Event::UserInput(InputEvent::ToggleScrollLock), &mut ps);
+        handle(Event::UserInput(InputEvent::UpdateUpperMark(9)), &mut ps);
+        assert_eq!(ps.upper_mark, 9);
+        assert_eq!(ps.split.unwrap().other_upper_mark, 0);
+    }
+
+    #[test]
+    fn batch() {
+        let mut ps = PagerState::new().unwrap();
+        let ev = Event::Batch(vec![
+            Event::SetData(TEST_STR.to_string()),
+            Event::SetPrompt("prompt".to_string()),
+        ]);
+        let mut out = Vec::new();
+
+        handle_event(
+            ev,
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.lines, TEST_STR);
+        assert_eq!(ps.prompt, "prompt");
+    }
+
+    #[test]
+    fn jump_history() {
+        let mut ps = PagerState::with_dimensions(10, 80);
+        ps.lines = "line\n".repeat(100);
+        ps.format_lines();
+        let mut out = Vec::new();
+        let mut handle = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Scrolling by up to a page isn't a jump
+        handle(Event::UserInput(InputEvent::UpdateUpperMark(9)), &mut ps);
+        assert!(ps.jump_history.is_empty());
+
+        handle(
+            Event::UserInput(InputEvent::UpdateUpperMark(usize::MAX - 1)),
+            &mut ps,
+        );
+        assert_eq!(ps.jump_history, vec![9]);
+
+        // Going back is a jump too, so doing it again returns to the end
+        handle(Event::UserInput(InputEvent::UpdateUpperMark(9)), &mut ps);
+        assert_eq!(ps.jump_history, vec![9, 91]);
+    }
+
+    #[test]
+    fn auto_scroll() {
+        let mut ps = PagerState::with_dimensions(10, 80);
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        ps.auto_scroll = Some(std::time::Duration::from_millis(10));
+        let mut out = Vec::new();
+        let mut step = |ps: &mut PagerState| {
+            auto_scroll_step(
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        step(&mut ps);
+        assert_eq!(ps.upper_mark, 1);
+        assert!(ps.auto_scroll.is_some());
+
+        // It stops at the bottom instead of going past it
+        while ps.auto_scroll.is_some() {
+            step(&mut ps);
+        }
+        assert_eq!(ps.upper_mark, ps.max_upper_mark());
+
+        // Resizing the terminal doesn't pause it, but anything the user does does
+        assert!(!Event::UserInput(InputEvent::UpdateTermArea(80, 10)).pauses_auto_scroll());
+        assert!(!Event::AppendData(TEST_STR.to_string()).pauses_auto_scroll());
+        assert!(Event::UserInput(InputEvent::UpdateUpperMark(0)).pauses_auto_scroll());
+    }
 }
@@ -1,24 +1,86 @@
 //! Provides the [`Event`] enum and all its related implementations
-use std::fmt::Debug;
+use crossterm::{event::KeyEvent, style::ContentStyle};
+use std::{fmt::Debug, time::Duration};
 
 use crate::{
     input::{InputClassifier, InputEvent},
-    ExitStrategy, LineNumbers,
+    Clipboard, ColorOutput, CtrlCBehavior, ExitStrategy, InputTrace, LineFormatter,
+    LineNumberFormatter, LineNumbers, MessageStyle, RenderTransform, ScrollAnchor, TextSource,
 };
 
 /// Different events that can be encountered while the pager is running
 pub enum Event {
     AppendData(String),
     SetData(String),
+    SetTextSource(Box<dyn TextSource + Send + Sync + 'static>),
     UserInput(InputEvent),
+    Batch(Vec<Self>),
     SetPrompt(String),
     SendMessage(String),
     SetLineNumbers(LineNumbers),
+    SetColorOutput(ColorOutput),
+    SetLineNumberStyle(ContentStyle),
+    SetTrailingWhitespaceStyle(Option<ContentStyle>),
+    SetLineNumberOffset(usize),
+    SetMaxLines(Option<usize>),
+    SetPinnedHeaderLines(usize),
+    SetPromptBottomPadding(usize),
+    SetPromptRows(usize),
+    SetClipboard(Clipboard),
+    SetCtrlCBehavior(CtrlCBehavior),
+    SetMessageStyle(MessageStyle),
+    SetAutoScroll(Option<Duration>),
+    SetWrapWidth(Option<usize>),
+    SetLineWrapping(bool),
+    SetWrapIndicator(Option<char>),
+    SetShowScrollbar(bool),
+    SetTruncationMarker(Option<char>),
+    SetSplit(bool),
+    SetInactivePaneStyle(Option<ContentStyle>),
+    SetReverseLines(bool),
+    SetEmptyPlaceholder(Option<String>),
+    SetLineNumberToggleKey(KeyEvent),
+    SetRedrawKey(KeyEvent),
+    SetWrapToggleKey(KeyEvent),
+    SetHelpKey(Option<KeyEvent>),
+    AddExitKey(KeyEvent),
+    RemoveExitKey(KeyEvent),
+    #[cfg(feature = "search")]
+    SetClearHighlightsKey(KeyEvent),
+    SetGutterMarker(usize, Option<(char, ContentStyle)>),
     SetExitStrategy(ExitStrategy),
     SetInputClassifier(Box<dyn InputClassifier + Send + Sync + 'static>),
+    SetPageOverlap(usize),
+    SetManagedInput(bool),
+    SetRewrapOnResize(bool),
+    SetResizeDebounce(Duration),
+    SetKeySequenceTimeout(Duration),
+    SetEnterDismissAlsoScrolls(bool),
+    SetScrollAnchorOnSetData(ScrollAnchor),
     AddExitCallback(Box<dyn FnMut() + Send + Sync + 'static>),
+    SetWrapProgressCallback(Box<dyn FnMut(usize, usize) + Send + Sync + 'static>),
+    SetOnReachBottom(Box<dyn FnMut() + Send + Sync + 'static>),
+    SetOnLineNumbersChanged(Box<dyn FnMut(LineNumbers) + Send + Sync + 'static>),
+    SetOnWrapChanged(Box<dyn FnMut(bool) + Send + Sync + 'static>),
+    SetLineFormatter(LineFormatter),
+    SetLineNumberFormatter(LineNumberFormatter),
+    SetRenderTransform(RenderTransform),
+    SetInputTrace(InputTrace),
     #[cfg(feature = "static_output")]
     SetRunNoOverflow(bool),
+    #[cfg(feature = "search")]
+    SetDimNonMatches(bool),
+    #[cfg(feature = "dynamic_output")]
+    SetInlineUntil(usize),
+    #[cfg(feature = "dynamic_output")]
+    SetExitWhenFits(bool),
+    #[cfg(feature = "dynamic_output")]
+    SetExitOnDisconnect(bool),
+    SetMessageLines(usize),
+    SetOverscroll(bool),
+    SetUpperMark(usize),
+    ScrollBy(isize),
+    SetMaxPrefixDigits(usize),
 }
 
 impl PartialEq for Event {
@@ -29,11 +91,76 @@ impl PartialEq for Event {
             | (Self::SetPrompt(d1), Self::SetPrompt(d2))
             | (Self::SendMessage(d1), Self::SendMessage(d2)) => d1 == d2,
             (Self::SetLineNumbers(d1), Self::SetLineNumbers(d2)) => d1 == d2,
+            (Self::SetColorOutput(d1), Self::SetColorOutput(d2)) => d1 == d2,
+            (Self::SetLineNumberStyle(d1), Self::SetLineNumberStyle(d2)) => d1 == d2,
+            (Self::SetTrailingWhitespaceStyle(d1), Self::SetTrailingWhitespaceStyle(d2)) => {
+                d1 == d2
+            }
+            (Self::SetLineNumberOffset(d1), Self::SetLineNumberOffset(d2)) => d1 == d2,
+            (Self::SetMaxLines(d1), Self::SetMaxLines(d2)) => d1 == d2,
+            (Self::SetPinnedHeaderLines(d1), Self::SetPinnedHeaderLines(d2)) => d1 == d2,
+            (Self::SetPromptBottomPadding(d1), Self::SetPromptBottomPadding(d2)) => d1 == d2,
+            (Self::SetPromptRows(d1), Self::SetPromptRows(d2)) => d1 == d2,
+            (Self::SetClipboard(d1), Self::SetClipboard(d2)) => d1 == d2,
+            (Self::SetCtrlCBehavior(d1), Self::SetCtrlCBehavior(d2)) => d1 == d2,
+            (Self::SetMessageStyle(d1), Self::SetMessageStyle(d2)) => d1 == d2,
+            (Self::SetAutoScroll(d1), Self::SetAutoScroll(d2)) => d1 == d2,
+            (Self::SetWrapWidth(d1), Self::SetWrapWidth(d2)) => d1 == d2,
+            (Self::SetLineWrapping(d1), Self::SetLineWrapping(d2)) => d1 == d2,
+            (Self::SetWrapIndicator(d1), Self::SetWrapIndicator(d2)) => d1 == d2,
+            (Self::SetShowScrollbar(d1), Self::SetShowScrollbar(d2)) => d1 == d2,
+            (Self::SetTruncationMarker(d1), Self::SetTruncationMarker(d2)) => d1 == d2,
+            (Self::SetSplit(d1), Self::SetSplit(d2)) => d1 == d2,
+            (Self::SetInactivePaneStyle(d1), Self::SetInactivePaneStyle(d2)) => d1 == d2,
+            (Self::SetReverseLines(d1), Self::SetReverseLines(d2)) => d1 == d2,
+            (Self::SetEmptyPlaceholder(d1), Self::SetEmptyPlaceholder(d2)) => d1 == d2,
+            (Self::SetLineNumberToggleKey(d1), Self::SetLineNumberToggleKey(d2))
+            | (Self::SetRedrawKey(d1), Self::SetRedrawKey(d2))
+            | (Self::SetWrapToggleKey(d1), Self::SetWrapToggleKey(d2))
+            | (Self::AddExitKey(d1), Self::AddExitKey(d2))
+            | (Self::RemoveExitKey(d1), Self::RemoveExitKey(d2)) => d1 == d2,
+            (Self::SetHelpKey(d1), Self::SetHelpKey(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetClearHighlightsKey(d1), Self::SetClearHighlightsKey(d2)) => d1 == d2,
             (Self::SetExitStrategy(d1), Self::SetExitStrategy(d2)) => d1 == d2,
+            (Self::SetManagedInput(d1), Self::SetManagedInput(d2)) => d1 == d2,
+            (Self::SetRewrapOnResize(d1), Self::SetRewrapOnResize(d2)) => d1 == d2,
+            (Self::SetResizeDebounce(d1), Self::SetResizeDebounce(d2)) => d1 == d2,
+            (Self::SetKeySequenceTimeout(d1), Self::SetKeySequenceTimeout(d2)) => d1 == d2,
+            (Self::SetEnterDismissAlsoScrolls(d1), Self::SetEnterDismissAlsoScrolls(d2)) => {
+                d1 == d2
+            }
+            (Self::SetScrollAnchorOnSetData(d1), Self::SetScrollAnchorOnSetData(d2)) => d1 == d2,
+            (Self::SetGutterMarker(i1, m1), Self::SetGutterMarker(i2, m2)) => i1 == i2 && m1 == m2,
             #[cfg(feature = "static_output")]
             (Self::SetRunNoOverflow(d1), Self::SetRunNoOverflow(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetDimNonMatches(d1), Self::SetDimNonMatches(d2)) => d1 == d2,
+            #[cfg(feature = "dynamic_output")]
+            (Self::SetInlineUntil(d1), Self::SetInlineUntil(d2)) => d1 == d2,
+            #[cfg(feature = "dynamic_output")]
+            (Self::SetExitWhenFits(d1), Self::SetExitWhenFits(d2)) => d1 == d2,
+            #[cfg(feature = "dynamic_output")]
+            (Self::SetExitOnDisconnect(d1), Self::SetExitOnDisconnect(d2)) => d1 == d2,
+            (Self::SetMessageLines(d1), Self::SetMessageLines(d2)) => d1 == d2,
+            (Self::SetOverscroll(d1), Self::SetOverscroll(d2)) => d1 == d2,
+            (Self::SetMaxPrefixDigits(d1), Self::SetMaxPrefixDigits(d2)) => d1 == d2,
+            (Self::SetPageOverlap(d1), Self::SetPageOverlap(d2)) => d1 == d2,
+            (Self::SetUpperMark(d1), Self::SetUpperMark(d2)) => d1 == d2,
+            (Self::ScrollBy(d1), Self::ScrollBy(d2)) => d1 == d2,
+            (Self::UserInput(d1), Self::UserInput(d2)) => d1 == d2,
+            (Self::Batch(d1), Self::Batch(d2)) => d1 == d2,
             (Self::SetInputClassifier(_), Self::SetInputClassifier(_))
-            | (Self::AddExitCallback(_), Self::AddExitCallback(_)) => true,
+            | (Self::SetTextSource(_), Self::SetTextSource(_))
+            | (Self::AddExitCallback(_), Self::AddExitCallback(_))
+            | (Self::SetWrapProgressCallback(_), Self::SetWrapProgressCallback(_))
+            | (Self::SetOnReachBottom(_), Self::SetOnReachBottom(_))
+            | (Self::SetOnLineNumbersChanged(_), Self::SetOnLineNumbersChanged(_))
+            | (Self::SetOnWrapChanged(_), Self::SetOnWrapChanged(_))
+            | (Self::SetLineFormatter(_), Self::SetLineFormatter(_))
+            | (Self::SetLineNumberFormatter(_), Self::SetLineNumberFormatter(_))
+            | (Self::SetRenderTransform(_), Self::SetRenderTransform(_))
+            | (Self::SetInputTrace(_), Self::SetInputTrace(_)) => true,
             _ => false,
         }
     }
@@ -43,16 +170,86 @@ impl Debug for Event {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::SetData(text) => write!(f, "SetData({:?})", text),
+            Self::SetTextSource(_) => write!(f, "SetTextSource"),
             Self::AppendData(text) => write!(f, "AppendData({:?})", text),
             Self::SetPrompt(text) => write!(f, "SetPrompt({:?})", text),
             Self::SendMessage(text) => write!(f, "SendMessage({:?})", text),
             Self::SetLineNumbers(ln) => write!(f, "SetLineNumbers({:?})", ln),
+            Self::SetColorOutput(co) => write!(f, "SetColorOutput({:?})", co),
+            Self::SetLineNumberStyle(style) => write!(f, "SetLineNumberStyle({:?})", style),
+            Self::SetTrailingWhitespaceStyle(style) => {
+                write!(f, "SetTrailingWhitespaceStyle({:?})", style)
+            }
+            Self::SetLineNumberOffset(offset) => write!(f, "SetLineNumberOffset({:?})", offset),
+            Self::SetMaxLines(max) => write!(f, "SetMaxLines({:?})", max),
+            Self::SetPinnedHeaderLines(n) => write!(f, "SetPinnedHeaderLines({:?})", n),
+            Self::SetPromptBottomPadding(n) => write!(f, "SetPromptBottomPadding({:?})", n),
+            Self::SetPromptRows(n) => write!(f, "SetPromptRows({:?})", n),
+            Self::SetClipboard(clipboard) => write!(f, "SetClipboard({:?})", clipboard),
+            Self::SetCtrlCBehavior(behavior) => write!(f, "SetCtrlCBehavior({:?})", behavior),
+            Self::SetMessageStyle(style) => write!(f, "SetMessageStyle({:?})", style),
+            Self::SetAutoScroll(interval) => write!(f, "SetAutoScroll({:?})", interval),
+            Self::SetWrapWidth(width) => write!(f, "SetWrapWidth({:?})", width),
+            Self::SetLineWrapping(val) => write!(f, "SetLineWrapping({:?})", val),
+            Self::SetWrapIndicator(ch) => write!(f, "SetWrapIndicator({:?})", ch),
+            Self::SetShowScrollbar(show) => write!(f, "SetShowScrollbar({:?})", show),
+            Self::SetTruncationMarker(marker) => write!(f, "SetTruncationMarker({:?})", marker),
+            Self::SetSplit(val) => write!(f, "SetSplit({:?})", val),
+            Self::SetInactivePaneStyle(style) => write!(f, "SetInactivePaneStyle({:?})", style),
+            Self::SetReverseLines(val) => write!(f, "SetReverseLines({:?})", val),
+            Self::SetEmptyPlaceholder(text) => write!(f, "SetEmptyPlaceholder({:?})", text),
+            Self::SetLineNumberToggleKey(key) => write!(f, "SetLineNumberToggleKey({:?})", key),
+            Self::SetRedrawKey(key) => write!(f, "SetRedrawKey({:?})", key),
+            Self::SetWrapToggleKey(key) => write!(f, "SetWrapToggleKey({:?})", key),
+            Self::SetHelpKey(key) => write!(f, "SetHelpKey({:?})", key),
+            Self::AddExitKey(key) => write!(f, "AddExitKey({:?})", key),
+            Self::RemoveExitKey(key) => write!(f, "RemoveExitKey({:?})", key),
+            #[cfg(feature = "search")]
+            Self::SetClearHighlightsKey(key) => write!(f, "SetClearHighlightsKey({:?})", key),
             Self::SetExitStrategy(es) => write!(f, "SetExitStrategy({:?})", es),
+            Self::SetGutterMarker(idx, marker) => {
+                write!(f, "SetGutterMarker({:?}, {:?})", idx, marker)
+            }
             Self::SetInputClassifier(_) => write!(f, "SetInputClassifier"),
+            Self::SetPageOverlap(rows) => write!(f, "SetPageOverlap({:?})", rows),
+            Self::SetManagedInput(val) => write!(f, "SetManagedInput({:?})", val),
+            Self::SetRewrapOnResize(val) => write!(f, "SetRewrapOnResize({:?})", val),
+            Self::SetResizeDebounce(interval) => write!(f, "SetResizeDebounce({:?})", interval),
+            Self::SetKeySequenceTimeout(timeout) => {
+                write!(f, "SetKeySequenceTimeout({:?})", timeout)
+            }
+            Self::SetEnterDismissAlsoScrolls(val) => {
+                write!(f, "SetEnterDismissAlsoScrolls({:?})", val)
+            }
+            Self::SetScrollAnchorOnSetData(anchor) => {
+                write!(f, "SetScrollAnchorOnSetData({:?})", anchor)
+            }
             Self::AddExitCallback(_) => write!(f, "AddExitCallback"),
+            Self::SetWrapProgressCallback(_) => write!(f, "SetWrapProgressCallback"),
+            Self::SetOnReachBottom(_) => write!(f, "SetOnReachBottom"),
+            Self::SetOnLineNumbersChanged(_) => write!(f, "SetOnLineNumbersChanged"),
+            Self::SetOnWrapChanged(_) => write!(f, "SetOnWrapChanged"),
+            Self::SetLineFormatter(_) => write!(f, "SetLineFormatter"),
+            Self::SetLineNumberFormatter(_) => write!(f, "SetLineNumberFormatter"),
+            Self::SetRenderTransform(_) => write!(f, "SetRenderTransform"),
+            Self::SetInputTrace(_) => write!(f, "SetInputTrace"),
             #[cfg(feature = "static_output")]
             Self::SetRunNoOverflow(val) => write!(f, "SetRunNoOverflow({:?})", val),
+            #[cfg(feature = "search")]
+            Self::SetDimNonMatches(val) => write!(f, "SetDimNonMatches({:?})", val),
+            #[cfg(feature = "dynamic_output")]
+            Self::SetInlineUntil(val) => write!(f, "SetInlineUntil({:?})", val),
+            #[cfg(feature = "dynamic_output")]
+            Self::SetExitWhenFits(val) => write!(f, "SetExitWhenFits({:?})", val),
+            #[cfg(feature = "dynamic_output")]
+            Self::SetExitOnDisconnect(val) => write!(f, "SetExitOnDisconnect({:?})", val),
+            Self::SetMessageLines(val) => write!(f, "SetMessageLines({:?})", val),
+            Self::SetOverscroll(val) => write!(f, "SetOverscroll({:?})", val),
+            Self::SetMaxPrefixDigits(n) => write!(f, "SetMaxPrefixDigits({:?})", n),
+            Self::SetUpperMark(val) => write!(f, "SetUpperMark({:?})", val),
+            Self::ScrollBy(delta) => write!(f, "ScrollBy({:?})", delta),
             Self::UserInput(input) => write!(f, "UserInput({:?})", input),
+            Self::Batch(events) => write!(f, "Batch({:?})", events),
         }
     }
 }
@@ -68,11 +265,87 @@ impl Event {
         matches!(self, Self::UserInput(InputEvent::UpdateUpperMark(_)))
     }
 
+    /// Whether the event changes nothing on the screen but the prompt
+    ///
+    /// The text still has to be redrawn if the prompt now takes up a different number of rows, or
+    /// if a message goes into the message log.
+    pub(crate) const fn only_changes_prompt(&self) -> bool {
+        matches!(
+            self,
+            Self::SetPrompt(_) | Self::SendMessage(_) | Self::UserInput(InputEvent::RestorePrompt)
+        )
+    }
+
+    /// Whether the event is something that the user did in the pager, which pauses auto scroll
+    ///
+    /// Resizing the terminal is not something that the user does in the pager.
+    pub(crate) const fn pauses_auto_scroll(&self) -> bool {
+        matches!(self, Self::UserInput(inp) if !matches!(inp, InputEvent::UpdateTermArea(..)))
+    }
+
+    /// Whether the event changes the list of keybindings, which is then formatted again if it is
+    /// shown
+    pub(crate) const fn changes_help(&self) -> bool {
+        #[cfg(feature = "search")]
+        if matches!(self, Self::SetClearHighlightsKey(_)) {
+            return true;
+        }
+        matches!(
+            self,
+            Self::UserInput(InputEvent::ShowHelp | InputEvent::UpdateTermArea(..))
+                | Self::SetColorOutput(_)
+                | Self::SetClipboard(_)
+                | Self::SetSplit(_)
+                | Self::SetLineNumberToggleKey(_)
+                | Self::SetRedrawKey(_)
+                | Self::SetWrapToggleKey(_)
+                | Self::SetHelpKey(_)
+                | Self::AddExitKey(_)
+                | Self::RemoveExitKey(_)
+                | Self::SetInputClassifier(_)
+        )
+    }
+
     #[cfg(feature = "dynamic_output")]
     pub(crate) const fn required_immidiate_screen_update(&self) -> bool {
+        #[cfg(feature = "search")]
+        if matches!(self, Self::SetDimNonMatches(_)) {
+            return true;
+        }
         matches!(
             self,
-            Self::SetData(_) | Self::SetPrompt(_) | Self::SendMessage(_) | Self::UserInput(_)
+            Self::SetData(_)
+                | Self::SetTextSource(_)
+                | Self::SetPrompt(_)
+                | Self::SendMessage(_)
+                | Self::SetMessageLines(_)
+                | Self::SetOverscroll(_)
+                | Self::SetUpperMark(_)
+                | Self::ScrollBy(_)
+                | Self::SetWrapWidth(_)
+                | Self::SetLineWrapping(_)
+                | Self::SetWrapIndicator(_)
+                | Self::SetShowScrollbar(_)
+                | Self::SetTruncationMarker(_)
+                | Self::SetLineFormatter(_)
+                | Self::SetLineNumberFormatter(_)
+                | Self::SetRenderTransform(_)
+                | Self::SetSplit(_)
+                | Self::SetInactivePaneStyle(_)
+                | Self::SetReverseLines(_)
+                | Self::SetEmptyPlaceholder(_)
+                | Self::SetLineNumberStyle(_)
+                | Self::SetTrailingWhitespaceStyle(_)
+                | Self::SetLineNumberOffset(_)
+                | Self::SetMaxLines(_)
+                | Self::SetPinnedHeaderLines(_)
+                | Self::SetPromptBottomPadding(_)
+                | Self::SetPromptRows(_)
+                | Self::SetMessageStyle(_)
+                | Self::SetColorOutput(_)
+                | Self::SetGutterMarker(..)
+                | Self::UserInput(_)
+                | Self::Batch(_)
         )
     }
 }
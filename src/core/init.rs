@@ -8,26 +8,38 @@
 //! * The [`start_reactor`] function displays the displays the output and also polls
 //! the [`Receiver`] held inside the [`Pager`] for events. Whenever a event is
 //! detected, it reacts to it accordingly.
-use super::{display::draw_full, ev_handler::handle_event, events::Event, term, RunMode};
-use crate::{error::MinusError, input::InputEvent, Pager, PagerState};
+use super::{
+    display::{draw_full, draw_prompt_only},
+    ev_handler::{auto_scroll_step, handle_event},
+    events::Event,
+    term, RunMode,
+};
+use crate::{error::MinusError, input, pager::StateSlot, ExitReason, MessageStyle, PagerState};
 
-use crossbeam_channel::{Receiver, Sender, TrySendError};
+#[cfg(feature = "static_output")]
+use super::display::dump_text;
+#[cfg(any(feature = "static_output", feature = "dynamic_output"))]
+use super::display::write_lines;
+use crossbeam_channel::{Receiver, RecvError, SendTimeoutError, Sender};
 use crossterm::event;
+#[cfg(feature = "static_output")]
+use crossterm::tty::IsTty;
 #[cfg(feature = "dynamic_output")]
 use crossterm::{
     execute,
     terminal::{Clear, ClearType},
 };
+#[cfg(feature = "dynamic_output")]
+use std::convert::TryInto;
 use std::{
     io::{stdout, Stdout},
     panic,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Once,
     },
+    time::Instant,
 };
-#[cfg(feature = "static_output")]
-use {super::display::write_lines, crossterm::tty::IsTty};
 
 #[cfg(feature = "search")]
 use parking_lot::Condvar;
@@ -40,8 +52,10 @@ pub static RUNMODE: parking_lot::Mutex<RunMode> = parking_lot::const_mutex(RunMo
 /// This is called by both [`dynamic_paging`](crate::dynamic_paging) and
 /// [`page_all`](crate::page_all) functions.
 ///
-/// It first receives all events present inside the [`Pager`]'s receiver
-/// and creates the initial state that to be stored inside the [`PagerState`]
+/// It takes the receiver and the shared state of a [`Pager`]. It first receives all events present
+/// inside the receiver and creates the initial state that to be stored inside the [`PagerState`]
+///
+/// Once the pager stops, it returns whether the user quit it or it finished on its own.
 ///
 /// Then it checks if the minus is running in static mode and does some checks:-
 /// * If standard output is not a terminal screen, that is if it is a file or block
@@ -58,6 +72,8 @@ pub static RUNMODE: parking_lot::Mutex<RunMode> = parking_lot::const_mutex(RunMo
 // using your library... your only weapon
 // So we just don't take any more proposals about this. It is really frustating to
 // to throughly test each implementation and fix out all rough edges around it
+// The closest thing is the opt-in Pager::set_exit_when_fits which only does this once the
+// application has dropped all of its pagers, i.e when no more data can arrive
 /// Next it initializes the runtime and calls [`start_reactor`] and a [`event reader`]` which is
 /// selected based on the enabled feature set:-
 ///
@@ -67,8 +83,14 @@ pub static RUNMODE: parking_lot::Mutex<RunMode> = parking_lot::const_mutex(RunMo
 /// fail.
 ///
 /// [`event reader`]: event_reader
+/// Installs the panic hook that restores the terminal
+static PANIC_HOOK: Once = Once::new();
+
 #[allow(clippy::module_name_repetitions)]
-pub fn init_core(mut pager: Pager) -> std::result::Result<(), MinusError> {
+pub fn init_core(
+    rx: &Receiver<Event>,
+    state_slot: &StateSlot,
+) -> std::result::Result<ExitReason, MinusError> {
     #[allow(unused_mut)]
     let mut out = stdout();
     // Is the event reader running
@@ -76,29 +98,41 @@ pub fn init_core(mut pager: Pager) -> std::result::Result<(), MinusError> {
     let input_thread_running = Arc::new((Mutex::new(true), Condvar::new()));
 
     #[allow(unused_mut)]
-    let mut ps = crate::state::PagerState::generate_initial_state(&mut pager.rx, &mut out)?;
+    let mut ps = crate::state::PagerState::generate_initial_state(rx, &mut out)?;
+    // Don't style the output if the environment doesn't want it
+    ps.detect_colors();
 
     // Static mode checks
     #[cfg(feature = "static_output")]
     if *RUNMODE.lock() == RunMode::Static {
         // If stdout is not a tty, write everyhting and quit
         if !out.is_tty() {
-            write_lines(&mut out, &mut ps)?;
-            return Ok(());
+            dump_text(&mut out, &ps)?;
+            return Ok(ExitReason::Finished);
         }
         // If number of lines of text is less than available wors, write everything and quit
         // unless run_no_overflow is set to true
         if ps.num_lines() <= ps.rows && ps.run_no_overflow {
             write_lines(&mut out, &mut ps)?;
             ps.exit();
-            return Ok(());
+            return Ok(ExitReason::Finished);
+        }
+    }
+
+    // Print the text inline if the application asked to do so, until it overflows
+    #[cfg(feature = "dynamic_output")]
+    {
+        let run_mode = *RUNMODE.lock();
+        if let (RunMode::Dynamic, Some(limit)) = (run_mode, ps.inline_until) {
+            print_inline_until_overflow(&mut out, &mut ps, rx, limit)?;
         }
     }
 
     // Setup terminal, adjust line wraps and get rows
     term::setup(&out)?;
 
-    {
+    // The pager can be started again after it quits, but the hook must only be wrapped once
+    PANIC_HOOK.call_once(|| {
         let panic_hook = panic::take_hook();
         panic::set_hook(Box::new(move |pinfo| {
             // While silently ignoring error is considered a bad practice, we are forced to do it here
@@ -110,37 +144,54 @@ pub fn init_core(mut pager: Pager) -> std::result::Result<(), MinusError> {
             ));
             panic_hook(pinfo);
         }));
-    }
+    });
 
+    let managed_input = ps.managed_input;
+    // Share the state with the pager so that its getters can read it
     let ps_mutex = Arc::new(Mutex::new(ps));
+    *state_slot.lock() = Some(ps_mutex.clone());
 
-    let evtx = pager.tx.clone();
-    let rx = pager.rx.clone();
+    // User input is sent over a separate channel, again to not keep the pager's channel connected.
+    // It holds as many events as the pager's channel, so that a pager with a bounded channel
+    // doesn't buffer an unbounded amount of input either
+    let (evtx, input_rx) = rx
+        .capacity()
+        .map_or_else(crossbeam_channel::unbounded, crossbeam_channel::bounded);
     let out = stdout();
 
     let p1 = ps_mutex.clone();
+    let p2 = ps_mutex.clone();
 
     #[cfg(feature = "search")]
     let input_thread_running2 = input_thread_running.clone();
 
-    let (r1, r2) =
-        crossbeam_utils::thread::scope(|s| -> (Result<(), MinusError>, Result<(), MinusError>) {
+    let (r1, r2, quit) = crossbeam_utils::thread::scope(
+        |s| -> (Result<(), MinusError>, Result<(), MinusError>, bool) {
             // Has the user quitted
             let is_exitted = Arc::new(AtomicBool::new(false));
             let is_exitted2 = is_exitted.clone();
+            let is_exitted3 = is_exitted.clone();
 
-            let t1 = s.spawn(move |_| {
-                event_reader(
-                    &evtx,
-                    &p1,
-                    #[cfg(feature = "search")]
-                    &input_thread_running2,
-                    &is_exitted2,
-                )
-            });
+            // If the application feeds the input itself, the sender is held here instead, since
+            // the reactor stops once the input channel is disconnected
+            let (t1, input_tx) = if managed_input {
+                let t1 = s.spawn(move |_| {
+                    event_reader(
+                        &evtx,
+                        &p1,
+                        #[cfg(feature = "search")]
+                        &input_thread_running2,
+                        &is_exitted2,
+                    )
+                });
+                (Some(t1), None)
+            } else {
+                (None, Some(evtx))
+            };
             let t2 = s.spawn(move |_| {
                 start_reactor(
-                    &rx,
+                    rx,
+                    &input_rx,
                     &ps_mutex,
                     &out,
                     #[cfg(feature = "search")]
@@ -148,13 +199,68 @@ pub fn init_core(mut pager: Pager) -> std::result::Result<(), MinusError> {
                     &is_exitted,
                 )
             });
-            let (r1, r2) = (t1.join().unwrap(), t2.join().unwrap());
-            (r1, r2)
-        })
-        .unwrap();
+            let r2 = t2.join().unwrap();
+            drop(input_tx);
+            let r1 = t1.map_or(Ok(()), |t1| t1.join().unwrap());
+            (r1, r2, is_exitted3.load(Ordering::SeqCst))
+        },
+    )
+    .unwrap();
     r1?;
     r2?;
-    Ok(())
+    Ok(if p2.lock().interrupted {
+        ExitReason::Interrupted
+    } else if quit {
+        ExitReason::Quit
+    } else {
+        ExitReason::Finished
+    })
+}
+
+/// Print the text on the main screen until it has more than `limit` lines
+///
+/// Events are handled as they arrive and each newly terminated line is printed as is.
+#[cfg(feature = "dynamic_output")]
+fn print_inline_until_overflow(
+    out: &mut Stdout,
+    ps: &mut PagerState,
+    rx: &Receiver<Event>,
+    limit: usize,
+) -> Result<(), MinusError> {
+    use std::io::Write;
+
+    // These are never touched as user input isn't read while printing inline
+    let is_exitted = Arc::new(AtomicBool::new(false));
+    #[cfg(feature = "search")]
+    let input_thread_running = Arc::new((Mutex::new(true), Condvar::new()));
+
+    let mut printed = 0;
+    loop {
+        // Only print lines that are terminated as the unterminated ones can still change
+        let terminated = ps.num_lines().saturating_sub(ps.unterminated);
+        for row in printed..terminated {
+            writeln!(out, "{}", ps.row(row))?;
+        }
+        printed = printed.max(terminated);
+        out.flush()?;
+
+        if ps.num_lines() > limit {
+            return Ok(());
+        }
+        if let Ok(ev) = rx.recv() {
+            handle_event(
+                ev,
+                out,
+                ps,
+                &is_exitted,
+                #[cfg(feature = "search")]
+                &input_thread_running,
+            )?;
+        } else {
+            // The channel can't take any more text, so start paging what we have
+            return Ok(());
+        }
+    }
 }
 
 /// Continously displays the output and reacts to events
@@ -173,6 +279,7 @@ pub fn init_core(mut pager: Pager) -> std::result::Result<(), MinusError> {
 #[allow(clippy::too_many_lines)]
 fn start_reactor(
     rx: &Receiver<Event>,
+    input_rx: &Receiver<Event>,
     ps: &Arc<Mutex<PagerState>>,
     out: &Stdout,
     #[cfg(feature = "search")] input_thread_running: &Arc<(Mutex<bool>, Condvar)>,
@@ -184,29 +291,109 @@ fn start_reactor(
     draw_full(&mut out_lock, &mut p)?;
     drop(p);
 
+    // Whether the application still holds a pager that can send events
+    let mut producer_connected = true;
+    // Used in place of rx once it is disconnected, as a disconnected channel is always ready
+    let disconnected = crossbeam_channel::never();
+    // When the next auto scroll step is due
+    let mut auto_scroll_at = None;
+    // The timed message on the prompt and when it should be taken down
+    let mut message_until = None;
+    // The last resize of the terminal that is held back and when it is applied
+    let mut pending_resize = None;
+
     let run_mode = *RUNMODE.lock();
     #[allow(clippy::match_same_arms)]
     match run_mode {
         #[cfg(feature = "dynamic_output")]
         RunMode::Dynamic => loop {
-            use std::{convert::TryInto, io::Write};
+            use std::io::Write;
 
             if is_exitted.load(Ordering::SeqCst) {
-                let mut runmode = RUNMODE.lock();
-                *runmode = RunMode::Uninitialized;
-                drop(runmode);
                 break;
             }
 
-            let event = rx.recv();
+            let data_rx = if producer_connected {
+                rx
+            } else {
+                &disconnected
+            };
+            let timer = auto_scroll_timer(&ps.lock(), &mut auto_scroll_at);
+            let message_timer = message_timer(&ps.lock(), &mut message_until);
+            let resize_timer = resize_timer(pending_resize);
+            let event = crossbeam_channel::select! {
+                recv(data_rx) -> ev => {
+                    if ev.is_err() {
+                        // All pagers have been dropped, so no more data will arrive
+                        producer_connected = false;
+                        let mut p = ps.lock();
+                        if p.exit_when_fits && p.num_lines() < p.rows {
+                            exit_inline(&mut out_lock, &mut p, is_exitted)?;
+                        } else if p.exits_on_disconnect() {
+                            exit_on_disconnect(
+                                &mut out_lock,
+                                &mut p,
+                                is_exitted,
+                                #[cfg(feature = "search")]
+                                input_thread_running,
+                            )?;
+                        }
+                        drop(p);
+                        continue;
+                    }
+                    ev
+                },
+                recv(input_rx) -> ev => {
+                    if debounce_resize(&ps.lock(), &ev, &mut pending_resize) {
+                        continue;
+                    }
+                    ev
+                },
+                recv(timer) -> _ => {
+                    auto_scroll_at = None;
+                    auto_scroll_step(
+                        &mut out_lock,
+                        &mut ps.lock(),
+                        is_exitted,
+                        #[cfg(feature = "search")]
+                        input_thread_running,
+                    )?;
+                    continue;
+                },
+                recv(message_timer) -> _ => {
+                    message_until = None;
+                    restore_prompt(&mut out_lock, &mut ps.lock())?;
+                    continue;
+                },
+                recv(resize_timer) -> _ => {
+                    // The terminal has kept its size, so the last resize is applied now
+                    Ok(Event::UserInput(pending_resize.take().unwrap().0))
+                },
+            };
+            // The event reader has stopped
+            if event.is_err() {
+                break;
+            }
+            if matches!(&event, Ok(ev) if ev.pauses_auto_scroll()) {
+                ps.lock().auto_scroll_paused = true;
+            }
 
             let mut p = ps.lock();
 
-            let rows: u16 = p.rows.try_into().unwrap();
             let num_lines = p.num_lines();
 
             #[allow(clippy::unnested_or_patterns)]
             match event {
+                Ok(ev) if ev.only_changes_prompt() => {
+                    handle_prompt_event(
+                        ev,
+                        &mut out_lock,
+                        &mut p,
+                        is_exitted,
+                        #[cfg(feature = "search")]
+                        input_thread_running,
+                    )?;
+                }
                 Ok(ev) if ev.required_immidiate_screen_update() => {
                     let is_exit_event = ev.is_exit_event();
                     let is_movement = ev.is_movement();
@@ -222,21 +409,34 @@ fn start_reactor(
                         draw_full(&mut out_lock, &mut p)?;
                     }
                 }
-                Ok(Event::SetPrompt(ref text) | Event::SendMessage(ref text)) => {
-                    if let Ok(Event::SetPrompt(_)) = event {
-                        p.prompt = text.to_string();
-                    } else {
-                        p.message = Some(text.to_string());
-                    }
-                    p.format_prompt();
-                    term::move_cursor(&mut out_lock, 0, rows, false)?;
-                    super::display::write_prompt(&mut out_lock, &p.displayed_prompt, rows)?;
+                Ok(Event::AppendData(text))
+                    if p.reverse_lines
+                        || p.text_source.is_some()
+                        || p.show_help
+                        || p.widens_line_numbers(&text)
+                        || p.render_transform.is_some()
+                        || (p.num_lines() == 0 && p.empty_placeholder.is_some()) =>
+                {
+                    // The new lines are displayed at the top, so all of the rows move. Or the lines
+                    // of the text source are copied into the pager first. Or the
+                    // placeholder for the empty text is on the screen and has to be cleared. Or
+                    // the text is hidden by the list of keybindings, which must stay on top. Or
+                    // the line numbers get wider, so all rows are shifted. Or the rows have to
+                    // go through the render transform
+                    p.append_str(&text);
+                    draw_full(&mut out_lock, &mut p)?;
                 }
                 Ok(Event::AppendData(text)) => {
+                    // Dropping the oldest lines only has to be drawn if it changes the screen
+                    let screen = p.overflows_max_lines(&text).then(|| visible_screen(&p));
+
                     // Make the string that nneds to be appended
-                    let (fmt_text, num_unterminated) = p.make_append_str(&text);
+                    let append_props = p.make_append_str(&text);
 
-                    if p.num_lines() < p.rows {
+                    // If the screen isn't filled yet, write as many of the new rows as fit on it.
+                    // If number of rows available for the text is 23 with 20 rows filled and
+                    // another 5 rows are given, 3 of them are written
+                    if let Some(num_appendable) = append_props.num_appendable {
                         // Move the cursor to the very next line after the last displayed line
                         term::move_cursor(
                             &mut out_lock,
@@ -244,31 +444,27 @@ fn start_reactor(
                             num_lines.saturating_sub(p.unterminated).try_into().unwrap(),
                             false,
                         )?;
-                        // available_rows -> Rows that are still unfilled
-                        //      rows - number of lines displayed -1 (for prompt)
-                        // For example if 20 rows are in total in a terminal
-                        // and 10 rows are already occupied, then this will be equal to 9
-                        let available_rows = p.rows.saturating_sub(
-                            p.num_lines()
-                                .saturating_sub(p.unterminated)
-                                .saturating_add(1),
-                        );
-                        // Minimum amount of text that an be appended
-                        // If available_rows is less, than this will be available rows else it will be
-                        // the length of the formatted text
-                        //
-                        // If number of rows in terminal is 23 with 20 rows filled and another 5 lines are given
-                        // This woll be equal to 3 as available rows will be 3
-                        // If in the above example only 2 lines are needed to be added, this will be equal to 2
-                        let num_appendable = fmt_text.len().min(available_rows);
                         if num_appendable >= 1 {
                             execute!(out_lock, Clear(ClearType::CurrentLine))?;
                         }
-                        write!(out_lock, "{}", fmt_text[0..num_appendable].join("\n\r"))?;
+                        write!(
+                            out_lock,
+                            "{}",
+                            append_props.fmt_lines[0..num_appendable].join("\n\r")
+                        )?;
                         out_lock.flush()?;
                     }
                     // Append the formatted string to PagerState::formatted_lines vec
-                    p.append_str_on_unterminated(fmt_text, num_unterminated);
+                    p.append_str_on_unterminated(
+                        append_props.fmt_lines,
+                        append_props.num_unterminated,
+                    );
+                    if let Some(screen) = screen {
+                        p.format_lines_within_max();
+                        if visible_screen(&p) != screen {
+                            draw_full(&mut out_lock, &mut p)?;
+                        }
+                    }
                 }
                 Ok(ev) => {
                     handle_event(
@@ -282,6 +478,17 @@ fn start_reactor(
                 }
                 Err(_) => {}
             }
+            // The user has scrolled to the end after the application dropped its pager
+            if !producer_connected && !is_exitted.load(Ordering::SeqCst) && p.exits_on_disconnect()
+            {
+                exit_on_disconnect(
+                    &mut out_lock,
+                    &mut p,
+                    is_exitted,
+                    #[cfg(feature = "search")]
+                    input_thread_running,
+                )?;
+            }
         },
         #[cfg(feature = "static_output")]
         RunMode::Static => loop {
@@ -291,28 +498,99 @@ fn start_reactor(
                 // This is not needed in dynamic paging because this is already handled by handle_event
                 let p = ps.lock();
                 term::cleanup(&mut out_lock, &p.exit_strategy, true)?;
-
-                let mut runmode = RUNMODE.lock();
-                *runmode = RunMode::Uninitialized;
-                drop(runmode);
-
                 break;
             }
 
-            if let Ok(Event::UserInput(inp)) = rx.recv() {
-                let mut p = ps.lock();
-                let is_movement = Event::UserInput(inp).is_movement();
-                handle_event(
-                    Event::UserInput(inp),
-                    &mut out_lock,
-                    &mut p,
-                    is_exitted,
-                    #[cfg(feature = "search")]
-                    input_thread_running,
-                )?;
-                if !is_movement {
+            // The application can still hold clones of the pager and send events through them, even
+            // after the initial state was generated. These must not be lost
+            let data_rx = if producer_connected {
+                rx
+            } else {
+                &disconnected
+            };
+            let timer = auto_scroll_timer(&ps.lock(), &mut auto_scroll_at);
+            let message_timer = message_timer(&ps.lock(), &mut message_until);
+            let resize_timer = resize_timer(pending_resize);
+            let event = crossbeam_channel::select! {
+                recv(data_rx) -> ev => {
+                    if ev.is_err() {
+                        producer_connected = false;
+                        continue;
+                    }
+                    ev
+                },
+                recv(input_rx) -> ev => {
+                    if debounce_resize(&ps.lock(), &ev, &mut pending_resize) {
+                        continue;
+                    }
+                    ev
+                },
+                recv(timer) -> _ => {
+                    auto_scroll_at = None;
+                    auto_scroll_step(
+                        &mut out_lock,
+                        &mut ps.lock(),
+                        is_exitted,
+                        #[cfg(feature = "search")]
+                        input_thread_running,
+                    )?;
+                    continue;
+                },
+                recv(message_timer) -> _ => {
+                    message_until = None;
+                    restore_prompt(&mut out_lock, &mut ps.lock())?;
+                    continue;
+                },
+                recv(resize_timer) -> _ => {
+                    // The terminal has kept its size, so the last resize is applied now
+                    Ok(Event::UserInput(pending_resize.take().unwrap().0))
+                },
+            };
+            // The event reader has stopped
+            if event.is_err() {
+                break;
+            }
+            if matches!(&event, Ok(ev) if ev.pauses_auto_scroll()) {
+                ps.lock().auto_scroll_paused = true;
+            }
+            let mut p = ps.lock();
+            match event {
+                Ok(ev) if ev.only_changes_prompt() => {
+                    handle_prompt_event(
+                        ev,
+                        &mut out_lock,
+                        &mut p,
+                        is_exitted,
+                        #[cfg(feature = "search")]
+                        input_thread_running,
+                    )?;
+                }
+                Ok(Event::UserInput(inp)) => {
+                    let is_movement = Event::UserInput(inp).is_movement();
+                    handle_event(
+                        Event::UserInput(inp),
+                        &mut out_lock,
+                        &mut p,
+                        is_exitted,
+                        #[cfg(feature = "search")]
+                        input_thread_running,
+                    )?;
+                    if !is_movement {
+                        draw_full(&mut out_lock, &mut p)?;
+                    }
+                }
+                Ok(ev) => {
+                    handle_event(
+                        ev,
+                        &mut out_lock,
+                        &mut p,
+                        is_exitted,
+                        #[cfg(feature = "search")]
+                        input_thread_running,
+                    )?;
                     draw_full(&mut out_lock, &mut p)?;
                 }
+                Err(_) => {}
             }
         },
         RunMode::Uninitialized => panic!(
@@ -320,6 +598,153 @@ fn start_reactor(
 This is most likely a bug. Please open an issue to the developers"
         ),
     }
+    *RUNMODE.lock() = RunMode::Uninitialized;
+    Ok(())
+}
+
+/// Make a channel that delivers a message when the next auto scroll step is due
+///
+/// `next_at` holds the time of the next step. It is set here if auto scroll has been just turned
+/// on and cleared if it is off or paused, in which case the channel never delivers anything.
+fn auto_scroll_timer(p: &PagerState, next_at: &mut Option<Instant>) -> Receiver<Instant> {
+    match p.auto_scroll {
+        Some(interval) if !p.auto_scroll_paused => {
+            crossbeam_channel::at(*next_at.get_or_insert_with(|| Instant::now() + interval))
+        }
+        _ => {
+            *next_at = None;
+            crossbeam_channel::never()
+        }
+    }
+}
+
+/// Make a channel that delivers a message when the message on the prompt should be taken down
+///
+/// This only happens with [`MessageStyle::Timed`]. `until` holds the message and its deadline,
+/// which starts over if another message is shown in the meantime.
+fn message_timer(p: &PagerState, until: &mut Option<(String, Instant)>) -> Receiver<Instant> {
+    if let (Some(msg), MessageStyle::Timed(duration)) = (&p.message, p.message_style) {
+        match until {
+            Some((shown, _)) if shown == msg => {}
+            _ => *until = Some((msg.clone(), Instant::now() + duration)),
+        }
+        crossbeam_channel::at(until.as_ref().unwrap().1)
+    } else {
+        *until = None;
+        crossbeam_channel::never()
+    }
+}
+
+/// Hold back a resize of the terminal while [`PagerState::resize_debounce`] is set
+///
+/// Returns whether `ev` has been held back. Only the last size is kept in `pending`, and the
+/// interval starts over with each resize, so that it is applied once the terminal stops changing.
+fn debounce_resize(
+    p: &PagerState,
+    ev: &Result<Event, RecvError>,
+    pending: &mut Option<(input::InputEvent, Instant)>,
+) -> bool {
+    match ev {
+        Ok(Event::UserInput(inp @ input::InputEvent::UpdateTermArea(..)))
+            if !p.resize_debounce.is_zero() =>
+        {
+            *pending = Some((*inp, Instant::now() + p.resize_debounce));
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Make a channel that delivers a message when the resize held back in `pending` is due
+fn resize_timer(pending: Option<(input::InputEvent, Instant)>) -> Receiver<Instant> {
+    pending.map_or_else(crossbeam_channel::never, |(_, at)| {
+        crossbeam_channel::at(at)
+    })
+}
+
+/// The rows of the text and the prompt as they are on the screen
+///
+/// This tells whether dropping the oldest lines changed anything that is displayed.
+#[cfg(feature = "dynamic_output")]
+fn visible_screen(p: &PagerState) -> (Vec<String>, String) {
+    (
+        p.visible_rows().map(String::from).collect(),
+        p.displayed_prompt.clone(),
+    )
+}
+
+/// Bring back the prompt in place of the timed message
+fn restore_prompt(out: &mut impl std::io::Write, p: &mut PagerState) -> Result<(), MinusError> {
+    p.message = None;
+    p.format_prompt();
+    draw_prompt_only(out, p)
+}
+
+/// Handle an event for which only the prompt has to be redrawn
+///
+/// The entire screen is redrawn instead if the prompt has changed the number of rows that are
+/// left for the text, or if the message went into the message log.
+fn handle_prompt_event(
+    ev: Event,
+    out: &mut impl std::io::Write,
+    p: &mut PagerState,
+    is_exitted: &Arc<AtomicBool>,
+    #[cfg(feature = "search")] input_thread_running: &Arc<(Mutex<bool>, Condvar)>,
+) -> Result<(), MinusError> {
+    let logs_message = matches!(ev, Event::SendMessage(_)) && p.message_lines > 0;
+    let content_rows = p.content_rows();
+    handle_event(
+        ev,
+        out,
+        p,
+        is_exitted,
+        #[cfg(feature = "search")]
+        input_thread_running,
+    )?;
+    if logs_message || p.content_rows() != content_rows {
+        draw_full(out, p)
+    } else {
+        draw_prompt_only(out, p)
+    }
+}
+
+/// Quit the pager as if the user had quit, after the application has dropped its pager
+#[cfg(feature = "dynamic_output")]
+fn exit_on_disconnect(
+    out: &mut impl std::io::Write,
+    p: &mut PagerState,
+    is_exitted: &Arc<AtomicBool>,
+    #[cfg(feature = "search")] input_thread_running: &Arc<(Mutex<bool>, Condvar)>,
+) -> Result<(), MinusError> {
+    handle_event(
+        Event::UserInput(input::InputEvent::Exit),
+        out,
+        p,
+        is_exitted,
+        #[cfg(feature = "search")]
+        input_thread_running,
+    )
+}
+
+/// Quit the pager and print the text on the main screen instead
+///
+/// The terminal is cleaned up first, hence the text is printed after the alternate screen is
+/// left. This is used when [`PagerState::exit_when_fits`] is set.
+#[cfg(feature = "dynamic_output")]
+fn exit_inline(
+    out: &mut impl std::io::Write,
+    p: &mut PagerState,
+    is_exitted: &Arc<AtomicBool>,
+) -> Result<(), MinusError> {
+    term::cleanup(&mut *out, &crate::ExitStrategy::PagerQuit, true)?;
+    p.upper_mark = 0;
+    write_lines(out, p)?;
+    out.flush()?;
+    p.exit();
+    is_exitted.store(true, Ordering::SeqCst);
+    if p.exit_strategy == crate::ExitStrategy::ProcessQuit {
+        std::process::exit(0);
+    }
     Ok(())
 }
 
@@ -343,29 +768,140 @@ fn event_reader(
             }
         }
 
-        if event::poll(std::time::Duration::from_millis(100))
-            .map_err(|e| MinusError::HandleEvent(e.into()))?
-        {
+        // Wake up in time for the keys of an incomplete key sequence to time out
+        let mut poll_timeout = std::time::Duration::from_millis(100);
+        let deadline = ps.lock().pending_keys_deadline();
+        if let Some(deadline) = deadline {
+            poll_timeout = poll_timeout.min(deadline.saturating_duration_since(Instant::now()));
+        }
+        let inputs = if event::poll(poll_timeout).map_err(|e| MinusError::HandleEvent(e.into()))? {
             let ev = event::read().map_err(|e| MinusError::HandleEvent(e.into()))?;
-            let mut guard = ps.lock();
             // Get the events
-            let input = guard.input_classifier.classify_input(ev, &guard);
-            if let Some(iev) = input {
-                if let InputEvent::Number(n) = iev {
-                    guard.prefix_num.push(n);
-                    guard.format_prompt();
-                } else if !guard.prefix_num.is_empty() {
-                    guard.prefix_num.clear();
-                    guard.format_prompt();
-                }
-                if let Err(TrySendError::Disconnected(_)) = evtx.try_send(Event::UserInput(iev)) {
-                    break;
+            input::classify_event(ev, &mut ps.lock())
+        } else {
+            input::resolve_timed_out_keys(&mut ps.lock())
+        };
+        // The reactor has stopped if the event can't be sent
+        if inputs
+            .into_iter()
+            .any(|iev| !send_user_input(evtx, Event::UserInput(iev), is_exitted))
+        {
+            break;
+        }
+    }
+    Result::<(), MinusError>::Ok(())
+}
+
+/// Send a user input event to the reactor
+///
+/// If the channel is full, this waits until there's space for the event, while regularly checking
+/// whether the pager has been exitted in the meantime. Returns `false` if the event could not be
+/// sent because the pager has quit or the channel is disconnected.
+fn send_user_input(evtx: &Sender<Event>, mut ev: Event, is_exitted: &Arc<AtomicBool>) -> bool {
+    loop {
+        match evtx.send_timeout(ev, std::time::Duration::from_millis(100)) {
+            Ok(()) => return true,
+            Err(SendTimeoutError::Timeout(e)) => {
+                if is_exitted.load(Ordering::SeqCst) {
+                    return false;
                 }
-            } else if !guard.prefix_num.is_empty() {
-                guard.prefix_num.clear();
-                guard.format_prompt();
+                ev = e;
             }
+            Err(SendTimeoutError::Disconnected(_)) => return false,
         }
     }
-    Result::<(), MinusError>::Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{debounce_resize, resize_timer, send_user_input};
+    use crate::{input::InputEvent, minus_core::events::Event, state::PagerState};
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    #[test]
+    fn send_user_input_to_full_channel() {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let is_exitted = Arc::new(AtomicBool::new(false));
+        assert!(send_user_input(
+            &tx,
+            Event::UserInput(InputEvent::Exit),
+            &is_exitted
+        ));
+
+        // The channel is full, so this waits until the pager quits
+        let is_exitted2 = is_exitted.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            is_exitted2.store(true, Ordering::SeqCst);
+        });
+        assert!(!send_user_input(
+            &tx,
+            Event::UserInput(InputEvent::Exit),
+            &is_exitted
+        ));
+        handle.join().unwrap();
+
+        drop(rx);
+        assert!(!send_user_input(
+            &tx,
+            Event::UserInput(InputEvent::Exit),
+            &Arc::new(AtomicBool::new(false))
+        ));
+    }
+
+    #[test]
+    fn debounce_resize_keeps_last_size() {
+        let mut ps = PagerState::new().unwrap();
+        let mut pending = None;
+
+        // Without a debounce interval, resizes go through right away
+        let resize = Ok(Event::UserInput(InputEvent::UpdateTermArea(80, 24)));
+        assert!(!debounce_resize(&ps, &resize, &mut pending));
+        assert!(pending.is_none());
+        assert!(resize_timer(pending).try_recv().is_err());
+
+        ps.resize_debounce = Duration::from_millis(20);
+        for (cols, rows) in [(80, 24), (100, 30), (120, 40)] {
+            let resize = Ok(Event::UserInput(InputEvent::UpdateTermArea(cols, rows)));
+            assert!(debounce_resize(&ps, &resize, &mut pending));
+        }
+        // Other events are never held back
+        let exit = Ok(Event::UserInput(InputEvent::Exit));
+        assert!(!debounce_resize(&ps, &exit, &mut pending));
+
+        // All the resizes have been coalesced into the last one
+        assert_eq!(
+            pending.map(|(ev, _)| ev),
+            Some(InputEvent::UpdateTermArea(120, 40))
+        );
+        let timer = resize_timer(pending);
+        assert!(timer.try_recv().is_err());
+        assert!(timer.recv_timeout(Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic_output")]
+    fn exits_on_disconnect_at_end() {
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 10;
+        ps.append_str(&"line\n".repeat(30));
+        assert!(!ps.exits_on_disconnect());
+
+        ps.exit_on_disconnect = true;
+        // The user hasn't seen the end of the text yet
+        ps.upper_mark = 0;
+        assert!(!ps.exits_on_disconnect());
+
+        ps.upper_mark = ps.max_upper_mark();
+        assert!(ps.exits_on_disconnect());
+
+        ps.exit_on_disconnect = false;
+        assert!(!ps.exits_on_disconnect());
+    }
 }
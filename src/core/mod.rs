@@ -1,3 +1,4 @@
+pub mod clipboard;
 mod display;
 pub mod ev_handler;
 pub mod events;
@@ -50,27 +50,22 @@ impl PartialEq for SearchMode {
 /// The function will change the prompt to `/` for Forward search or `?` for Reverse search
 /// It will then store the query in a String and return it when `Return` key is pressed
 /// or return with a empty string if so match is found.
+///
+/// `Alt+C` toggles `ignore_case` while the query is typed. The prompt shows `(?i)` after the
+/// `/` or `?` while case is ignored.
 #[cfg(feature = "search")]
 pub fn fetch_input(
     out: &mut impl std::io::Write,
     search_mode: SearchMode,
     rows: usize,
+    ignore_case: &mut bool,
 ) -> Result<String, MinusError> {
     // Place the cursor at the beginning of very prompt line, clear
     // the prompt and show the cursor
     #[allow(clippy::cast_possible_truncation)]
-    write!(
-        out,
-        "{}{}{}{}",
-        MoveTo(0, rows as u16),
-        Clear(ClearType::CurrentLine),
-        if search_mode == SearchMode::Forward {
-            "/"
-        } else {
-            "?"
-        },
-        cursor::Show
-    )?;
+    write!(out, "{}", MoveTo(0, rows as u16))?;
+    write_query(out, search_mode, "", *ignore_case)?;
+    write!(out, "{}", cursor::Show)?;
     out.flush()?;
     let mut string = String::new();
     loop {
@@ -91,7 +86,7 @@ pub fn fetch_input(
                 }) => {
                     string.pop();
                     // Update the line
-                    write!(out, "\r{}/{}", Clear(ClearType::CurrentLine), string)?;
+                    write_query(out, search_mode, &string, *ignore_case)?;
                     out.flush()?;
                 }
                 Event::Key(KeyEvent {
@@ -102,12 +97,21 @@ pub fn fetch_input(
                     // Return the string when enter is pressed
                     return Ok(string);
                 }
+                // Toggle whether the search ignores case
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('c'),
+                    modifiers: KeyModifiers::ALT,
+                }) => {
+                    *ignore_case = !*ignore_case;
+                    write_query(out, search_mode, &string, *ignore_case)?;
+                    out.flush()?;
+                }
                 Event::Key(event) => {
                     // For any character key, without a modifier, append it to the
                     // string and update the line
                     if let KeyCode::Char(c) = event.code {
                         string.push(c);
-                        write!(out, "\r/{}", string)?;
+                        write_query(out, search_mode, &string, *ignore_case)?;
                         out.flush()?;
                     }
                 }
@@ -117,6 +121,29 @@ pub fn fetch_input(
     }
 }
 
+/// Rewrite the line of the search prompt with the query typed so far
+#[cfg(feature = "search")]
+fn write_query(
+    out: &mut impl std::io::Write,
+    search_mode: SearchMode,
+    query: &str,
+    ignore_case: bool,
+) -> Result<(), MinusError> {
+    write!(
+        out,
+        "\r{}{}{}{}",
+        Clear(ClearType::CurrentLine),
+        if search_mode == SearchMode::Forward {
+            "/"
+        } else {
+            "?"
+        },
+        if ignore_case { "(?i)" } else { "" },
+        query
+    )?;
+    Ok(())
+}
+
 /// Highlights the search match
 ///
 /// The first return value returns the line that has all the search matches highlighted
@@ -130,6 +157,167 @@ pub fn highlight_line_matches(line: &str, query: &regex::Regex) -> (String, bool
         return (line.to_string(), false);
     }
 
+    let ranges = query
+        .find_iter(&stripped_str)
+        .map(|m| (m.start(), m.end()))
+        .collect::<Vec<_>>();
+    (highlight_ranges(line, &stripped_str, &ranges), true)
+}
+
+/// Highlights the search matches in the wrapped rows of a line
+///
+/// `rows` are the rows that `line` has been wrapped into. Searching each row on its own would miss
+/// the parts of a match that is split across rows by the wrapping, hence the matches are searched
+/// in the entire `line` and then each row gets highlighted with the parts of the matches that lie
+/// in it.
+///
+/// The first return value contains the highlighted rows. The second contains the indices of the
+/// rows in which a match starts.
+pub fn highlight_wrapped_matches(
+    line: &str,
+    rows: &[String],
+    query: &regex::Regex,
+) -> (Vec<String>, Vec<usize>) {
+    let stripped_line = ANSI_REGEX.replace_all(line, "");
+    let matches = query
+        .find_iter(&stripped_line)
+        .map(|m| (m.start(), m.end()))
+        .collect::<Vec<_>>();
+    if matches.is_empty() {
+        return (rows.to_vec(), Vec::new());
+    }
+
+    let mut highlighted = Vec::with_capacity(rows.len());
+    let mut match_rows = Vec::new();
+    // Position in the stripped line upto which the rows have been found
+    let mut cursor = 0;
+    for (idx, row) in rows.iter().enumerate() {
+        let stripped_row = ANSI_REGEX.replace_all(row, "");
+        // The rows are pieces of the line in the same order, only the whitespace at the wrapping
+        // points is left out. Hence each row can be found by looking after the previous one
+        let start = if let Some(pos) = stripped_line[cursor..].find(&*stripped_row) {
+            cursor + pos
+        } else {
+            // This shouldn't happen, but if it does, search the row on its own
+            let (hrow, is_match) = highlight_line_matches(row, query);
+            if is_match {
+                match_rows.push(idx);
+            }
+            highlighted.push(hrow);
+            continue;
+        };
+        let end = start + stripped_row.len();
+        cursor = end;
+
+        // Parts of the matches that lie in this row, relative to the start of the row
+        let ranges = matches
+            .iter()
+            .filter(|&&(m_start, m_end)| {
+                (m_start < end && m_end > start) || (m_start == m_end && m_start == start)
+            })
+            .map(|&(m_start, m_end)| (m_start.max(start) - start, m_end.min(end) - start))
+            .collect::<Vec<_>>();
+        if matches
+            .iter()
+            .any(|(m_start, _)| (start..end).contains(m_start))
+        {
+            match_rows.push(idx);
+        }
+        if ranges.is_empty() {
+            highlighted.push(row.clone());
+        } else {
+            highlighted.push(highlight_ranges(row, &stripped_row, &ranges));
+        }
+    }
+    (highlighted, match_rows)
+}
+
+/// Marks the search matches of a line with brackets
+///
+/// This is used instead of highlighting when colors are turned off. Each match is enclosed in `[`
+/// and `]`, keeping the ANSI escapes of `line` in place.
+///
+/// The first return value is the marked line. The second contains the positions of the `[` that
+/// start the matches, in the marked line with all ANSI escapes removed.
+pub fn mark_matches(line: &str, query: &regex::Regex) -> (String, Vec<usize>) {
+    let stripped_line = ANSI_REGEX.replace_all(line, "");
+    let matches = query
+        .find_iter(&stripped_line)
+        .map(|m| (m.start(), m.end()))
+        .collect::<Vec<_>>();
+    if matches.is_empty() {
+        return (line.to_owned(), Vec::new());
+    }
+
+    // Position in line of each position in the stripped line. The escapes are plain ASCII, so
+    // every byte outside of them corresponds to a byte of the stripped line
+    let mut positions = Vec::with_capacity(stripped_line.len() + 1);
+    let mut escapes = ANSI_REGEX.find_iter(line).peekable();
+    let mut pos = 0;
+    while pos <= line.len() {
+        if let Some(escape) = escapes.next_if(|escape| escape.start() == pos) {
+            pos = escape.end();
+            continue;
+        }
+        positions.push(pos);
+        pos += 1;
+    }
+
+    let mut marked = String::with_capacity(line.len() + 2 * matches.len());
+    let mut starts = Vec::with_capacity(matches.len());
+    let mut last = 0;
+    for (nth, (start, end)) in matches.into_iter().enumerate() {
+        // Each match before this one added two brackets
+        starts.push(start + 2 * nth);
+        // The closing bracket goes right after the last byte of the match, before any escapes
+        // that follow it
+        let end = if end > start {
+            positions[end - 1] + 1
+        } else {
+            positions[start]
+        };
+        marked.push_str(&line[last..positions[start]]);
+        marked.push('[');
+        marked.push_str(&line[positions[start]..end]);
+        marked.push(']');
+        last = end;
+    }
+    marked.push_str(&line[last..]);
+    (marked, starts)
+}
+
+/// Indices of the rows that contain any of the given positions
+///
+/// `rows` are the rows that `line` has been wrapped into and the `positions` are positions in
+/// `line` with all ANSI escapes removed.
+pub fn rows_containing(line: &str, rows: &[String], positions: &[usize]) -> Vec<usize> {
+    let stripped_line = ANSI_REGEX.replace_all(line, "");
+    let mut found = Vec::new();
+    // Position in the stripped line upto which the rows have been found
+    let mut cursor = 0;
+    for (idx, row) in rows.iter().enumerate() {
+        let stripped_row = ANSI_REGEX.replace_all(row, "");
+        // A row that can't be found, like one that is truncated with a marker, is taken to
+        // start right after the previous one
+        let start = stripped_line
+            .get(cursor..)
+            .and_then(|rest| rest.find(&*stripped_row))
+            .map_or(cursor, |pos| cursor + pos);
+        let end = (start + stripped_row.len()).min(stripped_line.len());
+        cursor = end;
+        if positions.iter().any(|pos| (start..end).contains(pos)) {
+            found.push(idx);
+        }
+    }
+    found
+}
+
+/// Highlights the given ranges of `line`
+///
+/// `stripped_str` is `line` with all ANSI escapes removed and the `ranges` are the start and end
+/// of the parts to be highlighted in it. The ANSI escapes of `line` are placed back at their
+/// positions, except the ones that lie inside a highlighted part.
+fn highlight_ranges(line: &str, stripped_str: &str, ranges: &[(usize, usize)]) -> String {
     // sum_width is used to calculate the total width of the ansi escapes
     // up to the point in the original string where it is being used
     let mut sum_width = 0;
@@ -149,20 +337,25 @@ pub fn highlight_line_matches(line: &str, query: &regex::Regex) -> (String, bool
         })
         .collect::<Vec<_>>();
 
-    // The matches of the term you're looking for, so that you can easily determine where
+    // The boundaries of the parts to be highlighted, so that you can easily determine where
     // the invert attributes will be placed
-    let matches = query
-        .find_iter(&stripped_str)
-        .flat_map(|c| [c.start(), c.end()])
+    let matches = ranges
+        .iter()
+        .flat_map(|(start, end)| [*start, *end])
         .collect::<Vec<_>>();
 
-    // Highlight all the instances of the search term in the stripped string
-    // by inverting their background/foreground colors
-    let mut inverted = query
-        .replace_all(&stripped_str, |caps: &regex::Captures| {
-            format!("{}{}{}", *INVERT, &caps[0], *NORMAL)
-        })
-        .to_string();
+    // Highlight all the given parts of the stripped string by inverting their
+    // background/foreground colors
+    let mut inverted = String::with_capacity(stripped_str.len() + ranges.len() * 8);
+    let mut last = 0;
+    for (start, end) in ranges {
+        inverted.push_str(&stripped_str[last..*start]);
+        inverted.push_str(&INVERT);
+        inverted.push_str(&stripped_str[*start..*end]);
+        inverted.push_str(&NORMAL);
+        last = *end;
+    }
+    inverted.push_str(&stripped_str[last..]);
 
     // inserted_escs_len == the total length of the ascii escapes which have been re-inserted
     // into the stripped string at the point where it is being checked.
@@ -195,7 +388,21 @@ pub fn highlight_line_matches(line: &str, query: &regex::Regex) -> (String, bool
         inserted_escs_len += esc.1.len();
     }
 
-    (inverted, true)
+    inverted
+}
+
+/// Dims the given line
+///
+/// Any reset sequence present in the line would also turn off the dimming for the rest of the line,
+/// hence the dim attribute is applied again after each of them.
+pub fn dim_line(line: &str) -> String {
+    let reset = Attribute::Reset.to_string();
+    format!(
+        "{dim}{line}{normal}",
+        dim = Attribute::Dim,
+        line = line.replace(&reset, &format!("{}{}", reset, Attribute::Dim)),
+        normal = Attribute::NormalIntensity
+    )
 }
 
 /// Set [`PagerState::search_mark`] to move to the next match
@@ -226,7 +433,10 @@ pub fn next_nth_match(ps: &mut PagerState, n: usize) {
 mod tests {
     use std::collections::BTreeSet;
 
-    use super::{highlight_line_matches, next_nth_match, INVERT, NORMAL};
+    use super::{
+        highlight_line_matches, highlight_wrapped_matches, mark_matches, next_nth_match,
+        rows_containing, INVERT, NORMAL,
+    };
     use crate::PagerState;
     use crossterm::style::Attribute;
     use regex::Regex;
@@ -264,6 +474,58 @@ eros.",
         assert_eq!(highlight_line_matches(&line, &pat).0, result);
     }
 
+    #[test]
+    fn match_across_wrapped_rows() {
+        let line = "this is a test line";
+        let rows = vec!["this is a te".to_string(), "st line".to_string()];
+        let (res, match_rows) =
+            highlight_wrapped_matches(line, &rows, &Regex::new("test").unwrap());
+        assert_eq!(
+            res,
+            vec![
+                format!("this is a {}te{}", *INVERT, *NORMAL),
+                format!("{}st{} line", *INVERT, *NORMAL)
+            ]
+        );
+        // Only the row where the match starts counts as a match
+        assert_eq!(match_rows, vec![0]);
+    }
+
+    #[test]
+    fn all_matches_in_row() {
+        let line = "foo \x1b[1mbar\x1b[0m foo baz foo";
+        let rows = vec![line.to_string()];
+        let (res, match_rows) = highlight_wrapped_matches(line, &rows, &Regex::new("foo").unwrap());
+        assert_eq!(
+            res,
+            vec![format!(
+                "{i}foo{n} \x1b[1mbar\x1b[0m {i}foo{n} baz {i}foo{n}",
+                i = *INVERT,
+                n = *NORMAL
+            )]
+        );
+        assert_eq!(match_rows, vec![0]);
+    }
+
+    #[test]
+    fn match_after_tab_and_wide_char() {
+        // The highlights are placed by byte offset in the text itself, so tabs and wide
+        // characters before a match don't shift them
+        let line = "a\t界 \x1b[1mb\x1b[0m test 界 test";
+        let rows = crate::wrap_str(line, 12);
+        assert_eq!(rows.len(), 2);
+        let (res, match_rows) =
+            highlight_wrapped_matches(line, &rows, &Regex::new("test").unwrap());
+        assert_eq!(
+            res,
+            vec![
+                format!("a\t界 \x1b[1mb\x1b[0m {}test{}", *INVERT, *NORMAL),
+                format!("界 {}test{}", *INVERT, *NORMAL)
+            ]
+        );
+        assert_eq!(match_rows, vec![0, 1]);
+    }
+
     #[test]
     fn no_match() {
         let orig = "no match";
@@ -354,4 +616,26 @@ eros.",
             )
         );
     }
+
+    #[test]
+    fn mark_matches_keeps_escapes() {
+        let line = format!("{}foo{} bar foo", ESC, NONE);
+        let pat = Regex::new("fo+").unwrap();
+        let (marked, starts) = mark_matches(&line, &pat);
+        assert_eq!(marked, format!("{}[foo]{} bar [foo]", ESC, NONE));
+        assert_eq!(starts, vec![0, 10]);
+    }
+
+    #[test]
+    fn marked_matches_in_wrapped_rows() {
+        let pat = Regex::new("ab").unwrap();
+        let (marked, starts) = mark_matches("xx ab yy zz ab", &pat);
+        assert_eq!(marked, "xx [ab] yy zz [ab]");
+        let rows = vec![
+            "xx [ab]".to_string(),
+            "yy zz".to_string(),
+            "[ab]".to_string(),
+        ];
+        assert_eq!(rows_containing(&marked, &rows, &starts), vec![0, 2]);
+    }
 }
@@ -1,9 +1,35 @@
 //! Contains functions for dealing with setup, cleanup
+//!
+//! minus sets up the terminal by itself when paging starts and restores it when the user quits,
+//! hence most applications never need these. They are useful for applications that draw on the
+//! terminal with [`crossterm`] themselves and want to switch between their own output and minus
+//! without the terminal flickering between the screens.
+//!
+//! [`setup`] puts the terminal in the state that minus expects while it is paging:-
+//! - The alternate screen is active
+//! - [Raw mode] is enabled
+//! - Mouse capture is enabled
+//! - The cursor is hidden
+//!
+//! [`teardown`] undoes all of these. Some things to keep in mind:-
+//! - Calls to [`setup`] and [`teardown`] should be paired. Calling [`setup`] twice does not nest,
+//!   a single [`teardown`] restores the terminal.
+//! - Don't call [`teardown`] while minus is paging, minus expects the terminal to stay set up
+//!   until the user quits.
+//! - minus restores the terminal on its own when the user quits. There is no need to call
+//!   [`teardown`] after that.
+//!
+//! [Raw mode]: ../../crossterm/terminal/index.html#raw-mode
 
 use crate::error::{CleanupError, MinusError, SetupError};
 use crossterm::{cursor, event, execute, queue, terminal, tty::IsTty};
 use std::io;
 
+/// Minimum number of rows required to display the pager
+///
+/// One row is needed for the text and one for the prompt
+pub const MIN_ROWS: usize = 2;
+
 /// Setup the terminal
 ///
 /// It will
@@ -12,11 +38,12 @@ use std::io;
 /// - Clear the entire screen and hide the cursor.
 ///
 /// # Errors
-/// The function will return with an error if `stdout` is not a terminal. It will qlso fail
-/// if it cannot executo commands on the terminal See [`SetupError`].
+/// The function will return with an error if `stdout` is not a terminal or if the terminal has
+/// less than [`MIN_ROWS`] rows. It will qlso fail if it cannot executo commands on the terminal
+/// See [`SetupError`].
 ///
-/// [alternate screen]: ../../../crossterm/terminal/index.html#alternate-screen
-/// [raw mode]: ../../../crossterm/terminal/index.html#raw-mode
+/// [alternate screen]: ../../crossterm/terminal/index.html#alternate-screen
+/// [raw mode]: ../../crossterm/terminal/index.html#raw-mode
 // This function should be kept close to `cleanup` to help ensure both are
 // doing the opposite of the other.
 pub fn setup(stdout: &io::Stdout) -> std::result::Result<(), SetupError> {
@@ -28,6 +55,11 @@ pub fn setup(stdout: &io::Stdout) -> std::result::Result<(), SetupError> {
         Err(SetupError::InvalidTerminal)
     }?;
 
+    let (_, rows) = terminal::size().map_err(|e| SetupError::TerminalSize(e.into()))?;
+    if usize::from(rows) < MIN_ROWS {
+        return Err(SetupError::TerminalTooSmall);
+    }
+
     execute!(out, terminal::EnterAlternateScreen)
         .map_err(|e| SetupError::AlternateScreen(e.into()))?;
     terminal::enable_raw_mode().map_err(|e| SetupError::RawMode(e.into()))?;
@@ -49,8 +81,8 @@ pub fn setup(stdout: &io::Stdout) -> std::result::Result<(), SetupError> {
 /// The function will return with an error if it fails to do execute commands on the
 /// terminal. See [`CleanupError`]
 ///
-/// [raw mode]: ../../../crossterm/terminal/index.html#raw-mode
-pub fn cleanup(
+/// [raw mode]: ../../crossterm/terminal/index.html#raw-mode
+pub(crate) fn cleanup(
     mut out: impl io::Write,
     es: &crate::ExitStrategy,
     cleanup_screen: bool,
@@ -72,10 +104,36 @@ pub fn cleanup(
     }
 }
 
+/// Restores the terminal to the state it was in before [`setup`] was called
+///
+/// It will
+/// - Display the cursor
+/// - Disable [raw mode]
+/// - Disable mouse capture
+/// - Switch the terminal's view back to the main screen
+///
+/// # Errors
+/// The function will return with an error if it fails to do execute commands on the
+/// terminal. See [`CleanupError`]
+///
+/// # Example
+/// ```no_run
+/// use std::io::stdout;
+///
+/// minus::term::setup(&stdout()).expect("Failed to set up the terminal");
+/// // Draw on the alternate screen
+/// minus::term::teardown(&stdout()).expect("Failed to restore the terminal");
+/// ```
+///
+/// [raw mode]: ../../crossterm/terminal/index.html#raw-mode
+pub fn teardown(stdout: &io::Stdout) -> std::result::Result<(), CleanupError> {
+    cleanup(stdout.lock(), &crate::ExitStrategy::PagerQuit, true)
+}
+
 /// Moves the terminal cursor to given x, y coordinates
 ///
 /// The `flush` parameter will immidiately flush the buffer if it is set to `true`
-pub fn move_cursor(
+pub(crate) fn move_cursor(
     out: &mut impl io::Write,
     x: u16,
     y: u16,
@@ -19,5 +19,13 @@ pub fn dynamic_paging(pager: Pager) -> Result<(), MinusError> {
     assert!(runmode.is_uninitialized(), "Failed to set the RUNMODE. This is caused probably bcause another instance of minus is already running");
     *runmode = minus_core::RunMode::Dynamic;
     drop(runmode);
-    init::init_core(pager)
+    // Don't hold on to a sender of the pager's channel, so that the channel disconnects once the
+    // application drops all of its pagers
+    let Pager { rx, ps, exit, .. } = pager;
+    let res = init::init_core(&rx, &ps);
+    // init_core can return early without starting the pager, so make sure that the pager can be
+    // started again
+    *init::RUNMODE.lock() = minus_core::RunMode::Uninitialized;
+    exit.notify(&res);
+    res.map(|_| ())
 }
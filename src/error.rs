@@ -37,67 +37,84 @@ pub enum SetupError {
     #[error("The standard output is not a valid terminal")]
     InvalidTerminal,
 
-    #[error("Failed to switch to alternate screen")]
-    AlternateScreen(TermError),
+    #[error("Failed to switch to alternate screen: {0}")]
+    AlternateScreen(#[source] TermError),
 
-    #[error("Failed to enable raw mode")]
-    RawMode(TermError),
+    #[error("Failed to enable raw mode: {0}")]
+    RawMode(#[source] TermError),
 
-    #[error("Failed to hide the cursor")]
-    HideCursor(TermError),
+    #[error("Failed to hide the cursor: {0}")]
+    HideCursor(#[source] TermError),
 
-    #[error("Failed to enable mouse capture")]
-    EnableMouseCapture(TermError),
+    #[error("Failed to enable mouse capture: {0}")]
+    EnableMouseCapture(#[source] TermError),
 
-    #[error("Couldn't determine the terminal size")]
-    TerminalSize(TermError),
+    #[error("Couldn't determine the terminal size: {0}")]
+    TerminalSize(#[source] TermError),
+
+    #[error("The terminal is too small to display the pager")]
+    TerminalTooSmall,
 }
 
 /// Errors that can occur during clean up
 #[derive(Debug, thiserror::Error)]
 #[allow(clippy::module_name_repetitions)]
 pub enum CleanupError {
-    #[error("Failed to disable mouse capture")]
-    DisableMouseCapture(TermError),
+    #[error("Failed to disable mouse capture: {0}")]
+    DisableMouseCapture(#[source] TermError),
 
-    #[error("Failed to show the cursor")]
-    ShowCursor(TermError),
+    #[error("Failed to show the cursor: {0}")]
+    ShowCursor(#[source] TermError),
 
-    #[error("Failed to disable raw mode")]
-    DisableRawMode(TermError),
+    #[error("Failed to disable raw mode: {0}")]
+    DisableRawMode(#[source] TermError),
 
-    #[error("Failed to switch back to main screen")]
-    LeaveAlternateScreen(TermError),
+    #[error("Failed to switch back to main screen: {0}")]
+    LeaveAlternateScreen(#[source] TermError),
 }
 
 /// Errors that can happen while running
 #[derive(Debug, thiserror::Error)]
 #[allow(clippy::module_name_repetitions)]
 pub enum MinusError {
-    #[error("Failed to initialize the terminal")]
+    /// The terminal could not be prepared for paging, for example because the standard output
+    /// is not a terminal or raw mode is not supported. Nothing has been displayed yet.
+    #[error("Failed to initialize the terminal: {0}")]
     Setup(#[from] SetupError),
 
-    #[error("Failed to clean up the terminal")]
+    /// The terminal could not be restored to its original state after the user quit
+    #[error("Failed to clean up the terminal: {0}")]
     Cleanup(#[from] CleanupError),
 
-    #[error("Failed to draw the new data")]
+    #[error("Failed to draw the new data: {0}")]
     Draw(#[from] std::io::Error),
 
-    #[error("Failed to handle terminal event")]
-    HandleEvent(TermError),
+    #[error("Failed to handle terminal event: {0}")]
+    HandleEvent(#[source] TermError),
 
-    #[error("Failed to do an operation on the cursor")]
+    #[error("Failed to do an operation on the cursor: {0}")]
     Cursor(#[from] TermError),
 
-    #[error("Failed to send formatted data to the pager")]
+    #[error("Failed to send formatted data to the pager: {0}")]
     FmtWriteError(#[from] std::fmt::Error),
 
-    #[error("Failed to send data to the receiver")]
+    #[error("Failed to send data to the receiver: {0}")]
     Communication(#[from] crossbeam_channel::SendError<Event>),
 
     #[error("Failed to convert between some primitives")]
     Conversion,
 
+    /// An operation needs the running pager but it hasn't started yet, as with
+    /// [`Pager::feed_crossterm_event`](crate::Pager::feed_crossterm_event)
+    #[error("The pager hasn't started yet")]
+    NotStarted,
+
+    /// The pager stopped because of an error, as told by [`Pager::wait_for_exit`](crate::Pager::wait_for_exit).
+    /// This holds the message of the error, while the error itself is returned by
+    /// [`dynamic_paging`](crate::dynamic_paging) or [`page_all`](crate::page_all).
+    #[error("The pager stopped with an error: {0}")]
+    Exited(String),
+
     #[error(transparent)]
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
@@ -116,3 +133,31 @@ impl From<regex::Error> for MinusError {
         Self::SearchExpError(RegexError::from(e))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{MinusError, SetupError, TermError};
+    use std::error::Error;
+    use std::io;
+
+    #[test]
+    fn source_chain() {
+        let io_err = || io::Error::new(io::ErrorKind::Unsupported, "not supported");
+        let err = MinusError::from(SetupError::RawMode(TermError::from(io_err())));
+        assert_eq!(
+            err.to_string(),
+            "Failed to initialize the terminal: Failed to enable raw mode: not supported"
+        );
+
+        let setup = err.source().unwrap();
+        assert!(setup.is::<SetupError>());
+        assert_eq!(setup.source().unwrap().to_string(), "not supported");
+
+        let err = MinusError::HandleEvent(TermError::from(io_err()));
+        assert_eq!(
+            err.to_string(),
+            "Failed to handle terminal event: not supported"
+        );
+        assert!(err.source().is_some());
+    }
+}
@@ -2,10 +2,14 @@
 //! to customize the default keybindings of minus
 
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Instant;
 
 #[cfg(feature = "search")]
 use crate::minus_core::search::SearchMode;
-use crate::{LineNumbers, PagerState};
+use crate::{Clipboard, CtrlCBehavior, LineNumbers, PagerState};
 
 /// Events handled by the `minus` pager.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -13,17 +17,66 @@ use crate::{LineNumbers, PagerState};
 pub enum InputEvent {
     /// `Ctrl+C` or `Q`, exits the application.
     Exit,
+    /// `Ctrl+C` if it has been set to interrupt. Restores the terminal, raises `SIGINT` and stops
+    /// the pager if the application survives that.
+    ///
+    /// The behaviour can be set with [`Pager::set_ctrl_c_behavior`](crate::Pager::set_ctrl_c_behavior)
+    Interrupt,
     /// The terminal was resized. Contains the new number of rows.
     UpdateTermArea(usize, usize),
     /// Sent by movement keys like `Up` `Down`, `PageUp`, 'PageDown', 'g', `G` etc. Contains the new value for the upper mark.
     UpdateUpperMark(usize),
-    /// `Ctrl+L`, inverts the line number display. Contains the new value.
+    /// `Ctrl+L` by default, inverts the line number display. Contains the new value.
+    ///
+    /// The key can be changed with [`Pager::set_line_number_toggle_key`](crate::Pager::set_line_number_toggle_key)
     UpdateLineNumber(LineNumbers),
     /// A number key has been pressed. This inner value is stored as a `char`.
     /// The input loop will append this number to its `count` string variable
     Number(char),
     /// Restore the original prompt
     RestorePrompt,
+    /// Restore the original prompt and move like [`InputEvent::UpdateUpperMark`]. Contains the
+    /// new value for the upper mark.
+    ///
+    /// This is what `Enter` does while a message is shown if
+    /// [`Pager::set_enter_dismiss_also_scrolls`](crate::Pager::set_enter_dismiss_also_scrolls)
+    /// is turned on
+    RestorePromptAndScroll(usize),
+    /// `Esc`, cancels the numeric prefix that has been typed so far
+    Cancel,
+    /// `y`, copies the line at the top of the screen to the clipboard.
+    ///
+    /// The way of copying can be set with [`Pager::set_clipboard`](crate::Pager::set_clipboard)
+    Copy,
+    /// `G` with a line number past the end of the text. Goes to the bottom and shows a message
+    /// that the end has been reached
+    ReachedEnd,
+    /// `Ctrl+L` if the line number toggle has been moved to another key. Clears and redraws the
+    /// entire screen, which is useful if it got corrupted by output from other programs.
+    ///
+    /// The key can be changed with [`Pager::set_redraw_key`](crate::Pager::set_redraw_key)
+    Redraw,
+    /// `Ctrl+W` by default, switches line wrapping on or off. Lines that aren't wrapped are cut
+    /// off at the edge of the screen.
+    ///
+    /// The key can be changed with [`Pager::set_wrap_toggle_key`](crate::Pager::set_wrap_toggle_key)
+    ToggleWrap,
+    /// `h` by default, shows the list of keybindings in place of the text.
+    ///
+    /// The key can be changed with [`Pager::set_show_help_key`](crate::Pager::set_show_help_key)
+    ShowHelp,
+    /// Any key while the list of keybindings is shown, displays the text again
+    HideHelp,
+    /// `Ctrl+D`/`Ctrl+U` with a count. Scrolls by that many rows and makes these keys scroll
+    /// by that many rows from then on instead of half a screen. Contains the count and whether
+    /// to scroll down.
+    SetHalfPage(usize, bool),
+    /// `Tab`, moves the focus to the other pane of a split view.
+    ///
+    /// A split view can be turned on with [`Pager::set_split`](crate::Pager::set_split)
+    SwitchPane,
+    /// `=`, switches whether the panes of a split view scroll together
+    ToggleScrollLock,
     /// `/`, Searching for certain pattern of text
     #[cfg(feature = "search")]
     Search(SearchMode),
@@ -39,6 +92,13 @@ pub enum InputEvent {
     /// Move to the previous nth match in the given direction
     #[cfg(feature = "search")]
     MoveToPrevMatch(usize),
+    /// `ESC u` by default, clears the highlights of the current search without changing the
+    /// scroll position. The search itself stays, so its matches can still be moved between, and
+    /// the next search is highlighted again.
+    ///
+    /// The key can be changed with [`Pager::set_clear_highlights_key`](crate::Pager::set_clear_highlights_key)
+    #[cfg(feature = "search")]
+    ClearHighlights,
 }
 
 /// Define custom keybindings
@@ -51,6 +111,9 @@ pub enum InputEvent {
 /// you need to directly work with the underlying [`crossterm`]
 /// crate**
 ///
+/// Keys that are pressed one after another, like `gg`, can be bound together by returning them
+/// from [`key_sequences`](InputClassifier::key_sequences). See [`KeySequences`] for an example.
+///
 /// # Example
 /// ```
 /// use minus::{input::{InputEvent, InputClassifier}, LineNumbers, Pager, PagerState};
@@ -87,23 +150,204 @@ pub enum InputEvent {
 #[allow(clippy::module_name_repetitions)]
 pub trait InputClassifier {
     fn classify_input(&self, ev: Event, ps: &PagerState) -> Option<InputEvent>;
+
+    /// Sequences of keys that are classified together by
+    /// [`classify_sequence`](InputClassifier::classify_sequence)
+    ///
+    /// There are none by default.
+    fn key_sequences(&self) -> Option<&KeySequences> {
+        None
+    }
+
+    /// Classify one of the [`key_sequences`](InputClassifier::key_sequences) after all of its
+    /// keys have been pressed
+    ///
+    /// Like with [`classify_input`](InputClassifier::classify_input), the numeric prefix typed
+    /// before the sequence is in [`PagerState::prefix_num`].
+    fn classify_sequence(&self, _keys: &[KeyEvent], _ps: &PagerState) -> Option<InputEvent> {
+        None
+    }
+
+    /// The keybindings listed with [`InputEvent::ShowHelp`], as pairs of the keys and what they
+    /// do
+    ///
+    /// These are the keybindings of [`DefaultInputClassifier`] by default, see [`default_help`].
+    fn help(&self, ps: &PagerState) -> Vec<(String, String)> {
+        default_help(ps)
+    }
+}
+
+/// A set of sequences of keys, like `gg` or `zz`, that are bound together
+///
+/// While the keys pressed so far are the start of a sequence, they are held back instead of
+/// being classified. Once the sequence is complete, it is passed to
+/// [`InputClassifier::classify_sequence`]. If the next key doesn't continue the sequence or it
+/// doesn't come within the timeout set with
+/// [`Pager::set_key_sequence_timeout`](crate::Pager::set_key_sequence_timeout), the keys held
+/// back are classified one by one with [`InputClassifier::classify_input`] instead. If one
+/// sequence is the start of another, the shorter one is taken when the timeout passes.
+///
+/// # Example
+/// ```
+/// use minus::{input::{DefaultInputClassifier, InputClassifier, InputEvent, KeySequences}, Pager, PagerState};
+/// use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+///
+/// struct CustomInputClassifier(KeySequences);
+/// impl InputClassifier for CustomInputClassifier {
+///     fn classify_input(&self, ev: Event, ps: &PagerState) -> Option<InputEvent> {
+///         DefaultInputClassifier.classify_input(ev, ps)
+///     }
+///
+///     fn key_sequences(&self) -> Option<&KeySequences> {
+///         Some(&self.0)
+///     }
+///
+///     fn classify_sequence(&self, _keys: &[KeyEvent], ps: &PagerState) -> Option<InputEvent> {
+///         // zz moves the line at the top of the screen to the middle
+///         Some(InputEvent::UpdateUpperMark(ps.upper_mark.saturating_sub(ps.rows / 2)))
+///     }
+/// }
+///
+/// let z = KeyEvent {
+///     code: KeyCode::Char('z'),
+///     modifiers: KeyModifiers::NONE,
+/// };
+/// let mut sequences = KeySequences::new();
+/// sequences.insert(&[z, z]);
+///
+/// let pager = Pager::new();
+/// pager.set_input_classifier(Box::new(CustomInputClassifier(sequences)));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KeySequences {
+    /// The sequences that continue with each key
+    next: HashMap<KeyEvent, Self>,
+    /// Whether a sequence ends here
+    complete: bool,
+}
+
+impl KeySequences {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sequence of `keys`
+    ///
+    /// Sequences of less than two keys are ignored, as single keys are classified with
+    /// [`InputClassifier::classify_input`].
+    pub fn insert(&mut self, keys: &[KeyEvent]) {
+        if keys.len() < 2 {
+            return;
+        }
+        let node = keys
+            .iter()
+            .fold(self, |node, &key| node.next.entry(key).or_default());
+        node.complete = true;
+    }
+
+    /// Check whether `keys` is one of the sequences
+    #[must_use]
+    pub fn contains(&self, keys: &[KeyEvent]) -> bool {
+        matches!(self.find(keys), Some(node) if node.complete)
+    }
+
+    /// The node that is reached by following `keys`, if they are the start of any sequence
+    fn find(&self, keys: &[KeyEvent]) -> Option<&Self> {
+        keys.iter().try_fold(self, |node, key| node.next.get(key))
+    }
+
+    fn match_keys(&self, keys: &[KeyEvent]) -> SequenceMatch {
+        match self.find(keys) {
+            Some(node) if !node.next.is_empty() => SequenceMatch::Partial,
+            Some(node) if node.complete => SequenceMatch::Complete,
+            _ => SequenceMatch::None,
+        }
+    }
 }
 
+/// How the keys pressed so far relate to the [`KeySequences`]
+enum SequenceMatch {
+    /// They aren't the start of any sequence
+    None,
+    /// They are the start of a longer sequence, and maybe a sequence themselves
+    Partial,
+    /// They are a sequence, which can't go any further
+    Complete,
+}
+
+/// The key that is pressed twice to go back to where the last jump started
+const QUOTE: KeyEvent = KeyEvent {
+    code: KeyCode::Char('\''),
+    modifiers: KeyModifiers::NONE,
+};
+
+/// The key sequences of [`DefaultInputClassifier`]
+static DEFAULT_SEQUENCES: Lazy<KeySequences> = Lazy::new(|| {
+    let mut sequences = KeySequences::new();
+    sequences.insert(&[QUOTE, QUOTE]);
+    sequences
+});
+
 /// The default keybindings in `minus`. These can be overriden by
 /// making a custom input handler struct and implementing the [`InputClassifier`] trait
+///
+/// A custom classifier that passes keys on to this one also has to pass on its
+/// [`key_sequences`](InputClassifier::key_sequences) and
+/// [`classify_sequence`](InputClassifier::classify_sequence) for `''` to work.
 pub struct DefaultInputClassifier;
 
 impl InputClassifier for DefaultInputClassifier {
+    fn key_sequences(&self) -> Option<&KeySequences> {
+        Some(&DEFAULT_SEQUENCES)
+    }
+
+    fn classify_sequence(&self, keys: &[KeyEvent], ps: &PagerState) -> Option<InputEvent> {
+        // Go back to where the last jump started
+        if keys == [QUOTE, QUOTE] {
+            ps.jump_history
+                .last()
+                .map(|&upper_mark| InputEvent::UpdateUpperMark(upper_mark))
+        } else {
+            None
+        }
+    }
+
     #[allow(clippy::too_many_lines)]
     fn classify_input(&self, ev: Event, ps: &PagerState) -> Option<InputEvent> {
         #[allow(clippy::unnested_or_patterns)]
         match ev {
+            // Switch line number display.
+            // This is checked first so that the toggle key can be remapped to any key
+            Event::Key(key) if key == ps.line_number_toggle_key => {
+                Some(InputEvent::UpdateLineNumber(!ps.line_numbers))
+            }
+            // Redraw the screen.
+            Event::Key(key) if key == ps.redraw_key => Some(InputEvent::Redraw),
+            // Switch line wrapping.
+            Event::Key(key) if key == ps.wrap_toggle_key => Some(InputEvent::ToggleWrap),
+            // Show the keybindings.
+            Event::Key(key) if Some(key) == ps.help_key => Some(InputEvent::ShowHelp),
+            // Quit.
+            Event::Key(key) if ps.exit_keys.contains(&key) => Some(InputEvent::Exit),
+            // Split view.
+            Event::Key(KeyEvent {
+                code: KeyCode::Tab,
+                modifiers: KeyModifiers::NONE,
+            }) if ps.split.is_some() => Some(InputEvent::SwitchPane),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('='),
+                modifiers: KeyModifiers::NONE,
+            }) if ps.split.is_some() => Some(InputEvent::ToggleScrollLock),
+            // Clear search highlights.
+            #[cfg(feature = "search")]
+            Event::Key(key) if key == ps.clear_highlights_key => Some(InputEvent::ClearHighlights),
             // Scroll up by one.
             Event::Key(KeyEvent {
                 code,
                 modifiers: KeyModifiers::NONE,
             }) if code == KeyCode::Up || code == KeyCode::Char('k') => {
-                let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
+                let position = parse_prefix(&ps.prefix_num).unwrap_or(1);
                 Some(InputEvent::UpdateUpperMark(
                     ps.upper_mark.saturating_sub(position),
                 ))
@@ -114,7 +358,7 @@ impl InputClassifier for DefaultInputClassifier {
                 code,
                 modifiers: KeyModifiers::NONE,
             }) if code == KeyCode::Down || code == KeyCode::Char('j') => {
-                let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
+                let position = parse_prefix(&ps.prefix_num).unwrap_or(1);
                 Some(InputEvent::UpdateUpperMark(
                     ps.upper_mark.saturating_add(position),
                 ))
@@ -125,41 +369,51 @@ impl InputClassifier for DefaultInputClassifier {
                 code: KeyCode::Char(c),
                 modifiers: KeyModifiers::NONE,
             }) if c.is_ascii_digit() => Some(InputEvent::Number(c)),
+            // Cancel the numeric prefix
+            Event::Key(KeyEvent {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            }) => Some(InputEvent::Cancel),
 
             // Enter key
             Event::Key(KeyEvent {
                 code: KeyCode::Enter,
                 modifiers: KeyModifiers::NONE,
             }) => {
-                if ps.message.is_some() {
-                    Some(InputEvent::RestorePrompt)
+                let position = parse_prefix(&ps.prefix_num).unwrap_or(1);
+                let upper_mark = ps.upper_mark.saturating_add(position);
+                if ps.message.is_none() {
+                    Some(InputEvent::UpdateUpperMark(upper_mark))
+                } else if ps.enter_dismiss_also_scrolls {
+                    Some(InputEvent::RestorePromptAndScroll(upper_mark))
                 } else {
-                    let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
-                    Some(InputEvent::UpdateUpperMark(
-                        ps.upper_mark.saturating_add(position),
-                    ))
+                    Some(InputEvent::RestorePrompt)
                 }
             }
 
-            // Scroll up by half screen height.
+            // Scroll up by half screen height, or by the count that was last given
             Event::Key(KeyEvent {
                 code: KeyCode::Char('u'),
                 modifiers,
             }) if modifiers == KeyModifiers::CONTROL || modifiers == KeyModifiers::NONE => {
-                let half_screen = (ps.rows / 2) as usize;
-                Some(InputEvent::UpdateUpperMark(
-                    ps.upper_mark.saturating_sub(half_screen),
-                ))
+                match parse_prefix(&ps.prefix_num) {
+                    Some(n) if n > 0 => Some(InputEvent::SetHalfPage(n, false)),
+                    _ => Some(InputEvent::UpdateUpperMark(
+                        ps.upper_mark.saturating_sub(ps.half_page_rows()),
+                    )),
+                }
             }
-            // Scroll down by half screen height.
+            // Scroll down by half screen height, or by the count that was last given
             Event::Key(KeyEvent {
                 code: KeyCode::Char('d'),
                 modifiers,
             }) if modifiers == KeyModifiers::CONTROL || modifiers == KeyModifiers::NONE => {
-                let half_screen = (ps.rows / 2) as usize;
-                Some(InputEvent::UpdateUpperMark(
-                    ps.upper_mark.saturating_add(half_screen),
-                ))
+                match parse_prefix(&ps.prefix_num) {
+                    Some(n) if n > 0 => Some(InputEvent::SetHalfPage(n, true)),
+                    _ => Some(InputEvent::UpdateUpperMark(
+                        ps.upper_mark.saturating_add(ps.half_page_rows()),
+                    )),
+                }
             }
 
             // Mouse scroll up/down
@@ -189,51 +443,77 @@ impl InputClassifier for DefaultInputClassifier {
                 code: KeyCode::Char('G'),
                 modifiers: KeyModifiers::NONE,
             }) => {
-                let mut position = ps
-                    .prefix_num
-                    .parse::<usize>()
+                let prefix = parse_prefix(&ps.prefix_num);
+                // Line numbers start from 1, so line n is past the end if n > source_line_count
+                if matches!(prefix, Some(n) if n > ps.source_line_count()) {
+                    return Some(InputEvent::ReachedEnd);
+                }
+                let mut position = prefix
                     .unwrap_or(usize::MAX)
                     // Reduce 1 here, because line numbering starts from 1
-                    // while upper_mark starts from 0
+                    // while the lines are indexed from 0
                     .saturating_sub(1);
                 if position == 0 {
                     position = usize::MAX;
+                } else if prefix.is_some() {
+                    // A line can be wrapped into several rows, go to the first of them
+                    position = ps.first_row_of(ps.text_line(position));
+                }
+                // With overscroll, the bottom would be the last line at the top of the screen, but
+                // going to the bottom should still show the entire last page
+                if ps.overscroll && position >= ps.num_lines() {
+                    position = ps.num_lines().saturating_sub(ps.page_rows());
                 }
                 Some(InputEvent::UpdateUpperMark(position))
             }
+            // Go to the previous/next paragraph.
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+            }) if (c == '{' || c == '}')
+                && (modifiers == KeyModifiers::NONE || modifiers == KeyModifiers::SHIFT) =>
+            {
+                let count = parse_prefix(&ps.prefix_num).unwrap_or(1);
+                Some(InputEvent::UpdateUpperMark(paragraph_row(
+                    ps,
+                    c == '}',
+                    count,
+                )))
+            }
 
             // Page Up/Down
             Event::Key(KeyEvent {
                 code: KeyCode::PageUp,
                 modifiers: KeyModifiers::NONE,
             }) => Some(InputEvent::UpdateUpperMark(
-                ps.upper_mark.saturating_sub(ps.rows - 1),
+                ps.upper_mark.saturating_sub(ps.page_step()),
             )),
             Event::Key(KeyEvent {
                 code: c,
                 modifiers: KeyModifiers::NONE,
             }) if c == KeyCode::PageDown || c == KeyCode::Char(' ') => Some(
-                InputEvent::UpdateUpperMark(ps.upper_mark.saturating_add(ps.rows - 1)),
+                InputEvent::UpdateUpperMark(ps.upper_mark.saturating_add(ps.page_step())),
             ),
 
+            // Copy the current line
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('y'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(InputEvent::Copy),
+
             // Resize event from the terminal.
             Event::Resize(cols, rows) => {
                 Some(InputEvent::UpdateTermArea(cols as usize, rows as usize))
             }
-            // Switch line number display.
+            // Quit or interrupt.
             Event::Key(KeyEvent {
-                code: KeyCode::Char('l'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => Some(InputEvent::UpdateLineNumber(!ps.line_numbers)),
-            // Quit.
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('q'),
-                modifiers: KeyModifiers::NONE,
-            })
-            | Event::Key(KeyEvent {
                 code: KeyCode::Char('c'),
                 modifiers: KeyModifiers::CONTROL,
-            }) => Some(InputEvent::Exit),
+            }) => match ps.ctrl_c_behavior {
+                CtrlCBehavior::Quit => Some(InputEvent::Exit),
+                CtrlCBehavior::Interrupt => Some(InputEvent::Interrupt),
+                CtrlCBehavior::Ignore => None,
+            },
             #[cfg(feature = "search")]
             Event::Key(KeyEvent {
                 code: KeyCode::Char('/'),
@@ -249,19 +529,28 @@ impl InputClassifier for DefaultInputClassifier {
                 code: KeyCode::Char('n'),
                 modifiers: KeyModifiers::NONE,
             }) => {
-                let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
+                let position = parse_prefix(&ps.prefix_num).unwrap_or(1);
                 if ps.search_mode == SearchMode::Reverse {
                     Some(InputEvent::MoveToPrevMatch(position))
                 } else {
                     Some(InputEvent::MoveToNextMatch(position))
                 }
             }
+            // N goes the opposite way of n, like in less and vim
             #[cfg(feature = "search")]
             Event::Key(KeyEvent {
                 code: KeyCode::Char('p'),
                 modifiers: KeyModifiers::NONE,
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('N'),
+                modifiers: KeyModifiers::SHIFT,
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('N'),
+                modifiers: KeyModifiers::NONE,
             }) => {
-                let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
+                let position = parse_prefix(&ps.prefix_num).unwrap_or(1);
                 if ps.search_mode == SearchMode::Reverse {
                     Some(InputEvent::MoveToNextMatch(position))
                 } else {
@@ -272,5 +561,299 @@ impl InputClassifier for DefaultInputClassifier {
         }
     }
 }
+
+/// Classify `ev` with the input classifier of `ps` and keep [`PagerState::prefix_num`] up to date
+///
+/// A number is added to the prefix, while any other input clears it, even if it isn't bound to
+/// anything. The prefix only applies to the command that directly follows it.
+///
+/// The event and how it was classified are passed to [`PagerState::input_trace`], if one is set.
+///
+/// While the list of keybindings is shown, any key closes it and mouse events are ignored, so
+/// that the hidden text isn't changed.
+pub(crate) fn classify_with_prefix(ev: Event, ps: &mut PagerState) -> Option<InputEvent> {
+    if ps.show_help {
+        match ev {
+            Event::Key(_) => return Some(InputEvent::HideHelp),
+            Event::Mouse(_) => return None,
+            Event::Resize(..) => {}
+        }
+    }
+    let input = ps.input_classifier.classify_input(ev, ps);
+    finish_classification(ev, input, ps)
+}
+
+/// Classify `ev` like [`classify_with_prefix`], taking the [`InputClassifier::key_sequences`]
+/// into account
+///
+/// A key that starts or continues a sequence is held back in [`PagerState::pending_keys`] till
+/// the sequence is complete. If the key doesn't continue the sequence, the keys held back are
+/// classified one by one before it, hence this can return several events. The keys held back are
+/// also classified first if they have timed out, see [`resolve_timed_out_keys`]. `Esc` drops the
+/// keys held back instead.
+pub(crate) fn classify_event(ev: Event, ps: &mut PagerState) -> Vec<InputEvent> {
+    let mut inputs = resolve_timed_out_keys(ps);
+    let key = match ev {
+        Event::Key(key) if !ps.show_help => key,
+        _ => {
+            inputs.extend(classify_with_prefix(ev, ps));
+            return inputs;
+        }
+    };
+    if key.code == KeyCode::Esc && !ps.pending_keys.is_empty() {
+        ps.pending_keys.clear();
+        ps.pending_keys_at = None;
+        inputs.extend(classify_with_prefix(ev, ps));
+        return inputs;
+    }
+    ps.pending_keys.push(key);
+    let sequence_match = ps
+        .input_classifier
+        .key_sequences()
+        .map_or(SequenceMatch::None, |sequences| {
+            sequences.match_keys(&ps.pending_keys)
+        });
+    match sequence_match {
+        SequenceMatch::Partial => ps.pending_keys_at = Some(Instant::now()),
+        SequenceMatch::Complete => inputs.extend(resolve_pending_keys(ps)),
+        SequenceMatch::None => {
+            ps.pending_keys.pop();
+            if ps.pending_keys.is_empty() {
+                inputs.extend(classify_with_prefix(ev, ps));
+            } else {
+                inputs.extend(resolve_pending_keys(ps));
+                // The key may start another sequence
+                inputs.extend(classify_event(ev, ps));
+            }
+        }
+    }
+    inputs
+}
+
+/// Classify the keys in [`PagerState::pending_keys`] if no key has followed them within
+/// [`PagerState::key_sequence_timeout`]
+pub(crate) fn resolve_timed_out_keys(ps: &mut PagerState) -> Vec<InputEvent> {
+    match ps.pending_keys_deadline() {
+        Some(deadline) if deadline <= Instant::now() => resolve_pending_keys(ps),
+        _ => Vec::new(),
+    }
+}
+
+/// Classify the keys in [`PagerState::pending_keys`] as a sequence if they are one, or else one
+/// by one
+fn resolve_pending_keys(ps: &mut PagerState) -> Vec<InputEvent> {
+    let keys = std::mem::take(&mut ps.pending_keys);
+    ps.pending_keys_at = None;
+    if keys.is_empty() {
+        return Vec::new();
+    }
+    if matches!(ps.input_classifier.key_sequences(), Some(sequences) if sequences.contains(&keys)) {
+        let input = ps.input_classifier.classify_sequence(&keys, ps);
+        finish_classification(Event::Key(keys[keys.len() - 1]), input, ps)
+            .into_iter()
+            .collect()
+    } else {
+        keys.into_iter()
+            .filter_map(|key| classify_with_prefix(Event::Key(key), ps))
+            .collect()
+    }
+}
+
+/// Trace the classification of `ev` and keep the numeric prefix up to date
+fn finish_classification(
+    ev: Event,
+    input: Option<InputEvent>,
+    ps: &mut PagerState,
+) -> Option<InputEvent> {
+    if let Some(trace) = &ps.input_trace {
+        trace(&ev, &input);
+    }
+    if let Some(InputEvent::Number(n)) = input {
+        // Digits past the limit are ignored
+        if ps.prefix_num.len() < ps.max_prefix_digits {
+            ps.prefix_num.push(n);
+            ps.format_prompt();
+        }
+    } else if !ps.prefix_num.is_empty() {
+        ps.prefix_num.clear();
+        ps.format_prompt();
+    }
+    input
+}
+
+/// The keybindings of [`DefaultInputClassifier`], as they are listed with [`InputEvent::ShowHelp`]
+///
+/// The keys that can be changed through the [`Pager`](crate::Pager) are listed as they are
+/// currently set. A custom [`InputClassifier::help`] can add its own keybindings to these.
+#[must_use]
+pub fn default_help(ps: &PagerState) -> Vec<(String, String)> {
+    let mut bindings = Vec::new();
+    if !ps.exit_keys.is_empty() {
+        let names: Vec<String> = ps.exit_keys.iter().map(|&key| key_name(key)).collect();
+        bindings.push((names.join("/"), "Quit the pager"));
+    }
+    bindings.extend(vec![
+        ("[n] Up/k".to_string(), "Scroll up by n lines, 1 by default"),
+        (
+            "[n] Down/j".to_string(),
+            "Scroll down by n lines, 1 by default",
+        ),
+        (
+            "[n] Enter".to_string(),
+            "Scroll down by n lines, or clear the message",
+        ),
+        ("PageUp".to_string(), "Scroll up by a page"),
+        ("PageDown/Space".to_string(), "Scroll down by a page"),
+        (
+            "[n] Ctrl+U/u".to_string(),
+            "Scroll up by half a page, or by n lines",
+        ),
+        (
+            "[n] Ctrl+D/d".to_string(),
+            "Scroll down by half a page, or by n lines",
+        ),
+        ("g".to_string(), "Go to the top"),
+        ("[n] G".to_string(), "Go to the bottom, or to line n"),
+        ("[n] {".to_string(), "Go to the previous paragraph"),
+        ("[n] }".to_string(), "Go to the next paragraph"),
+        ("''".to_string(), "Go back to where the last jump started"),
+        (key_name(ps.line_number_toggle_key), "Toggle line numbers"),
+        (key_name(ps.wrap_toggle_key), "Toggle line wrapping"),
+    ]);
+    // The redraw key only works if it isn't taken by the line number toggle
+    if ps.redraw_key != ps.line_number_toggle_key {
+        bindings.push((key_name(ps.redraw_key), "Redraw the screen"));
+    }
+    if ps.split.is_some() {
+        bindings.push(("Tab".to_string(), "Switch to the other pane"));
+        bindings.push(("=".to_string(), "Lock or unlock the scrolling of the panes"));
+    }
+    #[cfg(feature = "search")]
+    {
+        bindings.push(("/".to_string(), "Search forward"));
+        bindings.push(("?".to_string(), "Search backward"));
+        bindings.push(("[n] n".to_string(), "Go to the next match"));
+        bindings.push(("[n] p/N".to_string(), "Go to the previous match"));
+        bindings.push((
+            key_name(ps.clear_highlights_key),
+            "Clear the search highlights",
+        ));
+    }
+    if ps.clipboard != Clipboard::Disabled {
+        bindings.push(("y".to_string(), "Copy the line at the top of the screen"));
+    }
+    if let Some(key) = ps.help_key {
+        bindings.push((key_name(key), "Show this help"));
+    }
+    bindings
+        .into_iter()
+        .map(|(key, description)| (key, description.to_string()))
+        .collect()
+}
+
+/// Make the list of keybindings that is shown with [`InputEvent::ShowHelp`] from the
+/// [`InputClassifier::help`] of the current classifier
+pub(crate) fn help_text(ps: &PagerState) -> String {
+    let bindings = ps.input_classifier.help(ps);
+    let width = bindings.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    let mut text = String::new();
+    for (key, description) in bindings {
+        // Writing to a String can't fail
+        let _ = writeln!(text, "  {:width$}  {}", key, description, width = width);
+    }
+    text
+}
+
+/// Name of a key as it is shown in the list of keybindings, like `Ctrl+L`
+fn key_name(key: KeyEvent) -> String {
+    let mut name = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("Ctrl+");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("Alt+");
+    }
+    if key.modifiers.contains(KeyModifiers::SHIFT) {
+        name.push_str("Shift+");
+    }
+    match key.code {
+        // Letters with Ctrl are shown in upper case, as is customary
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            name.extend(c.to_uppercase());
+        }
+        KeyCode::Char(' ') => name.push_str("Space"),
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::F(n) => {
+            let _ = write!(name, "F{}", n);
+        }
+        code => {
+            let _ = write!(name, "{:?}", code);
+        }
+    }
+    name
+}
+
+/// Parse a numeric prefix into a count
+///
+/// Returns `None` if no prefix was typed. Prefixes too large for a `usize` saturate at
+/// `usize::MAX` instead of failing to parse.
+fn parse_prefix(prefix: &str) -> Option<usize> {
+    if prefix.is_empty() {
+        return None;
+    }
+    Some(
+        prefix
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .fold(0, |acc: usize, d| {
+                acc.saturating_mul(10).saturating_add(d as usize)
+            }),
+    )
+}
+
+/// Finds the row to which `{`/`}` should move
+///
+/// A paragraph boundary is a blank line that directly follows (when moving `forward`) or
+/// directly precedes (when moving backward) a non-blank line. The search starts from the line
+/// at the top of the screen and skips `count` boundaries. If there are not enough boundaries,
+/// this returns the first or last row of the text.
+fn paragraph_row(ps: &PagerState, forward: bool, count: usize) -> usize {
+    let current = ps.source_line(ps.upper_mark);
+    let mut blank = ps
+        .source_lines()
+        .map(|l| l.trim().is_empty())
+        .collect::<Vec<bool>>();
+    // Paragraphs are found in the order in which the lines are displayed
+    if ps.reverse_lines {
+        blank.reverse();
+    }
+    let is_boundary = |idx: usize| {
+        let neighbour = if forward {
+            idx.checked_sub(1)
+        } else {
+            Some(idx + 1)
+        };
+        blank[idx] && neighbour.and_then(|n| blank.get(n)) == Some(&false)
+    };
+    let count = count.max(1);
+
+    let target = if forward {
+        (current + 1..blank.len())
+            .filter(|&idx| is_boundary(idx))
+            .nth(count - 1)
+    } else {
+        (0..current)
+            .rev()
+            .filter(|&idx| is_boundary(idx))
+            .nth(count - 1)
+    };
+
+    match target {
+        Some(line) => ps.first_row_of(line),
+        None if forward => ps.num_lines().saturating_sub(1),
+        None => 0,
+    }
+}
+
 #[cfg(test)]
 mod tests;
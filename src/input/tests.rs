@@ -1,6 +1,9 @@
 #[cfg(feature = "search")]
 use crate::SearchMode;
-use crate::{input::InputEvent, LineNumbers, PagerState};
+use crate::{
+    input::{classify_with_prefix, InputEvent},
+    CtrlCBehavior, LineNumbers, PagerState,
+};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 
 // Just a transparent function to fix incompatiblity issues between
@@ -14,10 +17,9 @@ fn handle_input(ev: Event, p: &PagerState) -> Option<InputEvent> {
 #[test]
 #[allow(clippy::too_many_lines)]
 fn test_kb_nav() {
-    let mut pager = PagerState::new().unwrap();
+    let mut pager = PagerState::with_dimensions(5, 80);
     pager.upper_mark = 12;
     pager.line_numbers = LineNumbers::Enabled;
-    pager.rows = 5;
 
     {
         let ev = Event::Key(KeyEvent {
@@ -160,6 +162,176 @@ fn test_kb_nav() {
     }
 }
 
+#[test]
+fn test_vi_line_movement() {
+    let mut pager = PagerState::with_dimensions(5, 80);
+    pager.upper_mark = 12;
+
+    {
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('j'),
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            Some(InputEvent::UpdateUpperMark(pager.upper_mark + 1)),
+            handle_input(ev, &pager)
+        );
+    }
+
+    {
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('k'),
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            Some(InputEvent::UpdateUpperMark(pager.upper_mark - 1)),
+            handle_input(ev, &pager)
+        );
+    }
+
+    // With a numeric prefix, move by that many lines
+    pager.prefix_num = "5".to_string();
+
+    {
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('j'),
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            Some(InputEvent::UpdateUpperMark(pager.upper_mark + 5)),
+            handle_input(ev, &pager)
+        );
+    }
+
+    {
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('k'),
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            Some(InputEvent::UpdateUpperMark(pager.upper_mark - 5)),
+            handle_input(ev, &pager)
+        );
+    }
+}
+
+#[test]
+fn test_paragraph_movement() {
+    let mut pager = PagerState::with_dimensions(10, 80);
+    // The first line wraps into two rows, so every following line is one row further down
+    pager.lines = format!("{}\nb\n\nc\n\n\nd\ne\n", "a".repeat(100));
+    pager.format_lines();
+    let next = Event::Key(KeyEvent {
+        code: KeyCode::Char('}'),
+        modifiers: KeyModifiers::NONE,
+    });
+    let prev = Event::Key(KeyEvent {
+        code: KeyCode::Char('{'),
+        modifiers: KeyModifiers::SHIFT,
+    });
+
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(3)),
+        handle_input(next, &pager)
+    );
+    pager.prefix_num = "2".to_string();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(5)),
+        handle_input(next, &pager)
+    );
+    // Not enough paragraphs, stop at the last row
+    pager.prefix_num = "3".to_string();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(8)),
+        handle_input(next, &pager)
+    );
+
+    pager.upper_mark = 8;
+    pager.prefix_num = String::new();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(6)),
+        handle_input(prev, &pager)
+    );
+    pager.prefix_num = "2".to_string();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(3)),
+        handle_input(prev, &pager)
+    );
+    // Not enough paragraphs, stop at the first row
+    pager.prefix_num = "3".to_string();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        handle_input(prev, &pager)
+    );
+}
+
+#[test]
+fn goto_line_wrapped() {
+    let mut pager = PagerState::with_dimensions(10, 80);
+    // The first line wraps into two rows, so every following line is one row further down
+    pager.lines = format!("{}\nb\nc\n", "a".repeat(100));
+    pager.format_lines();
+    let goto = Event::Key(KeyEvent {
+        code: KeyCode::Char('G'),
+        modifiers: KeyModifiers::NONE,
+    });
+
+    pager.prefix_num = "3".to_string();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(3)),
+        handle_input(goto, &pager)
+    );
+    // There are 4 rows but only 3 lines
+    pager.prefix_num = "4".to_string();
+    assert_eq!(Some(InputEvent::ReachedEnd), handle_input(goto, &pager));
+
+    // The last line comes first if the lines are reversed
+    pager.reverse_lines = true;
+    pager.format_lines();
+    pager.prefix_num = "3".to_string();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        handle_input(goto, &pager)
+    );
+}
+
+#[test]
+fn test_cancel_prefix() {
+    let mut pager = PagerState::with_dimensions(10, 80);
+    let key = |c| {
+        Event::Key(KeyEvent {
+            code: c,
+            modifiers: KeyModifiers::NONE,
+        })
+    };
+
+    assert_eq!(
+        Some(InputEvent::Number('4')),
+        classify_with_prefix(key(KeyCode::Char('4')), &mut pager)
+    );
+    classify_with_prefix(key(KeyCode::Char('2')), &mut pager);
+    assert_eq!(pager.prefix_num, "42");
+
+    assert_eq!(
+        Some(InputEvent::Cancel),
+        classify_with_prefix(key(KeyCode::Esc), &mut pager)
+    );
+    assert!(pager.prefix_num.is_empty());
+
+    // G goes to the end rather than to line 42
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(usize::MAX - 1)),
+        classify_with_prefix(key(KeyCode::Char('G')), &mut pager)
+    );
+
+    // Digits past the limit are ignored
+    pager.max_prefix_digits = 3;
+    for c in "12345".chars() {
+        classify_with_prefix(key(KeyCode::Char(c)), &mut pager);
+    }
+    assert_eq!(pager.prefix_num, "123");
+}
+
 #[test]
 fn test_restore_prompt() {
     let mut pager = PagerState::new().unwrap();
@@ -175,15 +347,21 @@ fn test_restore_prompt() {
             Some(InputEvent::RestorePrompt),
             pager.input_classifier.classify_input(ev, &pager)
         );
+
+        // Clearing the message can also scroll
+        pager.enter_dismiss_also_scrolls = true;
+        assert_eq!(
+            Some(InputEvent::RestorePromptAndScroll(1)),
+            pager.input_classifier.classify_input(ev, &pager)
+        );
     }
 }
 
 #[test]
 fn test_mouse_nav() {
-    let mut pager = PagerState::new().unwrap();
+    let mut pager = PagerState::with_dimensions(5, 80);
     pager.upper_mark = 12;
     pager.line_numbers = LineNumbers::Enabled;
-    pager.rows = 5;
     {
         let ev = Event::Mouse(MouseEvent {
             kind: MouseEventKind::ScrollDown,
@@ -214,10 +392,9 @@ fn test_mouse_nav() {
 
 #[test]
 fn test_saturation() {
-    let mut pager = PagerState::new().unwrap();
+    let mut pager = PagerState::with_dimensions(5, 80);
     pager.upper_mark = 12;
     pager.line_numbers = LineNumbers::Enabled;
-    pager.rows = 5;
 
     {
         let ev = Event::Key(KeyEvent {
@@ -225,10 +402,9 @@ fn test_saturation() {
             modifiers: KeyModifiers::NONE,
         });
         // PagerState for local use
-        let mut pager = PagerState::new().unwrap();
+        let mut pager = PagerState::with_dimensions(5, 80);
         pager.upper_mark = usize::MAX;
         pager.line_numbers = LineNumbers::Enabled;
-        pager.rows = 5;
         assert_eq!(
             Some(InputEvent::UpdateUpperMark(usize::MAX)),
             handle_input(ev, &pager)
@@ -241,10 +417,9 @@ fn test_saturation() {
             modifiers: KeyModifiers::NONE,
         });
         // PagerState for local use
-        let mut pager = PagerState::new().unwrap();
+        let mut pager = PagerState::with_dimensions(5, 80);
         pager.upper_mark = usize::MIN;
         pager.line_numbers = LineNumbers::Enabled;
-        pager.rows = 5;
         assert_eq!(
             Some(InputEvent::UpdateUpperMark(usize::MIN)),
             handle_input(ev, &pager)
@@ -254,10 +429,9 @@ fn test_saturation() {
 
 #[test]
 fn test_misc_events() {
-    let mut pager = PagerState::new().unwrap();
+    let mut pager = PagerState::with_dimensions(5, 80);
     pager.upper_mark = 12;
     pager.line_numbers = LineNumbers::Enabled;
-    pager.rows = 5;
 
     {
         let ev = Event::Resize(42, 35);
@@ -303,14 +477,43 @@ fn test_misc_events() {
     }
 }
 
+#[test]
+fn test_remapped_line_number_toggle() {
+    let mut pager = PagerState::with_dimensions(5, 80);
+    pager.line_numbers = LineNumbers::Enabled;
+    pager.line_number_toggle_key = KeyEvent {
+        code: KeyCode::Char('#'),
+        modifiers: KeyModifiers::NONE,
+    };
+
+    {
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('#'),
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            Some(InputEvent::UpdateLineNumber(!pager.line_numbers)),
+            handle_input(ev, &pager)
+        );
+    }
+
+    {
+        // Ctrl+L now redraws the screen
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('l'),
+            modifiers: KeyModifiers::CONTROL,
+        });
+        assert_eq!(Some(InputEvent::Redraw), handle_input(ev, &pager));
+    }
+}
+
 #[test]
 #[allow(clippy::too_many_lines)]
 #[cfg(feature = "search")]
 fn test_search_bindings() {
-    let mut pager = PagerState::new().unwrap();
+    let mut pager = PagerState::with_dimensions(5, 80);
     pager.upper_mark = 12;
     pager.line_numbers = LineNumbers::Enabled;
-    pager.rows = 5;
 
     {
         let ev = Event::Key(KeyEvent {
@@ -354,6 +557,14 @@ fn test_search_bindings() {
         );
     }
 
+    {
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('u'),
+            modifiers: KeyModifiers::ALT,
+        });
+        assert_eq!(Some(InputEvent::ClearHighlights), handle_input(ev, &pager));
+    }
+
     {
         pager.search_mode = SearchMode::Reverse;
         // NextMatch and PrevMatch reverse search
@@ -376,3 +587,481 @@ fn test_search_bindings() {
         );
     }
 }
+
+#[test]
+#[cfg(feature = "search")]
+fn reverse_match_binding() {
+    let mut pager = PagerState::with_dimensions(5, 80);
+    pager.upper_mark = 12;
+    let reverse_event = Event::Key(KeyEvent {
+        code: KeyCode::Char('N'),
+        modifiers: KeyModifiers::SHIFT,
+    });
+
+    // N goes the opposite way of n, which follows the direction of the search
+    assert_eq!(
+        pager.input_classifier.classify_input(reverse_event, &pager),
+        Some(InputEvent::MoveToPrevMatch(1))
+    );
+    pager.search_mode = SearchMode::Reverse;
+    assert_eq!(
+        pager.input_classifier.classify_input(reverse_event, &pager),
+        Some(InputEvent::MoveToNextMatch(1))
+    );
+
+    // Some terminals send N without the shift modifier
+    let reverse_event = Event::Key(KeyEvent {
+        code: KeyCode::Char('N'),
+        modifiers: KeyModifiers::NONE,
+    });
+    pager.prefix_num = "3".to_string();
+    assert_eq!(
+        pager.input_classifier.classify_input(reverse_event, &pager),
+        Some(InputEvent::MoveToNextMatch(3))
+    );
+}
+
+#[test]
+fn test_huge_prefix() {
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char('G'),
+        modifiers: KeyModifiers::NONE,
+    });
+
+    // A count larger than usize::MAX goes to the end instead of being ignored
+    pager.prefix_num = "99999999999999999999".to_string();
+    assert_eq!(
+        Some(InputEvent::ReachedEnd),
+        pager.input_classifier.classify_input(ev, &pager)
+    );
+
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char('j'),
+        modifiers: KeyModifiers::NONE,
+    });
+    pager.upper_mark = 10;
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(usize::MAX)),
+        pager.input_classifier.classify_input(ev, &pager)
+    );
+}
+
+#[test]
+fn test_goto_past_end() {
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = "line\n".repeat(50);
+    pager.format_lines();
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char('G'),
+        modifiers: KeyModifiers::NONE,
+    });
+
+    pager.prefix_num = "50".to_string();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(49)),
+        pager.input_classifier.classify_input(ev, &pager)
+    );
+
+    pager.prefix_num = "1000".to_string();
+    assert_eq!(
+        Some(InputEvent::ReachedEnd),
+        pager.input_classifier.classify_input(ev, &pager)
+    );
+}
+
+#[test]
+fn test_page_overlap() {
+    let mut pager = PagerState::with_dimensions(11, 80);
+    pager.upper_mark = 20;
+    pager.page_overlap = 2;
+    let page_down = Event::Key(KeyEvent {
+        code: KeyCode::PageDown,
+        modifiers: KeyModifiers::NONE,
+    });
+    let page_up = Event::Key(KeyEvent {
+        code: KeyCode::PageUp,
+        modifiers: KeyModifiers::NONE,
+    });
+
+    // 10 rows of text with 2 of them overlapping
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(28)),
+        handle_input(page_down, &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(12)),
+        handle_input(page_up, &pager)
+    );
+
+    // The text still moves if the overlap is as large as the page
+    pager.page_overlap = 20;
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(21)),
+        handle_input(page_down, &pager)
+    );
+}
+
+#[test]
+fn page_down_wrapped_lines() {
+    // 4 rows of text on each page, as the prompt takes up the last row
+    let mut pager = PagerState::with_dimensions(5, 10);
+    pager.lines = "one\nthis line wraps\ntwo\nthree\nanother long line\nfour\n".to_string();
+    pager.format_lines();
+    let page_down = Event::Key(KeyEvent {
+        code: KeyCode::PageDown,
+        modifiers: KeyModifiers::NONE,
+    });
+
+    // Each page starts with the row right after the last row of the previous page, whether that
+    // row starts a line or continues a wrapped one
+    let mut screens = Vec::new();
+    for _ in 0..2 {
+        screens.push(
+            pager
+                .visible_rows()
+                .map(String::from)
+                .collect::<Vec<String>>(),
+        );
+        match handle_input(page_down, &pager) {
+            Some(InputEvent::UpdateUpperMark(um)) => pager.upper_mark = um,
+            ev => panic!("Unexpected event {:?}", ev),
+        }
+    }
+    assert_eq!(screens[0], vec!["one", "this line", "wraps", "two"]);
+    assert_eq!(screens[1], vec!["three", "another", "long line", "four"]);
+}
+
+#[test]
+fn exit_keys() {
+    let mut pager = PagerState::new().unwrap();
+    let esc = Event::Key(KeyEvent {
+        code: KeyCode::Esc,
+        modifiers: KeyModifiers::NONE,
+    });
+    let q = Event::Key(KeyEvent {
+        code: KeyCode::Char('q'),
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(Some(InputEvent::Cancel), handle_input(esc, &pager));
+    assert_eq!(Some(InputEvent::Exit), handle_input(q, &pager));
+
+    // An exit key takes the place of the default binding of the key
+    pager.exit_keys.push(KeyEvent {
+        code: KeyCode::Esc,
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(Some(InputEvent::Exit), handle_input(esc, &pager));
+    assert_eq!(Some(InputEvent::Exit), handle_input(q, &pager));
+
+    // The default can be removed
+    pager.exit_keys.remove(0);
+    assert_eq!(Some(InputEvent::Exit), handle_input(esc, &pager));
+    assert_eq!(None, handle_input(q, &pager));
+}
+
+#[test]
+fn test_split_bindings() {
+    let mut pager = PagerState::new().unwrap();
+    let tab = Event::Key(KeyEvent {
+        code: KeyCode::Tab,
+        modifiers: KeyModifiers::NONE,
+    });
+    let lock = Event::Key(KeyEvent {
+        code: KeyCode::Char('='),
+        modifiers: KeyModifiers::NONE,
+    });
+
+    // The keys do nothing without a split view
+    assert_eq!(None, handle_input(tab, &pager));
+    assert_eq!(None, handle_input(lock, &pager));
+
+    pager.split = Some(crate::state::Split {
+        other_upper_mark: 0,
+        right_focused: false,
+        scroll_lock: false,
+    });
+    assert_eq!(Some(InputEvent::SwitchPane), handle_input(tab, &pager));
+    assert_eq!(
+        Some(InputEvent::ToggleScrollLock),
+        handle_input(lock, &pager)
+    );
+}
+
+#[test]
+fn test_half_page_count() {
+    let mut pager = PagerState::with_dimensions(10, 80);
+    pager.upper_mark = 20;
+    let ctrl_d = Event::Key(KeyEvent {
+        code: KeyCode::Char('d'),
+        modifiers: KeyModifiers::CONTROL,
+    });
+    let ctrl_u = Event::Key(KeyEvent {
+        code: KeyCode::Char('u'),
+        modifiers: KeyModifiers::CONTROL,
+    });
+
+    // A count sets the amount to scroll by
+    pager.prefix_num = "3".to_string();
+    assert_eq!(
+        Some(InputEvent::SetHalfPage(3, true)),
+        handle_input(ctrl_d, &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::SetHalfPage(3, false)),
+        handle_input(ctrl_u, &pager)
+    );
+
+    // Without a count, the amount that was set last is used
+    pager.prefix_num.clear();
+    pager.half_page = Some(3);
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(23)),
+        handle_input(ctrl_d, &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(17)),
+        handle_input(ctrl_u, &pager)
+    );
+}
+
+#[test]
+fn ctrl_c_behavior() {
+    let mut pager = PagerState::with_dimensions(5, 80);
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char('c'),
+        modifiers: KeyModifiers::CONTROL,
+    });
+    assert_eq!(Some(InputEvent::Exit), handle_input(ev, &pager));
+
+    pager.ctrl_c_behavior = CtrlCBehavior::Interrupt;
+    assert_eq!(Some(InputEvent::Interrupt), handle_input(ev, &pager));
+
+    pager.ctrl_c_behavior = CtrlCBehavior::Ignore;
+    assert_eq!(None, handle_input(ev, &pager));
+    // q still quits
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char('q'),
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(Some(InputEvent::Exit), handle_input(ev, &pager));
+}
+
+#[test]
+fn help() {
+    let mut pager = PagerState::with_dimensions(5, 80);
+    let h = Event::Key(KeyEvent {
+        code: KeyCode::Char('h'),
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(
+        Some(InputEvent::ShowHelp),
+        classify_with_prefix(h, &mut pager)
+    );
+
+    // Any key closes the help, while mouse events are ignored
+    pager.show_help = true;
+    let j = Event::Key(KeyEvent {
+        code: KeyCode::Char('j'),
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(
+        Some(InputEvent::HideHelp),
+        classify_with_prefix(j, &mut pager)
+    );
+    let scroll = Event::Mouse(MouseEvent {
+        kind: MouseEventKind::ScrollDown,
+        row: 0,
+        column: 0,
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(None, classify_with_prefix(scroll, &mut pager));
+    assert_eq!(
+        Some(InputEvent::UpdateTermArea(40, 10)),
+        classify_with_prefix(Event::Resize(40, 10), &mut pager)
+    );
+
+    pager.show_help = false;
+    pager.help_key = None;
+    assert_eq!(None, classify_with_prefix(h, &mut pager));
+}
+
+#[test]
+fn jump_back() {
+    use crate::input::classify_event;
+
+    let mut pager = PagerState::with_dimensions(5, 80);
+    let quote = Event::Key(KeyEvent {
+        code: KeyCode::Char('\''),
+        modifiers: KeyModifiers::NONE,
+    });
+    let j = Event::Key(KeyEvent {
+        code: KeyCode::Char('j'),
+        modifiers: KeyModifiers::NONE,
+    });
+    // Nothing to go back to yet
+    assert!(classify_event(quote, &mut pager).is_empty());
+    assert!(classify_event(quote, &mut pager).is_empty());
+    assert_eq!(
+        classify_event(j, &mut pager),
+        vec![InputEvent::UpdateUpperMark(1)]
+    );
+
+    pager.jump_history = vec![3, 20];
+    assert!(classify_event(quote, &mut pager).is_empty());
+    assert_eq!(
+        classify_event(quote, &mut pager),
+        vec![InputEvent::UpdateUpperMark(20)]
+    );
+
+    // Both presses have to follow each other directly
+    assert!(classify_event(quote, &mut pager).is_empty());
+    assert_eq!(
+        classify_event(j, &mut pager),
+        vec![InputEvent::UpdateUpperMark(1)]
+    );
+    assert!(classify_event(quote, &mut pager).is_empty());
+    assert_eq!(pager.pending_keys.len(), 1);
+}
+
+#[test]
+fn input_trace() {
+    use std::sync::{Arc, Mutex};
+
+    let mut pager = PagerState::with_dimensions(5, 80);
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let trace_log = log.clone();
+    pager.input_trace = Some(Box::new(move |ev, input| {
+        trace_log.lock().unwrap().push((*ev, *input));
+    }));
+
+    let j = Event::Key(KeyEvent {
+        code: KeyCode::Char('j'),
+        modifiers: KeyModifiers::NONE,
+    });
+    let x = Event::Key(KeyEvent {
+        code: KeyCode::Char('x'),
+        modifiers: KeyModifiers::NONE,
+    });
+    classify_with_prefix(j, &mut pager);
+    classify_with_prefix(x, &mut pager);
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![(j, Some(InputEvent::UpdateUpperMark(1))), (x, None)]
+    );
+}
+
+#[test]
+fn key_sequences() {
+    use crate::input::{
+        classify_event, resolve_timed_out_keys, DefaultInputClassifier, InputClassifier,
+        KeySequences,
+    };
+    use std::time::{Duration, Instant};
+
+    struct SequenceClassifier(KeySequences);
+    impl InputClassifier for SequenceClassifier {
+        fn classify_input(&self, ev: Event, ps: &PagerState) -> Option<InputEvent> {
+            DefaultInputClassifier.classify_input(ev, ps)
+        }
+        fn key_sequences(&self) -> Option<&KeySequences> {
+            Some(&self.0)
+        }
+        fn classify_sequence(&self, keys: &[KeyEvent], ps: &PagerState) -> Option<InputEvent> {
+            let count = ps.prefix_num.parse().unwrap_or(1);
+            Some(InputEvent::UpdateUpperMark(keys.len() * 100 + count))
+        }
+    }
+
+    let key = |c| KeyEvent {
+        code: KeyCode::Char(c),
+        modifiers: KeyModifiers::NONE,
+    };
+    let mut sequences = KeySequences::new();
+    sequences.insert(&[key('z'), key('z')]);
+    sequences.insert(&[key('g'), key('a')]);
+    sequences.insert(&[key('g'), key('a'), key('a')]);
+    // Single keys are not sequences
+    sequences.insert(&[key('y')]);
+    assert!(sequences.contains(&[key('g'), key('a')]));
+    assert!(!sequences.contains(&[key('g')]));
+    assert!(!sequences.contains(&[key('y')]));
+
+    let mut pager = PagerState::with_dimensions(5, 80);
+    pager.upper_mark = 10;
+    pager.input_classifier = Box::new(SequenceClassifier(sequences));
+    let classify = |c, pager: &mut PagerState| classify_event(Event::Key(key(c)), pager);
+
+    assert!(classify('z', &mut pager).is_empty());
+    assert_eq!(
+        classify('z', &mut pager),
+        vec![InputEvent::UpdateUpperMark(201)]
+    );
+
+    // The count typed before the sequence applies to it and is cleared after it
+    assert_eq!(classify('5', &mut pager), vec![InputEvent::Number('5')]);
+    assert!(classify('z', &mut pager).is_empty());
+    assert_eq!(
+        classify('z', &mut pager),
+        vec![InputEvent::UpdateUpperMark(205)]
+    );
+    assert!(pager.prefix_num.is_empty());
+
+    // A key that breaks the sequence comes after the keys held back, which are classified on
+    // their own
+    assert!(classify('g', &mut pager).is_empty());
+    assert_eq!(
+        classify('j', &mut pager),
+        vec![
+            InputEvent::UpdateUpperMark(0),
+            InputEvent::UpdateUpperMark(11)
+        ]
+    );
+    // It can also start a sequence of its own
+    assert!(classify('g', &mut pager).is_empty());
+    assert_eq!(
+        classify('z', &mut pager),
+        vec![InputEvent::UpdateUpperMark(0)]
+    );
+    assert_eq!(pager.pending_keys, vec![key('z')]);
+    classify('z', &mut pager);
+
+    // The longest sequence is taken
+    classify('g', &mut pager);
+    assert!(classify('a', &mut pager).is_empty());
+    assert_eq!(
+        classify('a', &mut pager),
+        vec![InputEvent::UpdateUpperMark(301)]
+    );
+
+    // Keys held back for longer than the timeout are classified without waiting for the
+    // next key. The shorter sequence is taken if the longer one wasn't finished.
+    classify('g', &mut pager);
+    assert!(resolve_timed_out_keys(&mut pager).is_empty());
+    pager.pending_keys_at = Some(Instant::now().checked_sub(Duration::from_secs(2)).unwrap());
+    assert_eq!(
+        resolve_timed_out_keys(&mut pager),
+        vec![InputEvent::UpdateUpperMark(0)]
+    );
+    classify('g', &mut pager);
+    classify('a', &mut pager);
+    pager.pending_keys_at = Some(Instant::now().checked_sub(Duration::from_secs(2)).unwrap());
+    assert_eq!(
+        classify('j', &mut pager),
+        vec![
+            InputEvent::UpdateUpperMark(201),
+            InputEvent::UpdateUpperMark(11)
+        ]
+    );
+    assert!(pager.pending_keys.is_empty());
+
+    // Esc drops the keys held back instead of classifying them
+    classify('g', &mut pager);
+    let esc = Event::Key(KeyEvent {
+        code: KeyCode::Esc,
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(classify_event(esc, &mut pager), vec![InputEvent::Cancel]);
+    assert!(pager.pending_keys.is_empty());
+    assert_eq!(pager.pending_keys_deadline(), None);
+}
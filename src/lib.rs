@@ -1,7 +1,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
-// When no feature is active this crate is unusable but contains lots of
+// When no feature is active this crate can't start a pager but contains lots of
 // unused imports and dead code. To avoid useless warnings about this they
-// are allowed when no feature is active.
+// are allowed when no feature is active. This isn't a compile error, as the
+// features are off by default and the Pager is still useful for configuring it.
 #![cfg_attr(
     not(any(feature = "dynamic_output", feature = "static_output")),
     allow(unused_imports),
@@ -72,6 +73,11 @@
 //! * If you want a pager to display dynamic data and be configurable at runtime, enable the `dynamic_output`
 //! feature
 //! * If you want search support inside the pager, you need to enable the `search` feature
+//!
+//! At least one of `static_output` and `dynamic_output` has to be enabled. Neither of them is
+//! enabled by default, and without them minus only provides the [`Pager`] and its configuration,
+//! with no function that starts the pager. This lets libraries that only pass a [`Pager`] on to
+//! the application depend on minus without choosing how the text is displayed.
 //! ```toml
 //! [dependencies.minus]
 //! version = "^5.0"
@@ -176,25 +182,35 @@
 //!
 //! | Action            | Description                                                                                                               |
 //! |-------------------|---------------------------------------------------------------------------------------------------------------------------|
-//! | Ctrl+C/q          | Quit the pager                                                                                                            |
+//! | Ctrl+C/q          | Quit the pager. What Ctrl+C does can be changed with `Pager::set_ctrl_c_behavior`                                         |
 //! | \[n\] Arrow Up/k    | Scroll up by n number of line(s). If n is omitted, scroll up by 1 line                                                    |
 //! | \[n\] Arrow Down/j  | Scroll down by n number of line(s). If n is omitted, scroll down by 1 line                                                |
 //! | Page Up           | Scroll up by entire page                                                                                                  |
 //! | Page Down         | Scroll down by entire page                                                                                                |
 //! | \[n\] Enter         | Scroll down by n number of line(s). If n is omitted, scroll by 1 line. If there are prompt messages, this will clear them |
 //! | Space             | Scroll down by one page                                                                                                   |
-//! | Ctrl+U/u          | Scroll up by half a screen                                                                                                |
-//! | Ctrl+D/d          | Scroll down by half a screen                                                                                              |
+//! | [n] Ctrl+U/u      | Scroll up by half a screen. If n is present, scroll up by n lines, now and from then on                                   |
+//! | [n] Ctrl+D/d      | Scroll down by half a screen. If n is present, scroll down by n lines, now and from then on                               |
 //! | g                 | Go to the very top of the output                                                                                          |
-//! | \[n\] G             | Go to the very bottom of the output. If n is present, goes to that line                                                   |
+//! | \[n\] G             | Go to the very bottom of the output. If n is present, goes to that line or to the bottom if it is past the end            |
+//! | \[n\] {             | Go to the previous blank line before a paragraph. If n is present, skips n paragraphs                                     |
+//! | \[n\] }             | Go to the next blank line after a paragraph. If n is present, skips n paragraphs                                          |
+//! | ''                | Go back to where the last jump started. A jump is a move by more than a page, like `G`                                    |
 //! | Mouse scroll Up   | Scroll up by 5 lines                                                                                                      |
 //! | Mouse scroll Down | Scroll down by 5 lines                                                                                                    |
-//! | Ctrl+L            | Toggle line numbers if not forced enabled/disabled                                                                        |
+//! | Ctrl+L            | Toggle line numbers if not forced enabled/disabled. Redraws the screen if the toggle is moved to another key              |
+//! | Ctrl+W            | Toggle line wrapping. Lines that are not wrapped are cut off at the edge of the screen                                    |
+//! | Tab               | Move the focus to the other pane of a split view                                                                          |
+//! | =                 | Lock or unlock the scrolling of the panes of a split view                                                                 |
 //! | /                 | Start forward search                                                                                                      |
 //! | ?                 | Start backward search                                                                                                     |
-//! | Esc               | Cancel search input                                                                                                       |
+//! | Alt+C             | Toggle case sensitivity while typing a search query                                                                       |
+//! | Esc               | Cancel search input or the numeric prefix                                                                                 |
 //! | n                 | Go to the next search match                                                                                               |
-//! | p                 | Go to the next previous match                                                                                             |
+//! | p/N               | Go to the previous match                                                                                                  |
+//! | Esc u             | Clear the highlights of the current search                                                                                |
+//! | y                 | Copy the line at the top of the screen to the clipboard, if enabled                                                       |
+//! | h                 | Show the list of keybindings. Press any key to close it                                                                   |
 //!
 //! End-applications are free to change these bindings to better suit their needs.
 //!
@@ -216,19 +232,75 @@ mod static_pager;
 #[cfg(feature = "dynamic_output")]
 pub use dynamic_pager::dynamic_paging;
 #[cfg(feature = "static_output")]
-pub use static_pager::page_all;
+pub use static_pager::{page_all, page_all_ref};
 
 #[cfg(feature = "search")]
 pub use minus_core::search::SearchMode;
-use std::string::ToString;
+pub use minus_core::term;
+use std::{string::ToString, time::Duration};
 
 pub use error::MinusError;
-pub use pager::Pager;
+pub use pager::{Pager, PagerTemplate};
 pub use state::PagerState;
 
 /// A convenient type for `Vec<Box<dyn FnMut() + Send + Sync + 'static>>`
 pub type ExitCallbacks = Vec<Box<dyn FnMut() + Send + Sync + 'static>>;
 
+/// A convenient type for `Box<dyn Fn(&str) -> String + Send + Sync + 'static>`
+///
+/// See [`Pager::set_line_formatter`]
+pub type LineFormatter = Box<dyn Fn(&str) -> String + Send + Sync + 'static>;
+
+/// A convenient type for `Box<dyn Fn(usize, usize) -> String + Send + Sync + 'static>`
+///
+/// See [`Pager::set_line_number_formatter`]
+pub type LineNumberFormatter = Box<dyn Fn(usize, usize) -> String + Send + Sync + 'static>;
+
+/// A convenient type for `Box<dyn Fn(&str, usize) -> String + Send + Sync + 'static>`
+///
+/// See [`Pager::set_render_transform`]
+pub type RenderTransform = Box<dyn Fn(&str, usize) -> String + Send + Sync + 'static>;
+
+/// Text that the pager reads line by line instead of keeping a copy of it
+///
+/// This lets an application page a large text that it already holds, like a memory-mapped file
+/// or a rope, without copying it into the pager. Only the rows that are on the screen are kept
+/// formatted, the others are formatted again when they are scrolled to.
+///
+/// See [`Pager::set_text_source`]
+///
+/// # Example
+/// ```
+/// use minus::TextSource;
+///
+/// struct Numbers(Vec<String>);
+///
+/// impl TextSource for Numbers {
+///     fn line_count(&self) -> usize {
+///         self.0.len()
+///     }
+///
+///     fn line(&self, idx: usize) -> &str {
+///         &self.0[idx]
+///     }
+/// }
+/// ```
+pub trait TextSource {
+    /// Number of lines of the text
+    fn line_count(&self) -> usize;
+
+    /// The line at index `idx`, without the newline at its end
+    ///
+    /// This is only called for indices below [`line_count`](TextSource::line_count).
+    fn line(&self, idx: usize) -> &str;
+}
+
+/// A convenient type for `Box<dyn Fn(&crossterm::event::Event, &Option<InputEvent>) + Send + Sync + 'static>`
+///
+/// See [`Pager::set_input_trace`]
+pub type InputTrace =
+    Box<dyn Fn(&crossterm::event::Event, &Option<input::InputEvent>) + Send + Sync + 'static>;
+
 /// Behaviour that happens when the pager is exitted
 #[derive(PartialEq, Clone, Debug, Eq)]
 pub enum ExitStrategy {
@@ -248,6 +320,24 @@ pub enum ExitStrategy {
     PagerQuit,
 }
 
+/// Why a pager stopped running
+///
+/// See [`Pager::wait_for_exit`]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ExitReason {
+    /// The pager was quit. This is usually the user quitting it, but also includes the pager
+    /// quitting by itself as if the user had, like with
+    /// [`Pager::set_exit_on_disconnect`](crate::Pager::set_exit_on_disconnect).
+    Quit,
+    /// The pager stopped without being quit. For example, static output that fits on the screen
+    /// is printed without starting the pager.
+    Finished,
+    /// The pager was stopped by `Ctrl+C` with [`CtrlCBehavior::Interrupt`] and the application
+    /// survived the `SIGINT` that was raised. Applications that want to end the way a program
+    /// killed by `SIGINT` does can exit with status 130.
+    Interrupted,
+}
+
 /// Enum indicating whether to display the line numbers or not.
 ///
 /// Note that displaying line numbers may be less performant than not doing it.
@@ -296,6 +386,113 @@ impl std::ops::Not for LineNumbers {
     }
 }
 
+/// Whether to style the output with colors and other attributes
+///
+/// This covers everything that minus styles by itself, like the prompt, line numbers and search
+/// highlights. Styling that is already present in the text is written as is.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ColorOutput {
+    /// Style the output, unless the `NO_COLOR` environment variable is set to a non-empty value or
+    /// the standard output is not a terminal.
+    ///
+    /// **This is the default.**
+    Auto,
+    /// Always style the output.
+    Always,
+    /// Never style the output.
+    Never,
+}
+
+impl ColorOutput {
+    /// Returns `true` if the output should be styled
+    fn is_enabled(self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                use crossterm::tty::IsTty;
+
+                let no_color = std::env::var_os("NO_COLOR").filter(|val| !val.is_empty());
+                no_color.is_none() && std::io::stdout().is_tty()
+            }
+        }
+    }
+}
+
+/// How `y` copies the current line to the clipboard
+///
+/// See [`Pager::set_clipboard`]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Clipboard {
+    /// Ask the terminal to set the clipboard with the OSC 52 escape sequence.
+    ///
+    /// This works over SSH too, but not all terminals support it and some need it to be enabled
+    /// in their settings.
+    Osc52,
+    /// Pipe the text into the clipboard program of the platform. This is `pbcopy` on macOS,
+    /// `clip` on Windows and the first one of `wl-copy`, `xclip` or `xsel` that works
+    /// elsewhere.
+    Native,
+    /// Don't copy anything.
+    ///
+    /// **This is the default.**
+    Disabled,
+}
+
+/// What `Ctrl+C` does while the pager is running
+///
+/// The terminal is in raw mode while the pager runs, hence `Ctrl+C` does not send a `SIGINT` to
+/// the application but is handled by the pager like any other key.
+///
+/// See [`Pager::set_ctrl_c_behavior`]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum CtrlCBehavior {
+    /// Quit the pager like `q` does, following the [`ExitStrategy`].
+    ///
+    /// **This is the default.**
+    Quit,
+    /// Restore the terminal and raise `SIGINT`, as `Ctrl+C` would outside of the pager. The
+    /// signal handlers of the application run as usual. If the application survives the signal,
+    /// or on platforms without signals, the pager stops with [`ExitReason::Interrupted`] and the
+    /// application decides whether to terminate. The terminal is restored regardless of the
+    /// [`ExitStrategy`].
+    Interrupt,
+    /// Do nothing.
+    Ignore,
+}
+
+/// Where the text is scrolled to after it is replaced with [`Pager::set_text`]
+///
+/// See [`Pager::set_scroll_anchor_on_setdata`]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ScrollAnchor {
+    /// Go to the top of the new text.
+    ///
+    /// **This is the default.**
+    Top,
+    /// Stay at the same relative position, e.g. halfway through the new text if the old text
+    /// was scrolled halfway.
+    SamePercent,
+    /// Keep the line with the same line number at the top of the screen.
+    SameLine,
+}
+
+/// How a message sent with [`Pager::send_message`] is shown together with the prompt
+///
+/// See [`Pager::set_message_style`]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MessageStyle {
+    /// Show the message in place of the prompt, until the user presses `Enter`.
+    ///
+    /// **This is the default.**
+    Overlay,
+    /// Show the message after the prompt, until the user presses `Enter`.
+    Append,
+    /// Show the message in place of the prompt for the given time and then bring back the
+    /// prompt. The user can still press `Enter` to bring it back earlier.
+    Timed(Duration),
+}
+
 /// Wrap a line of string into a `Vec<String>` based on the number of columns
 pub(crate) fn wrap_str(line: &str, cols: usize) -> Vec<String> {
     textwrap::wrap(line, cols)
@@ -304,5 +501,128 @@ pub(crate) fn wrap_str(line: &str, cols: usize) -> Vec<String> {
         .collect::<Vec<String>>()
 }
 
+/// Wrap a line of string like [`wrap_str`], but leave room for `indent` at the start of every row
+/// except the first
+///
+/// The indent itself is not included in the returned rows.
+pub(crate) fn wrap_str_indented(line: &str, cols: usize, indent: &str) -> Vec<String> {
+    let options = textwrap::Options::new(cols).subsequent_indent(indent);
+    textwrap::wrap(line, options)
+        .iter()
+        .enumerate()
+        .map(|(idx, row)| {
+            if idx == 0 {
+                row.to_string()
+            } else {
+                row.strip_prefix(indent).unwrap_or(row).to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+}
+
+/// Cut a line of string at the given number of columns, for displaying it without wrapping
+///
+/// If the line is cut, all styling is reset at the end, as the escapes that would end the
+/// styling may lie in the part that was cut off. The `marker` is then added at the end to show
+/// that the line was cut. The line is cut shorter to make room for it, unless there is no room
+/// for anything but the marker, in which case it is left out.
+pub(crate) fn truncate_str(line: &str, cols: usize, marker: Option<char>) -> String {
+    use textwrap::core::{display_width, Word};
+
+    if display_width(line) <= cols {
+        return line.to_string();
+    }
+    let marker = marker
+        .map(String::from)
+        .filter(|marker| display_width(marker) < cols);
+    let width = cols - marker.as_deref().map_or(0, display_width);
+
+    let mut row = Word::from(line)
+        .break_apart(width)
+        .next()
+        .map_or_else(String::new, |w| w.to_string());
+    if line.contains('\x1b') {
+        row.push_str(&crossterm::style::Attribute::Reset.to_string());
+    }
+    if let Some(marker) = marker {
+        row.push_str(&marker);
+    }
+    row
+}
+
+/// Add the `whitespace` that was at the end of a line back to the `rows` it was wrapped into
+///
+/// The whitespace is added to the last row upto `cols` columns. If `wrap` is true, the rest is
+/// put on new rows, which are `indent` columns narrower like the continuation rows of
+/// [`wrap_str_indented`]. Otherwise whatever doesn't fit is left out.
+pub(crate) fn append_trailing_whitespace(
+    rows: &mut Vec<String>,
+    whitespace: &str,
+    cols: usize,
+    indent: usize,
+    wrap: bool,
+) {
+    if rows.is_empty() {
+        rows.push(String::new());
+    }
+    let mut chars = whitespace.chars().peekable();
+    loop {
+        let row_cols = if rows.len() > 1 {
+            cols.saturating_sub(indent).max(1)
+        } else {
+            cols.max(1)
+        };
+        let row = rows.last_mut().unwrap();
+        let room = row_cols.saturating_sub(textwrap::core::display_width(row));
+        row.extend(chars.by_ref().take(room));
+        if !wrap || chars.peek().is_none() {
+            break;
+        }
+        rows.push(String::new());
+    }
+}
+
+/// Apply the `style` to `text` and reset all styling after it
+///
+/// Unlike [`ContentStyle::apply`](crossterm::style::ContentStyle::apply), this also resets the
+/// attributes like bold after the text, so that they don't leak into whatever follows it.
+pub(crate) fn apply_style(style: crossterm::style::ContentStyle, text: &str) -> String {
+    let mut styled = style_escapes(style);
+    // Nothing to reset if there is no styling
+    if styled.is_empty() {
+        return text.to_string();
+    }
+    styled.push_str(text);
+    styled.push_str(&crossterm::style::Attribute::Reset.to_string());
+    styled
+}
+
+/// Apply the `style` to a row of text that may be styled itself
+///
+/// Any reset sequence in the row would also turn off the `style` for the rest of it, hence the
+/// `style` is applied again after each of them.
+pub(crate) fn apply_style_to_row(style: crossterm::style::ContentStyle, row: &str) -> String {
+    let escapes = style_escapes(style);
+    let reset = crossterm::style::Attribute::Reset.to_string();
+    apply_style(style, &row.replace(&reset, &(reset.clone() + &escapes)))
+}
+
+/// The escape sequences that turn on the `style`
+fn style_escapes(style: crossterm::style::ContentStyle) -> String {
+    use crossterm::style::{Attribute, SetBackgroundColor, SetForegroundColor};
+
+    let mut escapes = String::new();
+    if let Some(fg) = style.foreground_color {
+        escapes.push_str(&SetForegroundColor(fg).to_string());
+    }
+    if let Some(bg) = style.background_color {
+        escapes.push_str(&SetBackgroundColor(bg).to_string());
+    }
+    for attr in Attribute::iterator().filter(|attr| style.attributes.has(*attr)) {
+        escapes.push_str(&attr.to_string());
+    }
+    escapes
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,6 +1,20 @@
-use crate::{error::MinusError, input, minus_core::events::Event, ExitStrategy, LineNumbers};
+use crate::{
+    error::MinusError, input, minus_core::events::Event, Clipboard, ColorOutput, CtrlCBehavior,
+    ExitReason, ExitStrategy, InputTrace, LineFormatter, LineNumberFormatter, LineNumbers,
+    MessageStyle, PagerState, RenderTransform, ScrollAnchor, TextSource,
+};
 use crossbeam_channel::{Receiver, Sender};
-use std::fmt;
+use crossterm::{event::KeyEvent, style::ContentStyle};
+use parking_lot::Mutex;
+use std::{
+    borrow::Cow,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
 
 /// A pager acts as a middleman for communication between the main application
 /// and the user with the core functions of minus
@@ -13,10 +27,74 @@ use std::fmt;
 /// The receiver part of the channel is continously polled by the pager for events. Depending
 /// on the type of event that occurs, the pager will either redraw the screen or update
 /// the [PagerState](crate::state::PagerState)
+///
+/// Once the pager starts, its [PagerState](crate::state::PagerState) is shared with the
+/// [`Pager`], which lets getters like [`prompt`](Pager::prompt) read its current values.
+///
+/// Cloning a pager gives another handle to the same pager, not a new pager with the same
+/// configuration. The clones share the channel and the state, which is what lets one thread run
+/// [`dynamic_paging`](crate::dynamic_paging) while another sends text to it. To set up several
+/// pagers in the same way, use a [`PagerTemplate`].
 #[derive(Clone)]
 pub struct Pager {
     pub(crate) tx: Sender<Event>,
     pub(crate) rx: Receiver<Event>,
+    pub(crate) ps: StateSlot,
+    pub(crate) exit: Arc<ExitSignal>,
+    /// The prompt that was last set with [`Pager::set_prompt`], if any
+    prompt: Arc<Mutex<Option<String>>>,
+}
+
+/// Where the running pager shares its [`PagerState`], which is empty till the pager starts
+pub type StateSlot = Arc<Mutex<Option<Arc<Mutex<PagerState>>>>>;
+
+/// Why a pager stopped, or the message of the error it stopped with
+type ExitOutcome = Result<ExitReason, String>;
+
+/// Tells the clones of a pager when it has stopped running
+#[derive(Default)]
+pub struct ExitSignal {
+    /// Why the pager stopped, if it has, and the tasks waiting for it to stop
+    inner: Mutex<(Option<ExitOutcome>, Vec<Waker>)>,
+}
+
+impl ExitSignal {
+    /// Forget the previous run, as the pager is about to be started again
+    #[cfg(feature = "static_output")]
+    pub(crate) fn reset(&self) {
+        self.inner.lock().0 = None;
+    }
+
+    /// Record why the pager stopped and wake up everything waiting for it
+    pub(crate) fn notify(&self, res: &Result<ExitReason, MinusError>) {
+        let mut inner = self.inner.lock();
+        inner.0 = Some(
+            res.as_ref()
+                .map(|reason| *reason)
+                .map_err(ToString::to_string),
+        );
+        for waker in inner.1.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// Future returned by [`Pager::wait_for_exit`]
+struct WaitForExit(Arc<ExitSignal>);
+
+impl Future for WaitForExit {
+    type Output = Result<ExitReason, MinusError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut inner = self.0.inner.lock();
+        if let Some(res) = &inner.0 {
+            return Poll::Ready(res.clone().map_err(MinusError::Exited));
+        }
+        if !inner.1.iter().any(|waker| waker.will_wake(cx.waker())) {
+            inner.1.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
 }
 
 impl Pager {
@@ -28,8 +106,48 @@ impl Pager {
     /// ```
     #[must_use]
     pub fn new() -> Self {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        Self { tx, rx }
+        Self::from_channel(crossbeam_channel::unbounded())
+    }
+
+    /// Initialize a new pager that can hold atmost `size` pending events
+    ///
+    /// Every call to a method of [`Pager`] sends an event to the pager. Normally, any number of
+    /// events can be pending, hence a producer that pushes text faster than the pager processes
+    /// it can make the memory usage grow without bounds. With this, once `size` events are pending,
+    /// methods like [`push_str`](Pager::push_str) block until the pager has processed some of
+    /// them. This applies backpressure to the producer instead of growing memory. The user input
+    /// that the pager hasn't handled yet is held in a buffer of the same size.
+    ///
+    /// Since the calls can block, this needs some care:-
+    /// - Only use this with [`dynamic_paging`](crate::dynamic_paging) and send data from a
+    ///   different thread than the one running the pager. Sending more than `size` events before
+    ///   the pager has started, for example before calling [`page_all`](crate::page_all) on the
+    ///   same thread, blocks forever.
+    /// - Once the user quits the pager, nothing processes the events anymore. If the application
+    ///   continues running after that, like with [`ExitStrategy::PagerQuit`], the producer
+    ///   should stop sending data once the pager has quit.
+    ///
+    /// # Panics
+    /// This function panics if `size` is 0
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::with_event_buffer_size(1000);
+    /// ```
+    #[must_use]
+    pub fn with_event_buffer_size(size: usize) -> Self {
+        assert!(size > 0, "Event buffer size must be greater than 0");
+        Self::from_channel(crossbeam_channel::bounded(size))
+    }
+
+    fn from_channel((tx, rx): (Sender<Event>, Receiver<Event>)) -> Self {
+        Self {
+            tx,
+            rx,
+            ps: Arc::new(Mutex::new(None)),
+            exit: Arc::new(ExitSignal::default()),
+            prompt: Arc::new(Mutex::new(None)),
+        }
     }
 
     /// Set the output text to this `t`
@@ -38,6 +156,9 @@ impl Pager {
     /// If you want to append text, use the [`Pager::push_str`] function or the
     /// [`write!`]/[`writeln!`] macros
     ///
+    /// The new text is displayed from the top, unless set otherwise with
+    /// [`set_scroll_anchor_on_setdata`](Pager::set_scroll_anchor_on_setdata).
+    ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
     /// could not be sent to the receiver
@@ -51,6 +172,49 @@ impl Pager {
         Ok(self.tx.send(Event::SetData(s.into()))?)
     }
 
+    /// Page the lines of `source` in place of the text
+    ///
+    /// The pager reads the lines from `source` whenever it needs them instead of keeping a copy
+    /// of them, which saves memory with very large texts. Only the position of the rows that each
+    /// line wraps into and of the search matches is kept for every line.
+    ///
+    /// The source replaces the text like [`set_text`](Pager::set_text) does, and is replaced by
+    /// the text given to [`set_text`](Pager::set_text) in turn. As the pager can't add lines to
+    /// the source, appending text with [`push_str`](Pager::push_str) or the like copies the lines
+    /// of the source into the pager first. [`set_max_lines`](Pager::set_max_lines) doesn't drop
+    /// any lines of a source.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{Pager, TextSource};
+    ///
+    /// struct Lines(Vec<String>);
+    ///
+    /// impl TextSource for Lines {
+    ///     fn line_count(&self) -> usize {
+    ///         self.0.len()
+    ///     }
+    ///
+    ///     fn line(&self, idx: usize) -> &str {
+    ///         &self.0[idx]
+    ///     }
+    /// }
+    ///
+    /// let pager = Pager::new();
+    /// let lines = (1..=100_000).map(|n| format!("Line {}", n)).collect();
+    /// pager.set_text_source(Lines(lines)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_text_source(
+        &self,
+        source: impl TextSource + Send + Sync + 'static,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetTextSource(Box::new(source)))?)
+    }
+
     /// Appends text to the pager output.
     ///
     /// You can also use [`write!`]/[`writeln!`] macros to append data to the pager.
@@ -77,6 +241,64 @@ impl Pager {
         Ok(self.tx.send(Event::AppendData(s.into()))?)
     }
 
+    /// Appends many lines to the pager output at once
+    ///
+    /// Each item is appended as a line of its own, i.e followed by a newline. The lines are
+    /// joined and sent to the pager together, which is much faster than calling
+    /// [`push_str`](Pager::push_str) for every line, as the pager formats all of them in one go
+    /// and redraws the screen only once.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager
+    ///     .append_lines_iter((1..=100).map(|i| format!("Line {}", i)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn append_lines_iter<I>(&self, lines: I) -> Result<(), MinusError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let text = lines.into_iter().fold(String::new(), |mut text, line| {
+            text.push_str(&line.into());
+            text.push('\n');
+            text
+        });
+        if text.is_empty() {
+            return Ok(());
+        }
+        Ok(self.tx.send(Event::AppendData(text))?)
+    }
+
+    /// Appends raw bytes to the pager output.
+    ///
+    /// The bytes are converted to text using [`String::from_utf8_lossy`], hence any invalid UTF-8
+    /// sequence is replaced with the replacement character `�`. This lets applications pipe
+    /// arbitrary byte streams to the pager without sanitizing them first.
+    ///
+    /// Each call is converted independently. If you are reading from a stream in fixed size chunks,
+    /// a multi-byte character that gets split across two calls will show up as replacement
+    /// characters, so prefer splitting the stream on newlines if possible. Also note that control
+    /// characters present in the data are not escaped and are sent to the terminal as-is.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.append_bytes(b"Valid text and invalid \xF0\x90\x80 bytes\n").expect("Failed to send data to the pager");
+    /// ```
+    pub fn append_bytes(&self, bytes: &[u8]) -> Result<(), MinusError> {
+        self.push_str(String::from_utf8_lossy(bytes))
+    }
+
     /// Set line number configuration for the pager
     ///
     /// See [`LineNumbers`] for available options
@@ -96,36 +318,35 @@ impl Pager {
         Ok(self.tx.send(Event::SetLineNumbers(l))?)
     }
 
-    /// Set the text displayed at the bottom prompt
+    /// Set whether minus should style its output
     ///
-    /// # Panics
-    /// This function panics if the given text contains newline characters.
-    /// This is because, the pager reserves only one line for showing the prompt
-    /// and a newline will cause it to span multiple lines, breaking the display
+    /// This controls the styling that minus adds by itself, like the reverse video prompt,
+    /// line numbers and search highlights. When styling is turned off, searches still work and
+    /// the matches are enclosed in `[` and `]` instead of being highlighted. See [`ColorOutput`]
+    /// for available options.
+    ///
+    /// By default this is [`ColorOutput::Auto`], which turns off styling if the `NO_COLOR`
+    /// environment variable is set or the output is not a terminal.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
     /// could not be sent to the receiver
     ///
-    /// Example
+    /// # Example
     /// ```
-    /// use minus::Pager;
+    /// use minus::{ColorOutput, Pager};
     ///
     /// let pager = Pager::new();
-    /// pager.set_prompt("my prompt").expect("Failed to send data to the pager");
+    /// pager.set_color_output(ColorOutput::Never).expect("Failed to send data to the pager");
     /// ```
-    pub fn set_prompt(&self, text: impl Into<String>) -> Result<(), MinusError> {
-        let text = text.into();
-        assert!(!text.contains('\n'), "Prompt cannot contain newlines");
-        Ok(self.tx.send(Event::SetPrompt(text))?)
+    pub fn set_color_output(&self, co: ColorOutput) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetColorOutput(co))?)
     }
 
-    /// Display a temporary message at the prompt area
+    /// Set the style of the line numbers
     ///
-    /// # Panics
-    /// This function panics if the given text contains newline characters.
-    /// This is because, the pager reserves only one line for showing the prompt
-    /// and a newline will cause it to span multiple lines, breaking the display
+    /// The style is applied to the line number and the `.` separator following it, while the
+    /// text of the line keeps its own styling. By default line numbers are displayed in bold.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
@@ -133,92 +354,190 @@ impl Pager {
     ///
     /// # Example
     /// ```
+    /// use crossterm::style::{Attribute, Color, ContentStyle};
     /// use minus::Pager;
     ///
     /// let pager = Pager::new();
-    /// pager.send_message("An error occurred").expect("Failed to send data to the pager");
+    /// let style = ContentStyle {
+    ///     foreground_color: Some(Color::DarkGrey),
+    ///     attributes: Attribute::Dim.into(),
+    ///     ..ContentStyle::default()
+    /// };
+    /// pager.set_line_number_style(style).expect("Failed to send data to the pager");
     /// ```
-    pub fn send_message(&self, text: impl Into<String>) -> Result<(), MinusError> {
-        let text = text.into();
-        assert!(!text.contains('\n'), "Message cannot contain newlines");
-        Ok(self.tx.send(Event::SendMessage(text))?)
+    pub fn set_line_number_style(&self, style: ContentStyle) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineNumberStyle(style))?)
     }
 
-    /// Set the default exit strategy.
+    /// Highlight the spaces and tabs at the end of each line with the given style
     ///
-    /// This controls how the pager will behave when the user presses `q` or `Ctrl+C`.
-    /// See [`ExitStrategy`] for available options
+    /// This makes whitespace that would otherwise be invisible stand out, which is useful when
+    /// reviewing code or diffs. If the whitespace doesn't fit on the last row of a wrapped line,
+    /// it continues on the next rows. Search matches in the whitespace are highlighted as usual.
+    /// Whitespace that is followed by ANSI escapes is not counted as trailing. Passing `None`,
+    /// which is the default, turns the highlighting off.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
     /// could not be sent to the receiver
     ///
+    /// # Example
     /// ```
-    /// use minus::{Pager, ExitStrategy};
+    /// use crossterm::style::{Color, ContentStyle};
+    /// use minus::Pager;
     ///
     /// let pager = Pager::new();
-    /// pager.set_exit_strategy(ExitStrategy::ProcessQuit).expect("Failed to send data to the pager");
+    /// let style = ContentStyle {
+    ///     background_color: Some(Color::Red),
+    ///     ..ContentStyle::default()
+    /// };
+    /// pager.set_highlight_trailing_whitespace(Some(style)).expect("Failed to send data to the pager");
     /// ```
-    pub fn set_exit_strategy(&self, es: ExitStrategy) -> Result<(), MinusError> {
-        Ok(self.tx.send(Event::SetExitStrategy(es))?)
+    pub fn set_highlight_trailing_whitespace(
+        &self,
+        style: Option<ContentStyle>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetTrailingWhitespaceStyle(style))?)
     }
 
-    /// Set whether to display pager if there's less data than
-    /// available screen height
+    /// Set a function that renders the line numbers
     ///
-    /// When this is set to false, the pager will simply print all the lines
-    /// to the main screen and immediately quit if the number of lines to
-    /// display is less than the available columns in the terminal.
-    /// Setting this to true will cause a full pager to start and display the data
-    /// even if there is less number of lines to display than available rows.
+    /// The function is called with the line number and the number of columns that it should
+    /// take up, and returns the text that is displayed in place of the built-in `  12.` format.
+    /// This lets the application draw the gutter itself, for example with colors that alternate
+    /// from line to line. The returned text may contain ANSI escapes, but its visible width
+    /// should be exactly the given number of columns, otherwise the text is not aligned with
+    /// the rows that a wrapped line continues in. The line number style is not applied to it.
     ///
-    /// This is only available in static output mode as the size of the data is
-    /// known beforehand.
-    /// In async output the pager can receive more data anytime
+    /// The line number includes the [offset](Pager::set_line_number_offset) and the number of
+    /// columns is the same for all lines, as it fits the largest line number.
     ///
-    /// By default this is set to false
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// // Display even line numbers in blue
+    /// pager.set_line_number_formatter(Box::new(|number, width| {
+    ///     let text = format!("{:>width$}│", number, width = width - 1);
+    ///     if number % 2 == 0 {
+    ///         format!("\x1b[34m{}\x1b[0m", text)
+    ///     } else {
+    ///         text
+    ///     }
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_line_number_formatter(
+        &self,
+        formatter: LineNumberFormatter,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineNumberFormatter(formatter))?)
+    }
+
+    /// Set a function that transforms each row of the text right before it is drawn
+    ///
+    /// Unlike the [line formatter](Pager::set_line_formatter), which is applied once when the
+    /// text is added, this is called every time the screen is redrawn. Hence it can depend on
+    /// settings that change while the pager is running, for example to highlight keywords that
+    /// the user has chosen.
+    ///
+    /// The function is given a row of the screen and the index of the line of the text that the
+    /// row belongs to, counting from 0. A line that is wrapped is passed in row by row, all with
+    /// the same index. The row already contains the line number and the highlights of the search
+    /// matches. The returned text may contain ANSI escapes. It is cut off at the edge of the
+    /// screen if it is wider than that.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// // Display TODO in bold
+    /// pager.set_render_transform(Box::new(|row, _| {
+    ///     row.replace("TODO", "\x1b[1mTODO\x1b[0m")
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_render_transform(&self, transform: RenderTransform) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetRenderTransform(transform))?)
+    }
+
+    /// Set the number from which the line numbers start counting
+    ///
+    /// The first line is numbered `offset + 1`. This is useful when the text is a part of a larger
+    /// file, for example with an offset of 999, the line numbers start at 1000 and match the
+    /// line numbers in that file. The line numbers are padded to the width of the largest number,
+    /// which includes the offset. The default offset is 0.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
     /// could not be sent to the receiver
     ///
+    /// # Example
     /// ```
     /// use minus::Pager;
     ///
     /// let pager = Pager::new();
-    /// pager.set_run_no_overflow(true).expect("Failed to send data to the pager");
+    /// pager.set_line_number_offset(999).expect("Failed to send data to the pager");
     /// ```
-    #[cfg(feature = "static_output")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "static_output")))]
-    pub fn set_run_no_overflow(&self, val: bool) -> Result<(), MinusError> {
-        Ok(self.tx.send(Event::SetRunNoOverflow(val))?)
+    pub fn set_line_number_offset(&self, offset: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineNumberOffset(offset))?)
     }
 
-    /// Set a custom input classifer function.
+    /// Keep only the last `max` lines of the text
     ///
-    /// When the pager encounters a user input, it calls the input classifer with
-    /// the event and [PagerState](crate::state::PagerState) as parameters.
+    /// Once there are more lines, the oldest ones are dropped from the start of the text. This
+    /// keeps the memory bounded when paging a log that keeps growing, like `tail -f`. The
+    /// remaining lines keep their line numbers, as the dropped lines are added to the
+    /// [line number offset](Pager::set_line_number_offset). Replacing the text with
+    /// [`set_text`](Pager::set_text) starts counting the lines from the beginning again. With
+    /// `None`, which is the default, all lines are kept.
     ///
-    /// A input classifier is a type implementing the [`InputClassifier`](input::InputClassifier)
-    /// trait. It only has one required function, [`InputClassifier::classify_input`](input::InputClassifier::classify_input)
-    /// which matches user input events and maps them to a [`InputEvent`](input::InputEvent)s.
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_max_lines(Some(10_000)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_max_lines(&self, max: Option<usize>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetMaxLines(max))?)
+    }
+
+    /// Pin the first `n` lines of the text to the top of the screen
+    ///
+    /// The pinned lines are always displayed above the scrolled text, which is useful for things
+    /// like the header row of a table. Scrolling starts right after the pinned lines, so they are
+    /// never displayed twice. If the pinned lines do not fit on the screen, they are cut off so
+    /// that atleast one row is left for the rest of the text. Setting this to 0 disables it,
+    /// which is the default.
     ///
-    /// See the [`InputHandler`](input::InputClassifier) trait for information about implementing
-    /// it.
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
     /// could not be sent to the receiver
-    pub fn set_input_classifier(
-        &self,
-        handler: Box<dyn input::InputClassifier + Send + Sync>,
-    ) -> Result<(), MinusError> {
-        Ok(self.tx.send(Event::SetInputClassifier(handler))?)
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.push_str("NAME    SIZE\nfoo     10\nbar     20\n").expect("Failed to send data to the pager");
+    /// pager.set_pinned_header_lines(1).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_pinned_header_lines(&self, n: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetPinnedHeaderLines(n))?)
     }
 
-    /// Adds a function that will be called when the user quits the pager
+    /// Leave `rows` blank rows between the text and the prompt
     ///
-    /// Multiple functions can be stored for calling when the user quits. These functions
-    /// run sequentially in the order they were added
+    /// This is useful on terminals that draw a status bar over the last rows of the screen. The
+    /// padding comes out of the rows available for the text. On small terminals, it is reduced so
+    /// that atleast one row of text is still shown. The default is 0.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
@@ -228,29 +547,1687 @@ impl Pager {
     /// ```
     /// use minus::Pager;
     ///
-    /// fn hello() {
-    ///     println!("Hello");
-    /// }
+    /// let pager = Pager::new();
+    /// pager.set_prompt_bottom_padding(1).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_prompt_bottom_padding(&self, rows: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetPromptBottomPadding(rows))?)
+    }
+
+    /// Reserve `rows` rows at the bottom of the screen for the prompt
+    ///
+    /// A prompt or message that is too long for one row is wrapped into these rows instead of
+    /// being cut off. Whatever doesn't fit in them is still cut off. The search and numeric
+    /// prefix indicators are shown in the last row. The rows come out of the rows available for
+    /// the text, even while the prompt is short enough for fewer of them. On small terminals, the
+    /// rows are reduced so that atleast one row of text is still shown. The default is 1.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
     ///
     /// let pager = Pager::new();
-    /// pager.add_exit_callback(Box::new(hello)).expect("Failed to send data to the pager");
+    /// pager.set_prompt_rows(2).expect("Failed to send data to the pager");
     /// ```
-    pub fn add_exit_callback(
-        &self,
-        cb: Box<dyn FnMut() + Send + Sync + 'static>,
-    ) -> Result<(), MinusError> {
-        Ok(self.tx.send(Event::AddExitCallback(cb))?)
+    pub fn set_prompt_rows(&self, rows: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetPromptRows(rows))?)
     }
-}
 
-impl Default for Pager {
-    fn default() -> Self {
-        Self::new()
+    /// Set how `y` copies the line at the top of the screen to the clipboard
+    ///
+    /// [`Clipboard::Osc52`] works over SSH and needs nothing installed, but the terminal must
+    /// support it. [`Clipboard::Native`] uses the clipboard program of the platform instead.
+    /// Copying is disabled by default. A message at the prompt tells whether the line was
+    /// copied.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{Clipboard, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_clipboard(Clipboard::Osc52).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_clipboard(&self, clipboard: Clipboard) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetClipboard(clipboard))?)
     }
-}
 
-impl fmt::Write for Pager {
-    fn write_str(&mut self, s: &str) -> fmt::Result {
-        self.push_str(s).map_err(|_| fmt::Error)
+    /// Set what `Ctrl+C` does while the pager is running
+    ///
+    /// By default, `Ctrl+C` quits the pager just like `q`. Applications that want it to
+    /// interrupt the whole program, the way `Ctrl+C` normally does, can use
+    /// [`CtrlCBehavior::Interrupt`]. If the application survives the `SIGINT`, the pager stops
+    /// with [`ExitReason::Interrupted`]. With [`CtrlCBehavior::Ignore`], it does nothing and the user
+    /// quits with `q`.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{CtrlCBehavior, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_ctrl_c_behavior(CtrlCBehavior::Interrupt).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_ctrl_c_behavior(&self, behavior: CtrlCBehavior) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetCtrlCBehavior(behavior))?)
+    }
+
+    /// Set how messages are shown together with the prompt
+    ///
+    /// By default, a message sent with [`send_message`](Pager::send_message) replaces the prompt
+    /// until the user presses `Enter`. With [`MessageStyle::Append`], the message is shown after
+    /// the prompt instead, and with [`MessageStyle::Timed`] the prompt comes back on its own
+    /// after the given time.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{MessageStyle, Pager};
+    /// use std::time::Duration;
+    ///
+    /// let pager = Pager::new();
+    /// pager
+    ///     .set_message_style(MessageStyle::Timed(Duration::from_secs(3)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_message_style(&self, style: MessageStyle) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetMessageStyle(style))?)
+    }
+
+    /// Scroll down by a line after every `interval`
+    ///
+    /// This is useful for reading long text hands free, like a slideshow. The scrolling stops
+    /// once the bottom of the text is reached, and pauses as soon as the user presses a key or
+    /// uses the mouse. Calling this again resumes it. `None` turns it off, which is the default.
+    ///
+    /// This only moves through the text that is already there, unlike following the end of the
+    /// text as new data arrives.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let pager = minus::Pager::new();
+    /// pager.set_auto_scroll(Some(Duration::from_secs(1))).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_auto_scroll(&self, interval: Option<Duration>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetAutoScroll(interval))?)
+    }
+
+    /// Set whether long lines are wrapped
+    ///
+    /// With `false`, lines that are wider than the [wrap width](Pager::set_wrap_width) are cut
+    /// off instead, and the [truncation marker](Pager::set_truncation_marker) is displayed at
+    /// their end. The user can still toggle this with `Ctrl+W`, see
+    /// [`set_wrap_toggle_key`](Pager::set_wrap_toggle_key). Lines are wrapped by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_line_wrapping(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_line_wrapping(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineWrapping(val))?)
+    }
+
+    /// Set the number of columns at which the text is wrapped
+    ///
+    /// With `Some(n)`, the text is wrapped at `n` columns, irrespective of the width of the
+    /// terminal. This is useful for keeping the output readable on wide terminals. If the terminal
+    /// is narrower than `n` columns, the text is wrapped at the terminal width instead. With `None`,
+    /// the text is wrapped at the terminal width, which is the default.
+    ///
+    /// The columns used up by line numbers and gutter markers are counted as part of the width.
+    /// Like when the terminal is resized, the line at the top of the screen stays there.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_wrap_width(Some(80)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_wrap_width(&self, width: Option<usize>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetWrapWidth(width))?)
+    }
+
+    /// Set a character that marks the rows which continue a wrapped line
+    ///
+    /// With `Some(ch)`, `ch` is displayed at the start of every row after the first one of a
+    /// line that was wrapped, which makes it clear that the row is not a line of its own. The
+    /// character takes up a column, which is accounted for when wrapping. Lines that fit in a
+    /// single row are not marked. With `None`, which is the default, no marker is displayed.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_wrap_indicator(Some('↳')).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_wrap_indicator(&self, indicator: Option<char>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetWrapIndicator(indicator))?)
+    }
+
+    /// Set a text to display while there is no text to page
+    ///
+    /// Normally, the screen stays blank until the application sends the first text, which can
+    /// look like the application is stuck. The placeholder, for example `"Waiting for
+    /// output..."`, is displayed in the middle of the screen until then. It disappears as soon as
+    /// the first text arrives. The placeholder should fit on a single row. With `None`, the
+    /// placeholder is removed, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager
+    ///     .set_empty_placeholder(Some("Waiting for output...".to_string()))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_empty_placeholder(&self, text: Option<String>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetEmptyPlaceholder(text))?)
+    }
+
+    /// Display the lines in reverse order, i.e the last line first
+    ///
+    /// This is useful for logs, where the newest lines are the most interesting ones. Lines that
+    /// are wrapped are still read from top to bottom. All navigation and searching work on the
+    /// lines as they are displayed, so for example `g` goes to the last line of the text and `n`
+    /// moves to the next match further down the screen. Line numbers keep referring to the
+    /// original position of each line.
+    ///
+    /// When lines are appended while the top of the text is on the screen, the new lines are
+    /// displayed right away. Otherwise the screen keeps showing the same lines. Turning this on or
+    /// off keeps the line at the top of the screen there.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_reverse_lines(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_reverse_lines(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetReverseLines(val))?)
+    }
+
+    /// Display the text in two panes side by side
+    ///
+    /// Both panes show the same text but scroll independently, which is useful to compare two
+    /// parts of it. The text is wrapped to the width of a pane. `Tab` moves the focus to the other
+    /// pane and all movement keys and searches apply to the focused pane. `=` locks the panes
+    /// together, so that scrolling one of them scrolls the other one by the same amount. Pressing
+    /// it again unlocks them. The split view is turned off by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_split(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_split(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetSplit(val))?)
+    }
+
+    /// Set the style of the pane of a split view that doesn't have the focus
+    ///
+    /// This shows which pane the movement keys apply to, for example by dimming the other one.
+    /// The style is applied on top of the styling of the text. By default, both panes are
+    /// displayed alike. See [`set_split`](Pager::set_split).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::style::{Attribute, ContentStyle};
+    ///
+    /// let pager = minus::Pager::new();
+    /// let dim = ContentStyle {
+    ///     attributes: Attribute::Dim.into(),
+    ///     ..ContentStyle::default()
+    /// };
+    /// pager.set_inactive_pane_style(Some(dim)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_inactive_pane_style(&self, style: Option<ContentStyle>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetInactivePaneStyle(style))?)
+    }
+
+    /// Display a scrollbar in the last column of the terminal
+    ///
+    /// The scrollbar shows which part of the text is currently on the screen. While a search is
+    /// active, the positions of the matches are also marked on it. The text is wrapped one column
+    /// narrower to make room for the scrollbar. Nothing is drawn if the entire text fits on the
+    /// screen. The scrollbar is not displayed by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_show_scrollbar(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_show_scrollbar(&self, show: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetShowScrollbar(show))?)
+    }
+
+    /// Set the marker displayed at the end of lines that are cut off
+    ///
+    /// When line wrapping is turned off, lines that are wider than the terminal are cut off at
+    /// its right edge. The marker is displayed in the last column of such lines, so that it is
+    /// clear that some of their content is hidden. Pass `None` to cut lines off without a marker.
+    /// The default marker is `…`.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_truncation_marker(Some('>')).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_truncation_marker(&self, marker: Option<char>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetTruncationMarker(marker))?)
+    }
+
+    /// Set the key that toggles line numbers
+    ///
+    /// By default line numbers are toggled with `Ctrl+L`. Some users expect `Ctrl+L` to redraw the
+    /// screen instead, so this lets applications move the toggle to another key. The key is matched
+    /// before any other binding of the default input classifier, hence it takes priority over a
+    /// default binding for the same key.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_line_number_toggle_key(KeyEvent {
+    ///     code: KeyCode::Char('#'),
+    ///     modifiers: KeyModifiers::NONE,
+    /// }).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_line_number_toggle_key(&self, key: KeyEvent) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineNumberToggleKey(key))?)
+    }
+
+    /// Set the key that clears and redraws the entire screen
+    ///
+    /// This is useful when the terminal gets corrupted by output from other programs. By default
+    /// this is `Ctrl+L`, but since the line number toggle key takes priority and is also `Ctrl+L`
+    /// by default, the redraw key only works once one of the two is changed. See
+    /// [`Pager::set_line_number_toggle_key`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_redraw_key(KeyEvent {
+    ///     code: KeyCode::Char('r'),
+    ///     modifiers: KeyModifiers::CONTROL,
+    /// }).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_redraw_key(&self, key: KeyEvent) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetRedrawKey(key))?)
+    }
+
+    /// Set the key that switches line wrapping on or off
+    ///
+    /// When wrapping is off, every line takes up a single row and the part that doesn't fit on
+    /// the screen is cut off. The position in the text is kept when switching. By default this is
+    /// `Ctrl+W`.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_wrap_toggle_key(KeyEvent {
+    ///     code: KeyCode::Char('s'),
+    ///     modifiers: KeyModifiers::NONE,
+    /// }).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_wrap_toggle_key(&self, key: KeyEvent) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetWrapToggleKey(key))?)
+    }
+
+    /// Set the key that shows the list of keybindings
+    ///
+    /// The list is displayed in place of the text until the user presses any key, after which the
+    /// text is displayed again as it was. It lists the default keybindings, including the keys set
+    /// with functions like [`set_wrap_toggle_key`](Pager::set_wrap_toggle_key). By default this is
+    /// `h`. Pass `None` to turn the list off, for example if a custom
+    /// [input classifier](Pager::set_input_classifier) makes the list inaccurate.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_show_help_key(Some(KeyEvent {
+    ///     code: KeyCode::F(1),
+    ///     modifiers: KeyModifiers::NONE,
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_show_help_key(&self, key: Option<KeyEvent>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetHelpKey(key))?)
+    }
+
+    /// Add a key that quits the pager
+    ///
+    /// The key quits the pager just like `q` does, following the
+    /// [exit strategy](Pager::set_exit_strategy). It takes priority over the default keybindings,
+    /// except for the keys set with functions like [`set_wrap_toggle_key`](Pager::set_wrap_toggle_key). `q` is the only exit key by default,
+    /// and can be taken away with [`remove_exit_key`](Pager::remove_exit_key). `Ctrl+C` is
+    /// configured separately with [`set_ctrl_c_behavior`](Pager::set_ctrl_c_behavior).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.add_exit_key(KeyEvent {
+    ///     code: KeyCode::F(10),
+    ///     modifiers: KeyModifiers::NONE,
+    /// }).expect("Failed to send data to the pager");
+    /// ```
+    pub fn add_exit_key(&self, key: KeyEvent) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::AddExitKey(key))?)
+    }
+
+    /// Remove a key that quits the pager
+    ///
+    /// This also removes the default exit key `q`, for example to use it for something else in a
+    /// custom [input classifier](Pager::set_input_classifier). See
+    /// [`add_exit_key`](Pager::add_exit_key).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.remove_exit_key(KeyEvent {
+    ///     code: KeyCode::Char('q'),
+    ///     modifiers: KeyModifiers::NONE,
+    /// }).expect("Failed to send data to the pager");
+    /// ```
+    pub fn remove_exit_key(&self, key: KeyEvent) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::RemoveExitKey(key))?)
+    }
+
+    /// Place a marker in the gutter for the line at `line_idx`
+    ///
+    /// This is useful for showing things like diff markers or breakpoints beside the text. The
+    /// gutter is one column wide and is displayed left of the line numbers as soon as atleast one
+    /// marker is set. The marker is displayed with the given `style`. `line_idx` is the index of
+    /// the line in the text, starting from 0, so the marker stays with the same line even as more
+    /// text is appended. Setting a marker for a line that already has one replaces it.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::style::{Color, ContentStyle};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// let style = ContentStyle {
+    ///     foreground_color: Some(Color::Red),
+    ///     ..ContentStyle::default()
+    /// };
+    /// pager.set_gutter_marker(4, '●', style).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_gutter_marker(
+        &self,
+        line_idx: usize,
+        marker: char,
+        style: ContentStyle,
+    ) -> Result<(), MinusError> {
+        Ok(self
+            .tx
+            .send(Event::SetGutterMarker(line_idx, Some((marker, style))))?)
+    }
+
+    /// Remove the gutter marker set for the line at `line_idx`
+    ///
+    /// The gutter is hidden once all markers are removed.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.remove_gutter_marker(4).expect("Failed to send data to the pager");
+    /// ```
+    pub fn remove_gutter_marker(&self, line_idx: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetGutterMarker(line_idx, None))?)
+    }
+
+    /// Set the text displayed at the bottom prompt
+    ///
+    /// A message sent with [`send_message`](Pager::send_message) is shown in its place until the
+    /// user presses `Enter`, see [`set_message_style`](Pager::set_message_style).
+    ///
+    /// # Panics
+    /// This function panics if the given text contains newline characters.
+    /// This is because, the pager reserves only one line for showing the prompt
+    /// and a newline will cause it to span multiple lines, breaking the display.
+    /// Use [`set_prompt_rows`](Pager::set_prompt_rows) to give a long prompt more rows.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_prompt("my prompt").expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_prompt(&self, text: impl Into<String>) -> Result<(), MinusError> {
+        let text = text.into();
+        assert!(!text.contains('\n'), "Prompt cannot contain newlines");
+        *self.prompt.lock() = Some(text.clone());
+        Ok(self.tx.send(Event::SetPrompt(text))?)
+    }
+
+    /// Get the text displayed at the prompt
+    ///
+    /// This is the prompt set with [`set_prompt`](Pager::set_prompt), or the name of the
+    /// executable if no prompt has been set. Messages sent with
+    /// [`send_message`](Pager::send_message) are not included.
+    ///
+    /// While the pager runs, the value is read from its state, hence it only reflects the prompts
+    /// that the pager has already processed. A prompt that was set just before calling this might
+    /// not be returned yet. Before the pager has started, this is the prompt that was set last,
+    /// even though the pager hasn't processed it yet. Once the pager has stopped, the prompt from
+    /// when it stopped is returned.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_prompt("my prompt").expect("Failed to send data to the pager");
+    /// assert_eq!(pager.prompt(), "my prompt");
+    /// ```
+    #[must_use]
+    pub fn prompt(&self) -> String {
+        self.read_state(|ps| ps.prompt.clone()).unwrap_or_else(|| {
+            self.prompt
+                .lock()
+                .clone()
+                .unwrap_or_else(crate::state::default_prompt)
+        })
+    }
+
+    /// Get the index of the row that is displayed at the top of the text
+    ///
+    /// The rows count from 0 and are the rows into which the text has been wrapped. With a
+    /// pinned header, this is the first row below it. The value is kept within the text just
+    /// like when drawing it.
+    ///
+    /// Like [`prompt`](Pager::prompt), this reads the state of the running pager. Unlike it,
+    /// this returns `None` if the pager hasn't started yet.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// if let Some(upper_mark) = pager.upper_mark() {
+    ///     println!("Scrolled down by {} rows", upper_mark);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn upper_mark(&self) -> Option<usize> {
+        self.read_state(PagerState::clamped_upper_mark)
+    }
+
+    /// Get the indices of the lines that contain a match of the current search
+    ///
+    /// The indices count the lines of the text from 0, irrespective of how they are wrapped, and
+    /// are sorted in ascending order. Each line is listed once even if it has multiple matches.
+    /// This is empty if there is no active search. Together with the number of lines of the text,
+    /// this can be used to draw a minimap of the matches.
+    ///
+    /// Like [`prompt`](Pager::prompt), this reads the state of the running pager. There are no
+    /// matches before the pager has started.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// for line in pager.search_matches() {
+    ///     println!("Match on line {}", line + 1);
+    /// }
+    /// ```
+    #[must_use]
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_matches(&self) -> Vec<usize> {
+        let mut lines = self
+            .read_state(|ps| {
+                if ps.search_term.is_none() {
+                    return Vec::new();
+                }
+                ps.search_idx
+                    .iter()
+                    .map(|&row| ps.text_line(ps.source_line(row)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        // The rows are in the order in which the lines are displayed, which can be reversed
+        lines.sort_unstable();
+        lines.dedup();
+        lines
+    }
+
+    /// Check whether a search is active
+    ///
+    /// A search is active once the user submits a query and stays active until the text is
+    /// searched for something else. Clearing its highlights with
+    /// [`InputEvent::ClearHighlights`](crate::input::InputEvent::ClearHighlights) keeps it active,
+    /// so its matches can still be moved between.
+    ///
+    /// Like [`prompt`](Pager::prompt), this reads the state of the running pager. No search is
+    /// active before the pager has started.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// assert!(!pager.is_search_active());
+    /// ```
+    #[must_use]
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn is_search_active(&self) -> bool {
+        self.read_state(|ps| ps.search_term.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Get the query of the active search
+    ///
+    /// This is the pattern as entered by the user and is `None` if no search is active, see
+    /// [`is_search_active`](Pager::is_search_active).
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// if let Some(query) = pager.current_search() {
+    ///     println!("Searching for {}", query);
+    /// }
+    /// ```
+    #[must_use]
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn current_search(&self) -> Option<String> {
+        self.read_state(|ps| {
+            ps.search_term
+                .as_ref()
+                .map(|term| term.as_str().to_string())
+        })
+        .flatten()
+    }
+
+    /// Get the text that is currently displayed above the prompt
+    ///
+    /// The rows are joined with newlines and are exactly as they are displayed, that is with the
+    /// line numbers and gutter markers if they are shown, and with any styling that minus adds.
+    /// The prompt and the message log are not included. This is useful for integration tests
+    /// and for features like copying the visible text.
+    ///
+    /// Like [`prompt`](Pager::prompt), this reads the state of the running pager, hence changes
+    /// that the pager hasn't processed yet are not reflected. Unlike it, this returns `None` if
+    /// the pager hasn't started yet.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// if let Some(text) = pager.visible_text() {
+    ///     println!("{}", text);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn visible_text(&self) -> Option<String> {
+        self.read_state(|ps| ps.visible_rows().collect::<Vec<Cow<str>>>().join("\n"))
+    }
+
+    /// Run `f` on the state of the running pager, or return `None` if it hasn't started yet
+    fn read_state<T>(&self, f: impl FnOnce(&PagerState) -> T) -> Option<T> {
+        // The slot is released before the state is locked
+        let ps = self.ps.lock().clone()?;
+        let ps = ps.lock();
+        Some(f(&ps))
+    }
+
+    /// Display a temporary message at the prompt area
+    ///
+    /// If a message log is set up with [`set_message_lines`](Pager::set_message_lines), the
+    /// message is added to the log instead.
+    ///
+    /// # Panics
+    /// This function panics if the given text contains newline characters.
+    /// This is because, the pager reserves only one line for showing the prompt
+    /// and a newline will cause it to span multiple lines, breaking the display
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.send_message("An error occurred").expect("Failed to send data to the pager");
+    /// ```
+    pub fn send_message(&self, text: impl Into<String>) -> Result<(), MinusError> {
+        let text = text.into();
+        assert!(!text.contains('\n'), "Message cannot contain newlines");
+        Ok(self.tx.send(Event::SendMessage(text))?)
+    }
+
+    /// Send an input event to the pager as if the user had caused it
+    ///
+    /// The event goes past the [input classifier](Pager::set_input_classifier) straight to the
+    /// pager. This is useful for scripting demos and tests, which can then check the outcome
+    /// with [`visible_text`](Pager::visible_text) and [`upper_mark`](Pager::upper_mark) once the
+    /// pager has processed the event.
+    ///
+    /// Like any input from the user, this pauses the [auto scroll](Pager::set_auto_scroll).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::input::InputEvent;
+    ///
+    /// let pager = minus::Pager::new();
+    /// // Scroll to the tenth row
+    /// pager
+    ///     .send_input(InputEvent::UpdateUpperMark(9))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn send_input(&self, event: input::InputEvent) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::UserInput(event))?)
+    }
+
+    /// Reserve rows above the prompt for a log of messages
+    ///
+    /// Normally a message sent with [`send_message`](Pager::send_message) is shown in place of
+    /// the prompt and replaces any previous message. With this set to a non-zero value, `lines`
+    /// rows above the prompt always show the last `lines` messages instead, with the newest one at
+    /// the bottom. Older messages are dropped and the prompt is left as is. The text area shrinks
+    /// by `lines` rows to make room for the log.
+    ///
+    /// Messages longer than the terminal width are cut off. On a small terminal, the log gets
+    /// fewer rows so that at least one row of text is still visible.
+    ///
+    /// Setting this to 0 turns the log off, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_message_lines(3).expect("Failed to send data to the pager");
+    /// pager.send_message("Connected").expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_message_lines(&self, lines: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetMessageLines(lines))?)
+    }
+
+    /// Allow scrolling past the end of the text
+    ///
+    /// By default, scrolling stops once the last line reaches the bottom of the screen. With this
+    /// set to true, the text can be scrolled further until the last line is at the top of the
+    /// screen, with blank rows below it, like some text editors allow. Going to the bottom with
+    /// `G` still shows the entire last page.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_overscroll(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_overscroll(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOverscroll(val))?)
+    }
+
+    /// Set the position from where the text is displayed when the pager starts
+    ///
+    /// `upper_mark` is the index of the row that is displayed at the top of the screen, counting
+    /// the rows that wrapped lines take up. This is useful to reopen a text at the position where
+    /// the user left it. If the position lies beyond the text, the last page is displayed.
+    ///
+    /// The position is checked against the text that the pager has when it starts. In dynamic mode,
+    /// send the text before calling [`dynamic_paging`](crate::dynamic_paging), otherwise the
+    /// position ends up at the top. If this is called while the pager is running, it scrolls to the
+    /// given position.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_initial_upper_mark(120).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_initial_upper_mark(&self, upper_mark: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetUpperMark(upper_mark))?)
+    }
+
+    /// Scroll the text by `delta` rows, down if it is positive and up if it is negative
+    ///
+    /// Unlike [`set_initial_upper_mark`](Pager::set_initial_upper_mark), this moves relative to
+    /// the current position, so it doesn't need to be known. The text stops scrolling at its top
+    /// and bottom. The scroll behaves like one done by the user, for example the other pane of a
+    /// split view with locked scrolling moves along.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// // Move up by 5 rows
+    /// pager.scroll_by(-5).expect("Failed to send data to the pager");
+    /// ```
+    pub fn scroll_by(&self, delta: isize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::ScrollBy(delta))?)
+    }
+
+    /// Set the maximum number of digits that can be typed as a count before a command
+    ///
+    /// Any digits typed after the limit is reached are ignored. The default is 9 digits.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_max_prefix_digits(4).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_max_prefix_digits(&self, digits: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetMaxPrefixDigits(digits))?)
+    }
+
+    /// Set how many rows of the previous page stay on the screen when paging up or down
+    ///
+    /// By default, `PageDown` and `Space` move the text by an entire page, so that the first row
+    /// of the next page is the one right after the last row of the current page. With an overlap
+    /// of 1, the last row of the current page becomes the first row of the next page, which helps
+    /// keep track of the context. `PageUp` works the same way in the other direction. The text
+    /// always moves by atleast one row, even if the overlap is as large as the page.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_page_overlap(1).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_page_overlap(&self, rows: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetPageOverlap(rows))?)
+    }
+
+    /// Set the default exit strategy.
+    ///
+    /// This controls how the pager will behave when the user presses `q` or `Ctrl+C`.
+    /// See [`ExitStrategy`] for available options
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// ```
+    /// use minus::{Pager, ExitStrategy};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_exit_strategy(ExitStrategy::ProcessQuit).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_exit_strategy(&self, es: ExitStrategy) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetExitStrategy(es))?)
+    }
+
+    /// Set whether to display pager if there's less data than
+    /// available screen height
+    ///
+    /// When this is set to false, the pager will simply print all the lines
+    /// to the main screen and immediately quit if the number of lines to
+    /// display is less than the available columns in the terminal.
+    /// Setting this to true will cause a full pager to start and display the data
+    /// even if there is less number of lines to display than available rows.
+    ///
+    /// This is only available in static output mode as the size of the data is
+    /// known beforehand.
+    /// In async output the pager can receive more data anytime
+    ///
+    /// By default this is set to false
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_run_no_overflow(true).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "static_output")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "static_output")))]
+    pub fn set_run_no_overflow(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetRunNoOverflow(val))?)
+    }
+
+    /// Print the text inline until more than `lines` lines have been received
+    ///
+    /// If only a few lines ever arrive, starting a full screen pager is overkill. With this set,
+    /// dynamic paging starts by printing the text directly on the main screen, like a program
+    /// that isn't using a pager would. Once the text has more than `lines` lines, minus switches to
+    /// the alternate screen and starts paging as usual.
+    ///
+    /// This must be called before the pager is started, it has no effect afterwards.
+    ///
+    /// Switching the terminal mid way has some edge cases that you should be aware of:-
+    /// - The lines printed inline stay on the main screen and are still visible once the
+    ///   pager quits.
+    /// - Keys aren't handled while printing inline, hence `q` does not work. If fewer lines ever
+    ///   arrive, minus keeps waiting for more text until the application drops its [`Pager`] or
+    ///   the user interrupts the program with `Ctrl+C`.
+    /// - A line is printed only once it is terminated with a newline. Changing already printed
+    ///   text, for example with [`set_text`](Pager::set_text), does not change what has
+    ///   been printed.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_inline_until(10).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "dynamic_output")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dynamic_output")))]
+    pub fn set_inline_until(&self, lines: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetInlineUntil(lines))?)
+    }
+
+    /// Quit and print the text on the main screen if it fits there once all data has arrived
+    ///
+    /// Unlike static paging, dynamic paging does not know how much text there will be when it
+    /// starts, so it always starts in the alternate screen. With this set to true, minus checks
+    /// the text once more when the application drops every [`Pager`] that feeds the pager, which
+    /// signals that no more data will arrive. If the text fits on the screen without needing to
+    /// scroll at that point, minus leaves the alternate screen, prints the text on the main
+    /// screen and quits, just like [`page_all`](crate::page_all) does for short text. The
+    /// [`ExitStrategy`] and exit callbacks are honoured as if the user had quit.
+    ///
+    /// This is off by default. Some things to be aware of before turning it on:-
+    /// - The application must drop all of its [`Pager`]s, including the clones, for this to
+    ///   happen. A pager that is still held somewhere keeps minus paging.
+    /// - The pager is briefly visible in the alternate screen before minus quits, which can
+    ///   look like a flicker on some terminals.
+    /// - The check happens only once, when the application drops its pager. If the text doesn't
+    ///   fit at that point, minus keeps paging even if the terminal is resized later.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_exit_when_fits(true).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "dynamic_output")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dynamic_output")))]
+    pub fn set_exit_when_fits(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetExitWhenFits(val))?)
+    }
+
+    /// Quit the pager once no more data can arrive and the user has seen all of it
+    ///
+    /// minus knows that no more data can arrive when the application drops every [`Pager`] that
+    /// feeds the pager. If the end of the text is on the screen at that point, minus quits right
+    /// away, otherwise it keeps paging until the user scrolls to the end. The [`ExitStrategy`]
+    /// and exit callbacks are honoured as if the user had pressed `q`.
+    ///
+    /// This is off by default. Like with [`set_exit_when_fits`](Pager::set_exit_when_fits), the
+    /// application must drop all of its [`Pager`]s, including the clones, for this to happen.
+    /// If both are turned on and the text fits on the screen, it is printed on the main screen
+    /// as described there.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_exit_on_disconnect(true).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "dynamic_output")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dynamic_output")))]
+    pub fn set_exit_on_disconnect(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetExitOnDisconnect(val))?)
+    }
+
+    /// Set whether to dim the lines that don't contain a search match
+    ///
+    /// When this is set to true and a search is active, all lines that don't have a match for
+    /// the search query are displayed in a dim style, so that the matches stand out. Lines having
+    /// a match, including the one currently in focus, are displayed as usual.
+    ///
+    /// By default this is set to false
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_dim_non_matches(true).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_dim_non_matches(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetDimNonMatches(val))?)
+    }
+
+    /// Set the key that clears the highlights of the current search
+    ///
+    /// Clearing the highlights removes the highlighting from all matches, but keeps the current
+    /// search, so `n` and `N` still move between its matches. The scroll position stays unchanged
+    /// and the next search is highlighted again.
+    ///
+    /// By default this is `ESC u`, the same as in `less`. The terminal sends `ESC u` as `Alt+U`,
+    /// hence it is matched as a `u` key with the [`ALT`](crossterm::event::KeyModifiers::ALT)
+    /// modifier.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_clear_highlights_key(KeyEvent {
+    ///     code: KeyCode::Char('h'),
+    ///     modifiers: KeyModifiers::CONTROL,
+    /// }).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_clear_highlights_key(&self, key: KeyEvent) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetClearHighlightsKey(key))?)
+    }
+
+    /// Set a custom input classifer function.
+    ///
+    /// When the pager encounters a user input, it calls the input classifer with
+    /// the event and [PagerState](crate::state::PagerState) as parameters.
+    ///
+    /// A input classifier is a type implementing the [`InputClassifier`](input::InputClassifier)
+    /// trait. It only has one required function, [`InputClassifier::classify_input`](input::InputClassifier::classify_input)
+    /// which matches user input events and maps them to a [`InputEvent`](input::InputEvent)s.
+    ///
+    /// See the [`InputHandler`](input::InputClassifier) trait for information about implementing
+    /// it.
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    pub fn set_input_classifier(
+        &self,
+        handler: Box<dyn input::InputClassifier + Send + Sync>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetInputClassifier(handler))?)
+    }
+
+    /// Set whether the text is rewrapped when the terminal is resized
+    ///
+    /// Rewrapping the entire text can take a while if it is very large. When this is set to
+    /// false, the rows keep the width they were wrapped to instead:-
+    /// - If the terminal gets narrower, the rows are cut off at the edge of the screen and end
+    ///   with the [truncation marker](Pager::set_truncation_marker). There is no horizontal
+    ///   scrolling, so the rest of the rows can't be seen until the text is reformatted.
+    /// - If the terminal gets wider, the rows don't fill the extra columns.
+    ///
+    /// Text that is added or reformatted after the resize, for example by toggling line numbers,
+    /// is wrapped to the new width. This is set to true by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_rewrap_on_resize(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_rewrap_on_resize(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetRewrapOnResize(val))?)
+    }
+
+    /// Wait until the terminal has kept its size for `interval` before the pager adapts to it
+    ///
+    /// Dragging the edge of the terminal resizes it many times a second, and each resize
+    /// rewraps the entire text. With this, the resizes that follow each other more quickly than
+    /// `interval` are combined into one. The last size is always applied once the terminal
+    /// stops changing. A zero `interval` applies each resize right away, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let pager = minus::Pager::new();
+    /// pager
+    ///     .set_resize_debounce(Duration::from_millis(100))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_resize_debounce(&self, interval: Duration) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetResizeDebounce(interval))?)
+    }
+
+    /// Set how long to wait for the next key of a key sequence
+    ///
+    /// Once a key that starts one of the [`KeySequences`](crate::input::KeySequences) of the
+    /// input classifier is pressed, the pager waits for the rest of the sequence. If the next
+    /// key doesn't come within `timeout`, the keys pressed so far are classified on their own.
+    /// The default is one second.
+    ///
+    /// With [`set_managed_input(false)`](Pager::set_managed_input), the timeout is only checked
+    /// when the next event is passed to [`feed_crossterm_event`](Pager::feed_crossterm_event).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let pager = minus::Pager::new();
+    /// pager
+    ///     .set_key_sequence_timeout(Duration::from_millis(500))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_key_sequence_timeout(&self, timeout: Duration) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetKeySequenceTimeout(timeout))?)
+    }
+
+    /// Set whether `Enter` also scrolls down when it clears a message
+    ///
+    /// While a message is shown at the prompt, `Enter` only clears it by default and has to be
+    /// pressed again to scroll. When this is set to true, `Enter` clears the message and scrolls
+    /// down by a line, or by the count typed before it, at once.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_enter_dismiss_also_scrolls(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_enter_dismiss_also_scrolls(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetEnterDismissAlsoScrolls(val))?)
+    }
+
+    /// Set where the text is scrolled to after it is replaced with [`set_text`](Pager::set_text)
+    ///
+    /// By default, the new text is displayed from the top. With [`ScrollAnchor::SamePercent`] it
+    /// is scrolled to the same relative position as the old text, and with
+    /// [`ScrollAnchor::SameLine`] the line with the same line number stays at the top, which
+    /// suits text that is refreshed with small changes.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{Pager, ScrollAnchor};
+    ///
+    /// let pager = Pager::new();
+    /// pager
+    ///     .set_scroll_anchor_on_setdata(ScrollAnchor::SameLine)
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_scroll_anchor_on_setdata(&self, anchor: ScrollAnchor) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetScrollAnchorOnSetData(anchor))?)
+    }
+
+    /// Send several changes to the pager that are applied together
+    ///
+    /// `f` is given a pager on which the methods can be called as usual. The changes made
+    /// through it are collected and sent as a single unit once `f` returns, which the pager
+    /// applies all at once, with a single redraw of the screen. This avoids the screen flickering
+    /// through every intermediate state, for example while setting the text, the prompt and the
+    /// line numbers one after the other.
+    ///
+    /// If `f` returns an error, none of the changes are sent.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver, or any error returned by `f`
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{LineNumbers, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.batch(|pager| {
+    ///     pager.set_text("Hello\nWorld")?;
+    ///     pager.set_prompt("greeting.txt")?;
+    ///     pager.set_line_numbers(LineNumbers::Enabled)
+    /// }).expect("Failed to send data to the pager");
+    /// ```
+    pub fn batch(
+        &self,
+        f: impl FnOnce(&mut Self) -> Result<(), MinusError>,
+    ) -> Result<(), MinusError> {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut pager = Self {
+            tx,
+            rx,
+            ps: self.ps.clone(),
+            exit: self.exit.clone(),
+            prompt: self.prompt.clone(),
+        };
+        f(&mut pager)?;
+        let events = pager.rx.try_iter().collect();
+        Ok(self.tx.send(Event::Batch(events))?)
+    }
+
+    /// Set whether minus reads the user input from the terminal itself
+    ///
+    /// By default, minus reads the keyboard and mouse events from the terminal on a separate
+    /// thread. Applications that already read the terminal events in their own loop can set this
+    /// to false and pass the events to [`feed_crossterm_event`](Pager::feed_crossterm_event)
+    /// instead, as two readers would otherwise steal events from each other. The search prompt
+    /// still reads its input from the terminal.
+    ///
+    /// This only has an effect if it is called before the pager starts.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_managed_input(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_managed_input(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetManagedInput(val))?)
+    }
+
+    /// Pass a terminal event read by the application to the pager
+    ///
+    /// The event is classified by the [input classifier](Pager::set_input_classifier) just like
+    /// the events that minus reads itself and the resulting actions are sent to the pager. Events
+    /// that don't map to any action are ignored. A key that starts a key sequence is held back
+    /// till the sequence is complete. This is meant to be used together with
+    /// [`set_managed_input(false)`](Pager::set_managed_input).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::NotStarted)`](MinusError::NotStarted) if the
+    /// pager hasn't started yet, in which case the event is discarded as there is nothing for it
+    /// to act on. It will return a [`Err(MinusError::Communication)`](MinusError::Communication)
+    /// if the data could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    /// use minus::{MinusError, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// let key = Event::Key(KeyEvent {
+    ///     code: KeyCode::Char('j'),
+    ///     modifiers: KeyModifiers::NONE,
+    /// });
+    /// match pager.feed_crossterm_event(key) {
+    ///     // The pager hasn't been started here
+    ///     Ok(()) | Err(MinusError::NotStarted) => {}
+    ///     Err(e) => panic!("Failed to send data to the pager: {}", e),
+    /// }
+    /// ```
+    pub fn feed_crossterm_event(&self, ev: crossterm::event::Event) -> Result<(), MinusError> {
+        let ps = self.ps.lock().clone().ok_or(MinusError::NotStarted)?;
+        let inputs = input::classify_event(ev, &mut ps.lock());
+        for iev in inputs {
+            self.tx.send(Event::UserInput(iev))?;
+        }
+        Ok(())
+    }
+
+    /// Adds a function that will be called when the user quits the pager
+    ///
+    /// Multiple functions can be stored for calling when the user quits. These functions
+    /// run sequentially in the order they were added
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// fn hello() {
+    ///     println!("Hello");
+    /// }
+    ///
+    /// let pager = Pager::new();
+    /// pager.add_exit_callback(Box::new(hello)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn add_exit_callback(
+        &self,
+        cb: Box<dyn FnMut() + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::AddExitCallback(cb))?)
+    }
+
+    /// Set a function that reports the progress of wrapping large text
+    ///
+    /// Wrapping the text, for example after [`set_text`](Pager::set_text) or when the terminal is
+    /// resized, can take a while if the text has lots of lines. The function is called with the
+    /// number of lines wrapped so far and the total number of lines every 10,000 lines, and once
+    /// more when the wrapping has finished. This lets the application show a spinner or progress
+    /// bar meanwhile. It isn't called for text with fewer lines than that. Appended text is
+    /// wrapped on its own, so the progress of appending it counts only the appended lines.
+    ///
+    /// The function runs on the thread of the pager, so it should return quickly. Set it before
+    /// sending the text for it to be called for that text.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_wrap_progress_callback(Box::new(|done, total| {
+    ///     eprint!("\rWrapped {} of {} lines", done, total);
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_wrap_progress_callback(
+        &self,
+        cb: Box<dyn FnMut(usize, usize) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetWrapProgressCallback(cb))?)
+    }
+
+    /// Set a function to run when the user scrolls to the bottom of the text
+    ///
+    /// The function is run when the last line comes onto the screen while scrolling. This lets
+    /// the application fetch more text lazily, like the next page of a remote resource, and
+    /// append it. It runs only once while the user stays at the bottom, and again once they reach
+    /// the bottom after scrolling away from it or after more text has been added.
+    ///
+    /// The function runs on the thread of the pager, so it should return quickly. It can send the
+    /// text through a clone of the pager, or hand the fetching off to another thread.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let pager = minus::Pager::new();
+    /// // The thread that fetches the text checks this and appends the next page
+    /// let wants_more = Arc::new(AtomicBool::new(false));
+    /// let flag = wants_more.clone();
+    /// pager.set_on_reach_bottom(Box::new(move || flag.store(true, Ordering::Relaxed)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_reach_bottom(
+        &self,
+        cb: Box<dyn FnMut() + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnReachBottom(cb))?)
+    }
+
+    /// Set a function to run when the user toggles the line numbers
+    ///
+    /// The function is given the new [`LineNumbers`] setting. This lets the application save it
+    /// and restore it with [`set_line_numbers`](Pager::set_line_numbers) the next time. It isn't
+    /// run when the application changes the setting itself.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_on_line_numbers_changed(Box::new(|line_numbers| {
+    ///     eprintln!("Line numbers are now {:?}", line_numbers);
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_line_numbers_changed(
+        &self,
+        cb: Box<dyn FnMut(LineNumbers) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnLineNumbersChanged(cb))?)
+    }
+
+    /// Set a function to run when the user toggles line wrapping
+    ///
+    /// The function is given whether the lines are wrapped now. This lets the application save
+    /// the setting and restore it with [`set_line_wrapping`](Pager::set_line_wrapping) the next
+    /// time. It isn't run when the application changes the setting itself.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_on_wrap_changed(Box::new(|wrapping| {
+    ///     eprintln!("Line wrapping is now {}", if wrapping { "on" } else { "off" });
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_wrap_changed(
+        &self,
+        cb: Box<dyn FnMut(bool) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnWrapChanged(cb))?)
+    }
+
+    /// Wait for the pager to stop running
+    ///
+    /// The returned future completes once [`dynamic_paging`](crate::dynamic_paging) or
+    /// [`page_all`](crate::page_all) returns for this pager or any of its clones, and tells why
+    /// it stopped. This lets an async application run the pager on another thread and simply
+    /// `.await` it before continuing. If the pager has already stopped, it completes right away.
+    ///
+    /// If the pager stopped because of an error, the future gives a [`MinusError::Exited`] with
+    /// the message of that error. The error itself is returned by the function that ran the pager.
+    /// With [`page_all_ref`](crate::page_all_ref), it completes once the latest run has stopped.
+    ///
+    /// The future doesn't hold on to the pager. Hence the application can still drop all of its
+    /// pagers while waiting, which tells minus that no more data will arrive.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use minus::{dynamic_paging, ExitReason, MinusError, Pager};
+    /// use tokio::task::spawn_blocking;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), MinusError> {
+    ///     let pager = Pager::new();
+    ///     let exit = pager.wait_for_exit();
+    ///     let pager_task = spawn_blocking({
+    ///         let pager = pager.clone();
+    ///         move || dynamic_paging(pager)
+    ///     });
+    ///     pager.set_text("Hello")?;
+    ///
+    ///     if matches!(exit.await, Ok(ExitReason::Quit)) {
+    ///         println!("Bye");
+    ///     }
+    ///     pager_task.await.unwrap()
+    /// }
+    /// ```
+    pub fn wait_for_exit(
+        &self,
+    ) -> impl Future<Output = Result<ExitReason, MinusError>> + Send + 'static {
+        WaitForExit(self.exit.clone())
+    }
+
+    /// Set a function that transforms each line of the text before it is displayed
+    ///
+    /// The function is called with a line of the text, without its trailing newline, and returns
+    /// what should be displayed for it. This lets the application style the text with its own
+    /// renderer, for example to turn Markdown emphasis into ANSI escapes. The returned line is
+    /// what gets wrapped, searched and displayed, while copying with `y` still copies the
+    /// original line. The returned line should not contain any newlines.
+    ///
+    /// The function runs on the thread of the pager when a line is added, so it should be quick.
+    /// Its result is kept and used again whenever the text has to be rewrapped, for example when
+    /// the terminal is resized. The last line runs through it again each time it is continued
+    /// until it ends with a newline. Lines of a [`TextSource`] aren't copied into the pager, so
+    /// they run through it whenever they are formatted.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// // Display headings in bold
+    /// pager.set_line_formatter(Box::new(|line| {
+    ///     if line.starts_with('#') {
+    ///         format!("\x1b[1m{}\x1b[0m", line)
+    ///     } else {
+    ///         line.to_string()
+    ///     }
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_line_formatter(&self, formatter: LineFormatter) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineFormatter(formatter))?)
+    }
+
+    /// Set a function that is told how each input event has been classified
+    ///
+    /// The function is called with every terminal event that the pager receives and the action
+    /// that the [input classifier](Pager::set_input_classifier) has made of it, or `None` if the
+    /// event is ignored. This helps with debugging a custom keymap. Nothing is traced by default.
+    ///
+    /// The function runs on the thread that reads the input, while the state of the pager is
+    /// locked, so it should be quick and must not use the pager. Writing to the terminal would
+    /// mess up the display, so the trace should go to a file or a similar place.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let pager = minus::Pager::new();
+    /// let trace_log = log.clone();
+    /// pager.set_input_trace(Box::new(move |event, input| {
+    ///     trace_log.lock().unwrap().push(format!("{:?} => {:?}", event, input));
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_input_trace(&self, trace: InputTrace) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetInputTrace(trace))?)
+    }
+}
+
+impl Default for Pager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Write for Pager {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+/// A function that configures a [`Pager`], see [`PagerTemplate::with`]
+type Setup = Arc<dyn Fn(&Pager) -> Result<(), MinusError> + Send + Sync + 'static>;
+
+/// Configuration that can be applied to any number of pagers
+///
+/// The configuration of a [`Pager`] is sent to it as events, hence it can't be copied from one
+/// pager to another. Instead, a template keeps the functions that configure a pager and
+/// [`build`](PagerTemplate::build) runs them on a new pager each time. Every pager that is built
+/// has its own channel and state, unlike the clones of a [`Pager`].
+///
+/// Templates can be cloned and extended, for example to make variants of a common configuration.
+///
+/// # Example
+/// ```
+/// use minus::{LineNumbers, PagerTemplate};
+///
+/// let template = PagerTemplate::new()
+///     .with(|pager| pager.set_line_numbers(LineNumbers::Enabled))
+///     .with(|pager| pager.set_prompt("minus"));
+/// let errors = template
+///     .clone()
+///     .with(|pager| pager.set_prompt("Errors"));
+///
+/// let output = template.build().expect("Failed to send data to the pager");
+/// let error_output = errors.build().expect("Failed to send data to the pager");
+/// ```
+#[derive(Clone, Default)]
+pub struct PagerTemplate {
+    setup: Vec<Setup>,
+}
+
+impl PagerTemplate {
+    /// Make a template that doesn't change anything from the defaults
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a function that configures the pager
+    ///
+    /// The functions run in the order in which they are added, each time a pager is built.
+    /// Callbacks like the one for [`Pager::set_on_reach_bottom`] have to be made again in each
+    /// run, as every pager needs its own.
+    #[must_use]
+    pub fn with(
+        mut self,
+        setup: impl Fn(&Pager) -> Result<(), MinusError> + Send + Sync + 'static,
+    ) -> Self {
+        self.setup.push(Arc::new(setup));
+        self
+    }
+
+    /// Make a new pager with this configuration
+    ///
+    /// # Errors
+    /// This function returns the first error returned by the functions of the template
+    pub fn build(&self) -> Result<Pager, MinusError> {
+        let pager = Pager::new();
+        for setup in &self.setup {
+            setup(&pager)?;
+        }
+        Ok(pager)
     }
 }
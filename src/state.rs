@@ -1,23 +1,105 @@
 #[cfg(feature = "search")]
 use crate::minus_core::search::{self, SearchMode};
 use crate::{
-    error::{MinusError, TermError},
-    input, wrap_str, ExitStrategy, LineNumbers,
+    append_trailing_whitespace, apply_style,
+    error::{MinusError, SetupError},
+    input, truncate_str, wrap_str, wrap_str_indented, Clipboard, ColorOutput, CtrlCBehavior,
+    ExitStrategy, InputTrace, LineFormatter, LineNumberFormatter, LineNumbers, MessageStyle,
+    RenderTransform, ScrollAnchor, TextSource,
+};
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    style::{Attribute, ContentStyle},
+    terminal,
+    tty::IsTty,
 };
-use crossterm::{terminal, tty::IsTty};
 #[cfg(feature = "search")]
 use parking_lot::{Condvar, Mutex};
 #[cfg(feature = "search")]
 use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::io::Stdout;
 use std::{
+    borrow::Cow,
     io::stdout,
     sync::{atomic::AtomicBool, Arc},
+    time::{Duration, Instant},
 };
 
-use crate::minus_core::{ev_handler::handle_event, events::Event};
+use crate::minus_core::{ev_handler::handle_event, events::Event, term::MIN_ROWS};
 use crossbeam_channel::Receiver;
 
+/// Number of lines after which the progress of formatting the text is reported
+const WRAP_PROGRESS_INTERVAL: usize = 10_000;
+
+/// Function that is called with the number of lines formatted so far and the total number of lines
+type WrapProgressCallback = Box<dyn FnMut(usize, usize) + Send + Sync + 'static>;
+
+/// Report the progress of formatting after every [`WRAP_PROGRESS_INTERVAL`] lines
+///
+/// `idx` is the index of the line that was just formatted, out of `line_count` lines. The last
+/// line isn't reported here, since it is reported once the formatting has finished.
+fn report_wrap_progress(
+    progress: &mut Option<WrapProgressCallback>,
+    idx: usize,
+    line_count: usize,
+) {
+    // Whether the line completes another interval
+    let completes_interval = (idx + 1) / WRAP_PROGRESS_INTERVAL > idx / WRAP_PROGRESS_INTERVAL;
+    if completes_interval && idx + 1 < line_count {
+        if let Some(cb) = progress {
+            cb(idx + 1, line_count);
+        }
+    }
+}
+
+/// The prompt that is displayed if none has been set, which is the name of the executable
+pub fn default_prompt() -> String {
+    std::env::current_exe()
+        .unwrap_or_else(|_| std::path::PathBuf::from("minus"))
+        .file_name()
+        .map_or_else(
+            || std::ffi::OsString::from("minus"),
+            std::ffi::OsStr::to_os_string,
+        )
+        .into_string()
+        .unwrap_or_else(|_| String::from("minus"))
+}
+
+/// Number of positions kept in [`PagerState::jump_history`]
+const JUMP_HISTORY_LEN: usize = 16;
+
+/// The second pane of a split view
+///
+/// The focused pane always scrolls with [`PagerState::upper_mark`], hence all the movement and
+/// search functions work on it unchanged. Only the upper mark of the other pane is stored here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Split {
+    /// Upper mark of the pane that doesn't have the focus
+    pub(crate) other_upper_mark: usize,
+    /// Whether the right pane has the focus
+    pub(crate) right_focused: bool,
+    /// Whether scrolling the focused pane scrolls the other one by the same amount
+    pub(crate) scroll_lock: bool,
+}
+
+/// The rows made by [`PagerState::make_append_str`] for some appended text
+pub struct AppendProps {
+    /// The rows that should be appended to [`PagerState::formatted_lines`]
+    pub(crate) fmt_lines: Vec<String>,
+    /// Number of rows at the end of `fmt_lines` that belong to a line which isn't terminated yet
+    pub(crate) num_unterminated: usize,
+    /// Number of rows of `fmt_lines` that fit in the unfilled rows of the screen and can hence be
+    /// written directly below the text that is displayed.
+    ///
+    /// This is `None` if the text already filled the screen before the append, in which case the
+    /// new rows can only be seen by scrolling.
+    // Only the dynamic reactor writes the rows directly
+    #[allow(dead_code)]
+    pub(crate) num_appendable: Option<usize>,
+}
+
 /// Holds all information and configuration about the pager during
 /// its un time.
 ///
@@ -27,14 +109,94 @@ use crossbeam_channel::Receiver;
 ///
 /// Various fields are made public so that their values can be accessed while implementing the
 /// trait.
-#[allow(clippy::module_name_repetitions)]
+#[allow(clippy::module_name_repetitions, clippy::struct_excessive_bools)]
 pub struct PagerState {
     /// The text the pager has been told to be displayed
     pub(crate) lines: String,
     /// The output, flattened and formatted into the lines that should be displayed
     pub(crate) formatted_lines: Vec<String>,
+    /// Index of the line in [`PagerState::lines`] that each row of
+    /// [`PagerState::formatted_lines`] was formatted from
+    pub(crate) line_map: Vec<usize>,
+    /// Where the lines are read from in place of [`PagerState::lines`], if it is set. The rows of
+    /// its lines are only formatted when they are displayed, so [`PagerState::formatted_lines`]
+    /// stays empty
+    pub(crate) text_source: Option<Box<dyn TextSource + Send + Sync>>,
     /// Configuration for line numbers. See [`LineNumbers`]
     pub line_numbers: LineNumbers,
+    /// The key that toggles line numbers. This is `Ctrl+L` by default
+    pub line_number_toggle_key: KeyEvent,
+    /// The key that clears and redraws the entire screen. This is `Ctrl+L` by default, but as
+    /// [`PagerState::line_number_toggle_key`] takes priority, it only works once the line number
+    /// toggle is moved to another key
+    pub redraw_key: KeyEvent,
+    /// The key that toggles line wrapping. This is `Ctrl+W` by default
+    pub wrap_toggle_key: KeyEvent,
+    /// The key that shows the list of keybindings. This is `h` by default and `None` if the list
+    /// can't be shown
+    pub help_key: Option<KeyEvent>,
+    /// The keys that quit the pager. This is only `q` by default. `Ctrl+C` is handled separately,
+    /// see [`Pager::set_ctrl_c_behavior`](crate::Pager::set_ctrl_c_behavior)
+    pub exit_keys: Vec<KeyEvent>,
+    /// Whether the list of keybindings is displayed in place of the text
+    pub(crate) show_help: bool,
+    /// The rows of the list of keybindings, wrapped to the width of the terminal
+    pub(crate) help_rows: Vec<String>,
+    /// The prompt shown below the list of keybindings
+    pub(crate) help_prompt: String,
+    /// Whether long lines are wrapped. If this is `false`, they are cut off at the wrap width
+    pub(crate) line_wrapping: bool,
+    /// Character displayed at the end of lines that are cut off because they aren't wrapped
+    pub(crate) truncation_marker: Option<char>,
+    /// Character displayed at the start of the rows that continue a wrapped line
+    pub(crate) wrap_indicator: Option<char>,
+    /// Whether to draw a scrollbar in the last column
+    pub(crate) show_scrollbar: bool,
+    /// Text displayed in the middle of the screen while there is no text to page
+    pub(crate) empty_placeholder: Option<String>,
+    /// Whether the lines are displayed in reverse order, i.e the last line first
+    pub(crate) reverse_lines: bool,
+    /// Whether the text is rewrapped when the terminal is resized. If this is `false`, the rows
+    /// keep the width that they were wrapped to and are cut off at the edge of the screen.
+    pub(crate) rewrap_on_resize: bool,
+    /// How long the terminal has to keep its size before a resize is applied
+    pub(crate) resize_debounce: Duration,
+    /// Whether `Enter` scrolls down while it clears a message, rather than only clearing it
+    pub(crate) enter_dismiss_also_scrolls: bool,
+    /// Where the text is scrolled to after it is replaced
+    pub(crate) scroll_anchor_on_setdata: ScrollAnchor,
+    /// The text is displayed in two panes side by side if this is set
+    pub(crate) split: Option<Split>,
+    /// Style of the pane of a split view that doesn't have the focus
+    pub(crate) inactive_pane_style: Option<ContentStyle>,
+    /// Interval after which the text is scrolled down by a line
+    pub(crate) auto_scroll: Option<Duration>,
+    /// Whether the auto scroll has been paused by the user
+    pub(crate) auto_scroll_paused: bool,
+    /// The key that clears the highlights of the current search. This is `Alt+U` by default, which
+    /// is what the terminal sends for `ESC u`
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, cfg(feature = "search"))]
+    pub clear_highlights_key: KeyEvent,
+    /// Whether to style the output. See [`ColorOutput`]
+    pub(crate) color_output: ColorOutput,
+    /// Whether the output is actually styled. This is [`PagerState::color_output`] resolved
+    /// against the environment, see [`PagerState::detect_colors`]
+    pub(crate) use_colors: bool,
+    /// Style of the line numbers. The line numbers are bold by default
+    pub(crate) line_number_style: ContentStyle,
+    /// Style of the spaces and tabs at the end of each line. These are not highlighted if this
+    /// is `None`, which is the default
+    pub(crate) trailing_whitespace_style: Option<ContentStyle>,
+    /// Number added to the line numbers, the first line is numbered `line_number_offset + 1`
+    pub(crate) line_number_offset: usize,
+    /// The most lines that are kept, the oldest lines are dropped once there are more
+    pub(crate) max_lines: Option<usize>,
+    /// Number of lines that have been dropped from the start of the text because of
+    /// [`PagerState::max_lines`]
+    pub(crate) dropped_lines: usize,
+    /// Markers to display in the gutter, keyed by the index of the line in [`PagerState::lines`]
+    pub(crate) gutter_markers: HashMap<usize, (char, ContentStyle)>,
     /// Unterminated lines
     /// Keeps track of the number of lines at the last of [PagerState::formatted_lines] which are
     /// not terminated by a newline
@@ -45,6 +207,30 @@ pub struct PagerState {
     pub(crate) input_classifier: Box<dyn input::InputClassifier + Sync + Send>,
     /// Functions to run when the pager quits
     pub(crate) exit_callbacks: Vec<Box<dyn FnMut() + Send + Sync + 'static>>,
+    /// Function to report the progress of formatting the text to
+    pub(crate) wrap_progress_callback: Option<WrapProgressCallback>,
+    /// Function to run when the user scrolls to the bottom of the text
+    pub(crate) on_reach_bottom: Option<Box<dyn FnMut() + Send + Sync + 'static>>,
+    /// Function to run when the user toggles the line numbers
+    pub(crate) on_line_numbers_changed: Option<Box<dyn FnMut(LineNumbers) + Send + Sync + 'static>>,
+    /// Function to run when the user toggles line wrapping
+    pub(crate) on_wrap_changed: Option<Box<dyn FnMut(bool) + Send + Sync + 'static>>,
+    /// The number of rows of the text when [`PagerState::on_reach_bottom`] was last run, if the
+    /// user hasn't scrolled away from the bottom since
+    pub(crate) reached_bottom_at: Option<usize>,
+    /// Function that transforms each line of the text before it is wrapped
+    pub(crate) line_formatter: Option<LineFormatter>,
+    /// Lines returned by the [`PagerState::line_formatter`], by the index of their line in
+    /// [`PagerState::lines`]
+    ///
+    /// Only the terminated lines are kept, since the last line can still be continued.
+    line_formatter_cache: Vec<String>,
+    /// Function that renders the line numbers in place of the built-in format
+    pub(crate) line_number_formatter: Option<LineNumberFormatter>,
+    /// Function that transforms each row right before it is drawn on the screen
+    pub(crate) render_transform: Option<RenderTransform>,
+    /// Function that is told how each input event has been classified
+    pub(crate) input_trace: Option<InputTrace>,
     /// The behaviour to do when user quits the program using `q` or `Ctrl+C`
     /// See [`ExitStrategy`] for available options
     pub(crate) exit_strategy: ExitStrategy,
@@ -52,6 +238,39 @@ pub struct PagerState {
     /// The first element contains the actual message, while the second element tells
     /// whether the message has changed since the last display.
     pub(crate) message: Option<String>,
+    /// Number of rows reserved above the prompt for the message log. The log is disabled if
+    /// this is 0
+    pub(crate) message_lines: usize,
+    /// The most recent messages, oldest first. This holds at most
+    /// [`PagerState::message_lines`] messages
+    pub(crate) message_log: VecDeque<String>,
+    /// Whether the text can be scrolled past its end, see [`PagerState::max_upper_mark`]
+    pub(crate) overscroll: bool,
+    /// Upper marks from before the most recent jumps, i.e. moves by more than a page. The latest
+    /// one is last
+    pub(crate) jump_history: Vec<usize>,
+    /// Keys that have been held back as they are the start of one of the
+    /// [`InputClassifier::key_sequences`](input::InputClassifier::key_sequences)
+    pub(crate) pending_keys: Vec<KeyEvent>,
+    /// When the last of [`PagerState::pending_keys`] was pressed
+    pub(crate) pending_keys_at: Option<Instant>,
+    /// How long to wait for the next key of a sequence before classifying the
+    /// [`PagerState::pending_keys`] on their own. This is a second by default
+    pub(crate) key_sequence_timeout: Duration,
+    /// Number of lines at the start of the text that always stay at the top of the screen
+    pub(crate) pinned_header_lines: usize,
+    /// Number of blank rows to leave between the text and the prompt
+    pub(crate) prompt_bottom_padding: usize,
+    /// Number of rows reserved for the prompt
+    pub(crate) prompt_rows: usize,
+    /// How to copy text to the clipboard
+    pub(crate) clipboard: Clipboard,
+    /// What `Ctrl+C` does
+    pub(crate) ctrl_c_behavior: CtrlCBehavior,
+    /// Whether the pager was stopped by `Ctrl+C` with [`CtrlCBehavior::Interrupt`]
+    pub(crate) interrupted: bool,
+    /// How a message is shown together with the prompt
+    pub(crate) message_style: MessageStyle,
     /// The prompt that should be displayed to the user, formatted with the
     /// current search index and number of matches (if the search feature is enabled),
     /// and the current numbers inputted to scroll
@@ -69,9 +288,28 @@ pub struct PagerState {
     /// Do we want to page if there is no overflow
     #[cfg(feature = "static_output")]
     pub(crate) run_no_overflow: bool,
+    /// Print the text inline on the main screen until it has more than these many lines
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) inline_until: Option<usize>,
+    /// Quit and print the text on the main screen if it fits there once the application drops
+    /// its [`Pager`](crate::Pager)
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) exit_when_fits: bool,
+    /// Quit once the application drops its [`Pager`](crate::Pager) and the user has scrolled to
+    /// the end of the text
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) exit_on_disconnect: bool,
+    /// Whether minus reads the user input from the terminal itself
+    pub(crate) managed_input: bool,
     /// Stores the most recent search term
     #[cfg(feature = "search")]
     pub(crate) search_term: Option<regex::Regex>,
+    /// Whether the current search ignores case, toggled while the query is typed
+    #[cfg(feature = "search")]
+    pub(crate) search_ignore_case: bool,
+    /// Whether the matches of the search are shown without highlights, after they were cleared
+    #[cfg(feature = "search")]
+    pub(crate) highlights_hidden: bool,
     /// Direction of search
     ///
     /// See [`SearchMode`] for available options
@@ -86,18 +324,30 @@ pub struct PagerState {
     /// It should be 0 even when no search is in action
     #[cfg(feature = "search")]
     pub(crate) search_mark: usize,
+    /// Whether to dim the lines that don't have a search match while a search is active
+    #[cfg(feature = "search")]
+    pub(crate) dim_non_matches: bool,
     /// Available rows in the terminal
     pub rows: usize,
     /// Available columns in the terminal
     pub cols: usize,
+    /// Number of columns at which the text should be wrapped. If this is `None`, the text is
+    /// wrapped at the terminal width
+    pub(crate) wrap_width: Option<usize>,
     /// This variable helps in scrolling more than one line at a time
     /// It keeps track of all the numbers that have been entered by the user
     /// untill any of `j`, `k`, `G`, `Up` or `Down` is pressed
     pub prefix_num: String,
+    /// Maximum number of digits that [`PagerState::prefix_num`] can hold
+    pub(crate) max_prefix_digits: usize,
+    /// Number of rows of the previous page that remain visible after paging up or down
+    pub(crate) page_overlap: usize,
+    /// Number of rows to scroll by with `Ctrl+D`/`Ctrl+U` if a count was given to them before
+    pub(crate) half_page: Option<usize>,
 }
 
 impl PagerState {
-    pub(crate) fn new() -> Result<Self, TermError> {
+    pub(crate) fn new() -> Result<Self, SetupError> {
         let (rows, cols);
 
         if cfg!(test) {
@@ -106,7 +356,7 @@ impl PagerState {
             rows = 10;
         } else if stdout().is_tty() {
             // If a proper terminal is present, get size and set it
-            let size = terminal::size()?;
+            let size = terminal::size().map_err(|e| SetupError::TerminalSize(e.into()))?;
             cols = size.0 as usize;
             rows = size.1 as usize;
         } else {
@@ -115,46 +365,153 @@ impl PagerState {
             rows = 1;
         };
 
-        let prompt = std::env::current_exe()
-            .unwrap_or_else(|_| std::path::PathBuf::from("minus"))
-            .file_name()
-            .map_or_else(
-                || std::ffi::OsString::from("minus"),
-                std::ffi::OsStr::to_os_string,
-            )
-            .into_string()
-            .unwrap_or_else(|_| String::from("minus"));
+        Ok(Self::with_dimensions(rows, cols))
+    }
+
+    /// Create a [`PagerState`] with the given number of `rows` and `cols`
+    ///
+    /// Unlike the state created by minus when paging starts, this does not query the terminal for its
+    /// size. This makes it useful for unit tests and for embedding minus where no terminal is
+    /// available.
+    ///
+    /// # Example
+    /// ```
+    /// use minus::PagerState;
+    ///
+    /// let ps = PagerState::with_dimensions(20, 80);
+    /// assert_eq!(ps.rows, 20);
+    /// assert_eq!(ps.cols, 80);
+    /// ```
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn with_dimensions(rows: usize, cols: usize) -> Self {
+        let prompt = default_prompt();
 
         let mut state = Self {
             lines: String::with_capacity(u16::MAX.into()),
             formatted_lines: Vec::with_capacity(u16::MAX.into()),
+            line_map: Vec::with_capacity(u16::MAX.into()),
+            text_source: None,
             line_numbers: LineNumbers::Disabled,
+            line_number_toggle_key: KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            redraw_key: KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            wrap_toggle_key: KeyEvent {
+                code: KeyCode::Char('w'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            help_key: Some(KeyEvent {
+                code: KeyCode::Char('h'),
+                modifiers: KeyModifiers::NONE,
+            }),
+            exit_keys: vec![KeyEvent {
+                code: KeyCode::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }],
+            show_help: false,
+            help_rows: Vec::new(),
+            help_prompt: String::new(),
+            line_wrapping: true,
+            truncation_marker: Some('…'),
+            wrap_indicator: None,
+            show_scrollbar: false,
+            empty_placeholder: None,
+            reverse_lines: false,
+            rewrap_on_resize: true,
+            resize_debounce: Duration::ZERO,
+            enter_dismiss_also_scrolls: false,
+            scroll_anchor_on_setdata: ScrollAnchor::Top,
+            split: None,
+            inactive_pane_style: None,
+            auto_scroll: None,
+            auto_scroll_paused: false,
+            #[cfg(feature = "search")]
+            clear_highlights_key: KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::ALT,
+            },
+            color_output: ColorOutput::Auto,
+            use_colors: true,
+            line_number_style: ContentStyle {
+                attributes: Attribute::Bold.into(),
+                ..ContentStyle::default()
+            },
+            trailing_whitespace_style: None,
+            line_number_offset: 0,
+            max_lines: None,
+            dropped_lines: 0,
+            gutter_markers: HashMap::new(),
             upper_mark: 0,
             unterminated: 0,
             prompt,
             exit_strategy: ExitStrategy::ProcessQuit,
             input_classifier: Box::new(input::DefaultInputClassifier {}),
             exit_callbacks: Vec::with_capacity(5),
+            wrap_progress_callback: None,
+            on_reach_bottom: None,
+            on_line_numbers_changed: None,
+            on_wrap_changed: None,
+            reached_bottom_at: None,
+            line_formatter: None,
+            line_formatter_cache: Vec::new(),
+            line_number_formatter: None,
+            render_transform: None,
+            input_trace: None,
             message: None,
+            message_lines: 0,
+            message_log: VecDeque::new(),
+            jump_history: Vec::new(),
+            pending_keys: Vec::new(),
+            pending_keys_at: None,
+            key_sequence_timeout: Duration::from_secs(1),
+            overscroll: false,
+            pinned_header_lines: 0,
+            prompt_bottom_padding: 0,
+            prompt_rows: 1,
+            clipboard: Clipboard::Disabled,
+            ctrl_c_behavior: CtrlCBehavior::Quit,
+            interrupted: false,
+            message_style: MessageStyle::Overlay,
             displayed_prompt: String::new(),
             #[cfg(feature = "static_output")]
             run_no_overflow: false,
+            #[cfg(feature = "dynamic_output")]
+            inline_until: None,
+            #[cfg(feature = "dynamic_output")]
+            exit_when_fits: false,
+            #[cfg(feature = "dynamic_output")]
+            exit_on_disconnect: false,
+            managed_input: true,
             #[cfg(feature = "search")]
             search_term: None,
             #[cfg(feature = "search")]
+            search_ignore_case: false,
+            #[cfg(feature = "search")]
+            highlights_hidden: false,
+            #[cfg(feature = "search")]
             search_mode: SearchMode::default(),
             #[cfg(feature = "search")]
             search_idx: BTreeSet::new(),
             #[cfg(feature = "search")]
             search_mark: 0,
-            // Just to be safe in tests, keep at 1x1 size
+            #[cfg(feature = "search")]
+            dim_non_matches: false,
             cols,
             rows,
+            wrap_width: None,
             prefix_num: String::new(),
+            max_prefix_digits: 9,
+            page_overlap: 0,
+            half_page: None,
         };
 
         state.format_prompt();
-        Ok(state)
+        state
     }
 
     /// Generate the initial [`PagerState`]
@@ -169,7 +526,7 @@ impl PagerState {
     /// This function will return an error if it could not create the default [`PagerState`] or fails
     /// to process the events
     pub fn generate_initial_state(
-        rx: &mut Receiver<Event>,
+        rx: &Receiver<Event>,
         mut out: &mut Stdout,
     ) -> Result<Self, MinusError> {
         let mut ps = Self::new()?;
@@ -186,8 +543,351 @@ impl PagerState {
         Ok(ps)
     }
 
+    /// Resolve [`PagerState::color_output`] and reformat the text and prompt if that changes
+    /// whether the output is styled
+    pub(crate) fn detect_colors(&mut self) {
+        let use_colors = self.color_output.is_enabled();
+        if use_colors != self.use_colors {
+            self.use_colors = use_colors;
+            self.format_lines();
+            self.format_prompt();
+        }
+    }
+
+    /// Number of rows in [`PagerState::formatted_lines`]
+    ///
+    /// This counts the rows that the lines are wrapped into, which is what the upper mark and
+    /// the rows of the screen are measured in. See [`PagerState::source_line_count`] for the
+    /// number of lines of the text. With a [`PagerState::text_source`], the rows aren't kept, so
+    /// they are counted in [`PagerState::line_map`] instead.
     pub(crate) fn num_lines(&self) -> usize {
-        self.formatted_lines.len()
+        if self.text_source.is_some() {
+            self.line_map.len()
+        } else {
+            self.formatted_lines.len()
+        }
+    }
+
+    /// Number of lines in [`PagerState::lines`], irrespective of how they are wrapped
+    ///
+    /// Line numbers and the indices in [`PagerState::line_map`] are counted in these lines.
+    pub(crate) fn source_line_count(&self) -> usize {
+        self.text_source
+            .as_ref()
+            .map_or_else(|| self.lines.lines().count(), |source| source.line_count())
+    }
+
+    /// The lines of the text in their original order
+    ///
+    /// These are read from the [`PagerState::text_source`] if there is one, otherwise they are
+    /// the lines of [`PagerState::lines`].
+    pub(crate) fn source_lines(&self) -> Box<dyn DoubleEndedIterator<Item = &str> + '_> {
+        match &self.text_source {
+            Some(source) => Box::new((0..source.line_count()).map(move |idx| source.line(idx))),
+            None => Box::new(self.lines.lines()),
+        }
+    }
+
+    /// The row of the text at index `row`
+    ///
+    /// Without a [`PagerState::text_source`], this is the row in
+    /// [`PagerState::formatted_lines`]. Otherwise the line of the row is read from the source
+    /// and formatted again.
+    pub(crate) fn row(&self, row: usize) -> Cow<'_, str> {
+        self.text_source.as_ref().map_or_else(
+            || Cow::Borrowed(self.formatted_lines[row].as_str()),
+            |source| {
+                let line = self.source_line(row);
+                let idx = self.text_line(line);
+                let mut rows = self.formatted_line(
+                    source.line(idx),
+                    self.line_number_width(),
+                    idx,
+                    #[cfg(feature = "search")]
+                    0,
+                    #[cfg(feature = "search")]
+                    &mut BTreeSet::new(),
+                );
+                let nth = row - self.first_row_of(line);
+                Cow::Owned(if nth < rows.len() {
+                    rows.swap_remove(nth)
+                } else {
+                    String::new()
+                })
+            },
+        )
+    }
+
+    /// Whether the line that the given row belongs to has a search match
+    ///
+    /// [`PagerState::search_idx`] only holds the row where each match starts, so this looks
+    /// through all the rows of the line, which also covers the rows that continue a match.
+    #[cfg(feature = "search")]
+    pub(crate) fn line_has_match(&self, row: usize) -> bool {
+        let line = self.source_line(row);
+        let rows = self.first_row_of(line)..self.first_row_of(line + 1);
+        self.search_idx.range(rows).next().is_some()
+    }
+
+    /// Number of digits of the largest line number, which all line numbers are padded to
+    fn line_number_width(&self) -> usize {
+        (self.line_number_base() + self.source_line_count())
+            .to_string()
+            .len()
+    }
+
+    /// Number of rows that are actually taken up by the prompt
+    ///
+    /// This is [`PagerState::prompt_rows`], but it is reduced on small terminals so that at least
+    /// one row of text can still be shown. The prompt always takes up atleast one row.
+    pub(crate) fn prompt_rows_shown(&self) -> usize {
+        1 + self
+            .prompt_rows
+            .saturating_sub(1)
+            .min(self.rows.saturating_sub(MIN_ROWS))
+    }
+
+    /// Number of rows that are actually taken up by the message log
+    ///
+    /// This is [`PagerState::message_lines`], but it is reduced on small terminals so that at
+    /// least one row of text can still be shown
+    pub(crate) fn message_log_rows(&self) -> usize {
+        self.message_lines.min(
+            self.rows
+                .saturating_sub(MIN_ROWS + self.prompt_rows_shown() - 1),
+        )
+    }
+
+    /// Number of blank rows that are actually left above the prompt
+    ///
+    /// This is [`PagerState::prompt_bottom_padding`], but it is reduced on small terminals so
+    /// that at least one row of text can still be shown
+    pub(crate) fn padding_rows(&self) -> usize {
+        self.prompt_bottom_padding.min(
+            self.rows
+                .saturating_sub(MIN_ROWS + self.prompt_rows_shown() - 1 + self.message_log_rows()),
+        )
+    }
+
+    /// Number of rows available for displaying the text
+    ///
+    /// This excludes the prompt, the message log and the padding above the prompt
+    pub(crate) fn content_rows(&self) -> usize {
+        self.rows.saturating_sub(
+            self.prompt_rows_shown() + self.message_log_rows() + self.padding_rows(),
+        )
+    }
+
+    /// Number of rows taken up by the pinned header
+    ///
+    /// These are the rows of the first [`PagerState::pinned_header_lines`] lines. Atleast one row
+    /// is always left for the text to scroll in.
+    pub(crate) fn pinned_rows(&self) -> usize {
+        self.first_row_of(self.pinned_header_lines)
+            .min(self.content_rows().saturating_sub(1))
+    }
+
+    /// Number of rows in which the text scrolls
+    ///
+    /// These are the [`PagerState::content_rows`] below the pinned header
+    pub(crate) fn page_rows(&self) -> usize {
+        self.content_rows() - self.pinned_rows()
+    }
+
+    /// Number of rows by which paging up or down moves the text
+    ///
+    /// This is a full page less the [`PagerState::page_overlap`], but always atleast one row
+    pub(crate) fn page_step(&self) -> usize {
+        self.page_rows().saturating_sub(self.page_overlap).max(1)
+    }
+
+    /// Number of rows by which `Ctrl+D`/`Ctrl+U` scroll
+    ///
+    /// This is half the screen, unless a count was given to one of these keys. That count is then
+    /// used from there on, like in `less`.
+    pub(crate) fn half_page_rows(&self) -> usize {
+        self.half_page.unwrap_or(self.rows / 2)
+    }
+
+    /// The largest value that [`PagerState::upper_mark`] can take
+    ///
+    /// Normally this makes the last line appear at the bottom of the screen. With
+    /// [`PagerState::overscroll`], the text can be scrolled till the last line is at the top.
+    /// This never goes below [`PagerState::min_upper_mark`].
+    pub(crate) fn max_upper_mark(&self) -> usize {
+        let max = if self.overscroll {
+            self.num_lines().saturating_sub(1)
+        } else {
+            self.num_lines().saturating_sub(self.page_rows())
+        };
+        max.max(self.min_upper_mark())
+    }
+
+    /// The rows of [`PagerState::formatted_lines`] that are displayed in the content area
+    ///
+    /// These are the rows of the pinned header followed by the rows from the upper mark onwards,
+    /// as [`write_lines`](crate::minus_core::display::write_lines) would draw them.
+    pub(crate) fn visible_rows(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.visible_rows_from(self.upper_mark)
+    }
+
+    /// The rows that are displayed in the content area if the upper mark is at `upper_mark`
+    ///
+    /// See [`PagerState::visible_rows`]
+    pub(crate) fn visible_rows_from(
+        &self,
+        upper_mark: usize,
+    ) -> impl Iterator<Item = Cow<'_, str>> {
+        self.visible_row_indices_from(upper_mark)
+            .map(move |row| self.row(row))
+    }
+
+    /// Indices of the rows of [`PagerState::formatted_lines`] that are displayed in the content
+    /// area if the upper mark is at `upper_mark`
+    pub(crate) fn visible_row_indices_from(
+        &self,
+        upper_mark: usize,
+    ) -> impl Iterator<Item = usize> {
+        let upper_mark = upper_mark
+            .min(self.max_upper_mark())
+            .max(self.min_upper_mark());
+        let lower_mark = upper_mark
+            .saturating_add(self.page_rows())
+            .min(self.num_lines());
+        (0..self.pinned_rows()).chain(upper_mark.min(lower_mark)..lower_mark)
+    }
+
+    /// The smallest value that [`PagerState::upper_mark`] can take
+    ///
+    /// This is the first row after the pinned header, so that the header isn't displayed twice
+    pub(crate) fn min_upper_mark(&self) -> usize {
+        self.pinned_rows()
+    }
+
+    /// When the [`PagerState::pending_keys`] time out, if there are any
+    pub(crate) fn pending_keys_deadline(&self) -> Option<Instant> {
+        self.pending_keys_at
+            .map(|at| at + self.key_sequence_timeout)
+    }
+
+    /// [`PagerState::upper_mark`] kept within the text, as it is when the text is drawn
+    pub(crate) fn clamped_upper_mark(&self) -> usize {
+        self.upper_mark
+            .min(self.max_upper_mark())
+            .max(self.min_upper_mark())
+    }
+
+    /// Remember `from` in the jump history if the upper mark has moved away from it by more than
+    /// a page
+    pub(crate) fn record_jump(&mut self, from: usize) {
+        let (min, max) = (self.min_upper_mark(), self.max_upper_mark());
+        let to = self.upper_mark.min(max).max(min);
+        if to.max(from) - to.min(from) <= self.page_rows() {
+            return;
+        }
+        if self.jump_history.len() >= JUMP_HISTORY_LEN {
+            self.jump_history.remove(0);
+        }
+        self.jump_history.push(from);
+    }
+
+    /// Add a message to the message log, dropping the oldest one if the log is full
+    pub(crate) fn log_message(&mut self, message: String) {
+        if self.message_log.len() >= self.message_lines {
+            self.message_log.pop_front();
+        }
+        self.message_log.push_back(message);
+    }
+
+    /// Index of the displayed line from which the given row was formatted
+    ///
+    /// This is the index of the line in [`PagerState::lines`] unless the lines are displayed in
+    /// reverse, see [`PagerState::text_line`].
+    pub(crate) fn source_line(&self, row: usize) -> usize {
+        self.line_map
+            .get(row)
+            .or_else(|| self.line_map.last())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Index of the first row in [`PagerState::formatted_lines`] of the given displayed line
+    pub(crate) fn first_row_of(&self, line: usize) -> usize {
+        self.line_map.partition_point(|&l| l < line)
+    }
+
+    /// Index of the line in [`PagerState::lines`] that is displayed as the given line
+    ///
+    /// The lines are displayed in their original order, except with
+    /// [`PagerState::reverse_lines`]. Reversing the order is its own inverse, hence this also
+    /// gives the displayed line of a line of [`PagerState::lines`].
+    pub(crate) fn text_line(&self, line: usize) -> usize {
+        if self.reverse_lines {
+            self.line_map
+                .last()
+                .map_or(0, |&last| last.saturating_sub(line))
+        } else {
+            line
+        }
+    }
+
+    /// Number of columns at which the text is wrapped
+    ///
+    /// This is [`PagerState::wrap_width`] if it is set, otherwise the terminal width. The wrap
+    /// width is never allowed to exceed the terminal width, as the terminal would then wrap the
+    /// lines on its own and break the display. The column of the scrollbar is left out of the
+    /// terminal width. In a split view, this is limited to the width of a pane.
+    pub(crate) fn wrap_cols(&self) -> usize {
+        let cols = self.pane_cols();
+        self.wrap_width.map_or(cols, |w| w.min(cols))
+    }
+
+    /// Fit a row of [`PagerState::formatted_lines`] on the screen
+    ///
+    /// The rows are normally wrapped to fit the screen already. Only if the terminal got narrower
+    /// while [`PagerState::rewrap_on_resize`] is off, the rows can be wider than the screen. They
+    /// are then cut off at its edge, as the terminal would otherwise wrap them on its own. The
+    /// [`PagerState::truncation_marker`] shows where a row was cut off, since there is no
+    /// horizontal scrolling to reach the rest of it.
+    pub(crate) fn clip_row<'a>(&self, row: &'a str) -> Cow<'a, str> {
+        if self.rewrap_on_resize {
+            Cow::Borrowed(row)
+        } else {
+            Cow::Owned(crate::truncate_str(
+                row,
+                self.pane_cols(),
+                self.truncation_marker,
+            ))
+        }
+    }
+
+    /// The row of [`PagerState::formatted_lines`] at index `row` as it is drawn on the screen
+    ///
+    /// The [`PagerState::render_transform`] is applied to the row with the index of its line in
+    /// [`PagerState::lines`]. As the transformed row can have any width, it is always cut off at
+    /// the edge of the pane. Otherwise the row is only fitted with [`PagerState::clip_row`].
+    pub(crate) fn render_row<'a>(&self, row: usize, line: &'a str) -> Cow<'a, str> {
+        self.render_transform.as_ref().map_or_else(
+            || self.clip_row(line),
+            |transform| {
+                let line = transform(line, self.text_line(self.source_line(row)));
+                Cow::Owned(truncate_str(&line, self.pane_cols(), None))
+            },
+        )
+    }
+
+    /// Number of columns available to the text of a pane
+    ///
+    /// Without a split view, there is only one pane which takes up the entire terminal width
+    /// except for the column of the scrollbar. With a split view, the remaining columns are
+    /// divided among the two panes and the separator between them.
+    pub(crate) fn pane_cols(&self) -> usize {
+        let cols = self.cols.saturating_sub(usize::from(self.show_scrollbar));
+        if self.split.is_some() {
+            cols.saturating_sub(1) / 2
+        } else {
+            cols
+        }
     }
 
     /// Formats the given `line`
@@ -197,9 +897,22 @@ impl PagerState {
     ///     For example, this will be 2 if number of lines in [`PagerState::lines`] is 50 and 3 if
     ///     number of lines in [`PagerState::lines`] is 500. This is used for calculating the padding
     ///     of each displayed line.
+    /// - `len_line_number` includes [`PagerState::line_number_base`], as that is added to the
+    ///   displayed numbers.
     /// - `idx` is the position index where the line is placed in [`PagerState::lines`].
     /// - `formatted_idx` is the position index where the line will be placed in the resulting
     ///    [`PagerState::formatted_lines`]
+    ///
+    /// If any gutter marker is set, a one column gutter is added before the line numbers. The
+    /// marker for `idx`, if any, is placed in the gutter of the first row of the line.
+    ///
+    /// If a line formatter is set, the line is passed through it first and its output is what
+    /// gets wrapped, searched and displayed.
+    ///
+    /// If [`PagerState::trailing_whitespace_style`] is set, the whitespace at the end of the line
+    /// is kept and styled with it, continuing on further rows if it doesn't fit. Search matches
+    /// are highlighted over it.
+    #[allow(clippy::too_many_lines)]
     pub(crate) fn formatted_line(
         &self,
         line: &str,
@@ -212,138 +925,283 @@ impl PagerState {
             self.line_numbers,
             LineNumbers::Enabled | LineNumbers::AlwaysOn
         );
+        let cached = self
+            .line_formatter_cache
+            .get(idx)
+            .filter(|_| self.text_source.is_none());
+        let line: Cow<str> = match (&self.line_formatter, cached) {
+            (Some(_), Some(cached)) => Cow::Borrowed(cached),
+            (Some(formatter), None) => Cow::Owned(formatter(line)),
+            (None, _) => Cow::Borrowed(line),
+        };
+        let line = &*line;
 
-        if line_numbers {
-            // Padding is the space that the actual line text will be shifted to accomodate for
-            // in line numbers. This is equal to:-
-            // 1 for initial space + len_line_number + 1 for `.` sign and + 1 for the followup space
-            //
-            // We reduce this from the number of available columns as this space cannot be used for
-            // actual line display when wrapping the lines
-            let padding = len_line_number + LineNumbers::EXTRA_PADDING;
-            let wrapped_lines = wrap_str(line, self.cols.saturating_sub(padding + 2));
-            let mut formatted_rows = Vec::with_capacity(256);
-
-            let first_line = {
-                #[cfg_attr(not(feature = "search"), allow(unused_mut))]
-                let mut row = wrapped_lines.first().unwrap().to_string();
-
-                #[cfg(feature = "search")]
-                if let Some(st) = self.search_term.as_ref() {
-                    // highlight the lines with matching search terms
-                    // If a match is found, add this line's index to PagerState::search_idx
-                    let (highlighted_row, is_match) = search::highlight_line_matches(&row, st);
-                    if is_match {
-                        search_idx.insert(formatted_idx);
+        // The gutter is only shown if there is atleast one marker. Lines without a marker and the
+        // wrapped rows of a line get a blank space in the gutter to keep the text aligned
+        let (marker, gutter_width) = if self.gutter_markers.is_empty() {
+            (String::new(), 0)
+        } else {
+            let marker = self.gutter_markers.get(&idx).map_or_else(
+                || " ".to_string(),
+                |(ch, style)| {
+                    if self.use_colors {
+                        apply_style(*style, &ch.to_string())
+                    } else {
+                        ch.to_string()
                     }
-                    row = highlighted_row;
-                }
+                },
+            );
+            (marker, 1)
+        };
+        let cols = self.wrap_cols().saturating_sub(gutter_width);
 
-                if cfg!(not(test)) {
-                    format!(
-                        "{bold}{number: >len$}.{reset} {row}",
-                        bold = crossterm::style::Attribute::Bold,
-                        number = idx + 1,
-                        len = padding,
-                        reset = crossterm::style::Attribute::Reset,
-                        row = row
-                    )
-                } else {
-                    // In tests, we don't care about ANSI sequences for cool looking line numbers
-                    // hence we don't include them in tests. It just makes testing more difficult
-                    format!(
-                        "{number: >len$}. {row}",
-                        number = idx + 1,
-                        len = padding,
-                        row = row
-                    )
+        // Padding is the space that the actual line text will be shifted to accomodate for
+        // in line numbers. This is equal to:-
+        // 1 for initial space + len_line_number + 1 for `.` sign and + 1 for the followup space
+        //
+        // We reduce this from the number of available columns as this space cannot be used for
+        // actual line display when wrapping the lines
+        let padding = len_line_number + LineNumbers::EXTRA_PADDING;
+        let text_cols = if line_numbers {
+            cols.saturating_sub(padding + 2)
+        } else {
+            cols
+        };
+
+        // Wrapping leaves out the whitespace at the end of the rows. Hence, if it is to be
+        // highlighted, it is split off here and added back after the text is wrapped
+        let trailing_ws_style = self.trailing_whitespace_style.filter(|_| self.use_colors);
+        let text = if trailing_ws_style.is_some() {
+            line.trim_end_matches([' ', '\t'])
+        } else {
+            line
+        };
+
+        // Without colors, the search matches are marked with brackets instead of being
+        // highlighted. The brackets take up columns, so they are added before wrapping
+        #[cfg(feature = "search")]
+        let bracketed = self
+            .search_term
+            .as_ref()
+            .filter(|_| !self.use_colors && !self.highlights_hidden)
+            .map(|st| search::mark_matches(text, st));
+        #[cfg(feature = "search")]
+        let wrapped = bracketed.as_ref().map_or(text, |(marked, _)| marked);
+        #[cfg(not(feature = "search"))]
+        let wrapped = text;
+
+        let mut rows = match (self.line_wrapping, self.wrap_indicator) {
+            (false, _) => vec![truncate_str(wrapped, text_cols, self.truncation_marker)],
+            (true, Some(indicator)) => {
+                wrap_str_indented(wrapped, text_cols, &indicator.to_string())
+            }
+            (true, None) => wrap_str(wrapped, text_cols),
+        };
+        // Index of the row where the trailing whitespace starts
+        let trailing_ws_row = rows.len().saturating_sub(1);
+        if text.len() < line.len() {
+            let indent = self.wrap_indicator.map_or(0, |_| 1);
+            append_trailing_whitespace(
+                &mut rows,
+                &line[text.len()..],
+                text_cols,
+                indent,
+                self.line_wrapping,
+            );
+        }
+
+        #[cfg(feature = "search")]
+        if let Some((marked, starts)) = &bracketed {
+            let match_rows = search::rows_containing(marked, &rows, starts);
+            search_idx.extend(match_rows.into_iter().map(|i| formatted_idx + i));
+        } else if let Some(st) = self.search_term.as_ref() {
+            // highlight the lines with matching search terms
+            // If a match is found, add the index of the row where it starts to
+            // PagerState::search_idx
+            let (highlighted_rows, match_rows) = search::highlight_wrapped_matches(line, &rows, st);
+            search_idx.extend(match_rows.into_iter().map(|i| formatted_idx + i));
+            if self.use_colors && !self.highlights_hidden {
+                rows = highlighted_rows;
+            }
+        }
+
+        // Whitespace that is highlighted as a search match is followed by the escapes that end
+        // the highlight, so only the whitespace after the last match gets styled here
+        if let Some(style) = trailing_ws_style {
+            for row in rows.iter_mut().skip(trailing_ws_row) {
+                let text_len = row.trim_end_matches([' ', '\t']).len();
+                if text_len < row.len() {
+                    let whitespace = apply_style(style, &row[text_len..]);
+                    row.truncate(text_len);
+                    row.push_str(&whitespace);
                 }
-            };
+            }
+        }
 
-            formatted_rows.push(first_line);
+        // The indicator is added after highlighting the search matches, so that it can't be
+        // matched
+        if let Some(indicator) = self.wrap_indicator {
+            for row in rows.iter_mut().skip(1) {
+                row.insert(0, indicator);
+            }
+        }
 
-            #[cfg_attr(not(feature = "search"), allow(unused_mut))]
-            #[cfg_attr(not(feature = "search"), allow(unused_variables))]
-            let mut lines_left = wrapped_lines
-                .into_iter()
+        if line_numbers {
+            rows.into_iter()
                 .enumerate()
-                .skip(1)
-                .map(|(wrap_idx, mut row)| {
-                    #[cfg(feature = "search")]
-                    if let Some(st) = self.search_term.as_ref() {
-                        // highlight the lines with matching search terms
-                        // If a match is found, add this line's index to PagerState::search_idx
-                        let (highlighted_row, is_match) = search::highlight_line_matches(&row, st);
-                        if is_match {
-                            search_idx.insert(formatted_idx + wrap_idx);
-                        }
-                        row = highlighted_row;
+                .map(|(wrap_idx, row)| {
+                    if wrap_idx > 0 {
+                        " ".repeat(gutter_width + padding + 2) + &row
+                    } else if let Some(formatter) = &self.line_number_formatter {
+                        format!(
+                            "{marker}{number} {row}",
+                            marker = marker,
+                            number = formatter(self.line_number_base() + idx + 1, padding + 1),
+                            row = row
+                        )
+                    } else if cfg!(not(test)) && self.use_colors {
+                        let number = format!(
+                            "{number: >len$}.",
+                            number = self.line_number_base() + idx + 1,
+                            len = padding
+                        );
+                        format!(
+                            "{marker}{number} {row}",
+                            marker = marker,
+                            number = apply_style(self.line_number_style, &number),
+                            row = row
+                        )
+                    } else {
+                        // In tests, we don't care about ANSI sequences for cool looking line numbers
+                        // hence we don't include them in tests. It just makes testing more difficult
+                        format!(
+                            "{marker}{number: >len$}. {row}",
+                            marker = marker,
+                            number = self.line_number_base() + idx + 1,
+                            len = padding,
+                            row = row
+                        )
                     }
-                    " ".repeat(padding + 2) + &row
                 })
-                .collect::<Vec<String>>();
-            formatted_rows.append(&mut lines_left);
-            formatted_rows
+                .collect::<Vec<String>>()
         } else {
-            #[cfg_attr(not(feature = "search"), allow(unused_variables))]
-            wrap_str(line, self.cols)
-                .iter()
+            rows.into_iter()
                 .enumerate()
                 .map(|(wrap_idx, row)| {
-                    #[cfg(feature = "search")]
-                    {
-                        self.search_term.as_ref().map_or_else(
-                            || row.to_string(),
-                            |st| {
-                                // highlight the lines with matching search terms
-                                // If a match is found, add this line's index to PagerState::search_idx
-                                let (hrow, is_match) = search::highlight_line_matches(row, st);
-                                if is_match {
-                                    search_idx.insert(formatted_idx + wrap_idx);
-                                }
-                                hrow
-                            },
-                        )
+                    if wrap_idx == 0 {
+                        marker.clone() + &row
+                    } else {
+                        " ".repeat(gutter_width) + &row
                     }
-                    #[cfg(not(feature = "search"))]
-                    row.to_string()
                 })
                 .collect::<Vec<String>>()
         }
     }
 
+    /// Set the [`PagerState::line_formatter`] and format the text with it
+    pub(crate) fn set_line_formatter(&mut self, formatter: LineFormatter) {
+        self.line_formatter = Some(formatter);
+        self.line_formatter_cache.clear();
+        self.format_lines_anchored();
+    }
+
+    /// Run the [`PagerState::line_formatter`] on the terminated lines that it hasn't run on yet
+    ///
+    /// Its results are kept, so that formatting the text again, for example after a resize,
+    /// doesn't run it again. The lines of a text source aren't kept, as they aren't copied into
+    /// the pager either.
+    fn cache_formatted_lines(&mut self) {
+        if self.line_formatter.is_none() || self.text_source.is_some() {
+            return;
+        }
+        let terminated = self.source_line_count()
+            - usize::from(!self.lines.is_empty() && !self.lines.ends_with('\n'));
+        let cached = self.line_formatter_cache.len();
+        if let Some(formatter) = &self.line_formatter {
+            self.line_formatter_cache.extend(
+                self.lines
+                    .lines()
+                    .take(terminated)
+                    .skip(cached)
+                    .map(formatter),
+            );
+        }
+    }
+
+    /// Take out the [`PagerState::wrap_progress_callback`] for formatting `line_count` lines
+    ///
+    /// Progress is only reported for text large enough to take a noticable time, so this is
+    /// `None` for fewer lines than [`WRAP_PROGRESS_INTERVAL`].
+    fn take_wrap_progress(&mut self, line_count: usize) -> Option<WrapProgressCallback> {
+        if line_count >= WRAP_PROGRESS_INTERVAL {
+            self.wrap_progress_callback.take()
+        } else {
+            None
+        }
+    }
+
+    /// Report that all `line_count` lines are formatted and put the callback back
+    fn finish_wrap_progress(&mut self, progress: Option<WrapProgressCallback>, line_count: usize) {
+        if let Some(mut cb) = progress {
+            cb(line_count, line_count);
+            self.wrap_progress_callback = Some(cb);
+        }
+    }
+
     pub(crate) fn format_lines(&mut self) {
+        self.cache_formatted_lines();
         // Keep it for the record and don't call it unless it is really necessory as this is kinda
         // expensive
-        let line_count = self.lines.lines().count();
+        let line_count = self.source_line_count();
 
         // Calculate len_line_number. This will be 2 if line_count is 50 and 3 if line_count is 100 (etc)
-        let len_line_number = line_count.to_string().len();
+        let len_line_number = self.line_number_width();
 
         // Search idx, this will get filled by the self.formatted_line function
         // we will later set this to self.search_idx
         #[cfg(feature = "search")]
         let mut search_idx = BTreeSet::new();
-        let mut formatted_idx = 0;
+        let mut line_map = Vec::with_capacity(self.line_map.capacity());
+        let mut progress = self.take_wrap_progress(line_count);
 
-        self.formatted_lines = self
-            .lines
-            .lines()
-            .enumerate()
-            .flat_map(|(idx, line)| {
-                let new_line = self.formatted_line(
-                    line,
-                    len_line_number,
-                    idx,
-                    #[cfg(feature = "search")]
-                    formatted_idx,
-                    #[cfg(feature = "search")]
-                    &mut search_idx,
-                );
-                formatted_idx += new_line.len();
-                new_line
-            })
-            .collect::<Vec<String>>();
+        let reverse = self.reverse_lines;
+        let lines: Box<dyn Iterator<Item = &str>> = if reverse {
+            Box::new(self.source_lines().rev())
+        } else {
+            Box::new(self.source_lines())
+        };
+        // The rows of a text source are formatted again when they are displayed
+        let keep_rows = self.text_source.is_none();
+        let mut formatted_lines = Vec::new();
+
+        // idx is the index of the line in the order in which the lines are displayed. Line
+        // numbers always refer to the lines in the original order though
+        for (idx, line) in lines.enumerate() {
+            let new_line = self.formatted_line(
+                line,
+                len_line_number,
+                if reverse { line_count - 1 - idx } else { idx },
+                #[cfg(feature = "search")]
+                line_map.len(),
+                #[cfg(feature = "search")]
+                &mut search_idx,
+            );
+            line_map.resize(line_map.len() + new_line.len(), idx);
+            report_wrap_progress(&mut progress, idx, line_count);
+            if keep_rows {
+                formatted_lines.extend(new_line);
+            }
+        }
+        self.formatted_lines = formatted_lines;
+        // The rows of an unterminated last line are replaced when the line is continued
+        self.unterminated = if reverse || self.lines.is_empty() || self.lines.ends_with('\n') {
+            0
+        } else {
+            line_map.len() - line_map.partition_point(|&l| l + 1 < line_count)
+        };
+        self.line_map = line_map;
+
+        self.finish_wrap_progress(progress, line_count);
 
         #[cfg(feature = "search")]
         {
@@ -353,6 +1211,237 @@ impl PagerState {
         self.format_prompt();
     }
 
+    /// Replace the text and scroll to where [`PagerState::scroll_anchor_on_setdata`] says
+    pub(crate) fn set_text(&mut self, text: String) {
+        self.replace_text(text, None);
+    }
+
+    /// Replace the text with the lines of `source` and scroll like [`PagerState::set_text`]
+    pub(crate) fn set_text_source(&mut self, source: Box<dyn TextSource + Send + Sync>) {
+        self.replace_text(String::new(), Some(source));
+    }
+
+    fn replace_text(&mut self, text: String, source: Option<Box<dyn TextSource + Send + Sync>>) {
+        let (min, max) = (self.min_upper_mark(), self.max_upper_mark());
+        let upper_mark = self.upper_mark.min(max).max(min);
+        let top_line = self.text_line(self.source_line(upper_mark));
+        self.lines = text;
+        self.text_source = source;
+        self.line_formatter_cache.clear();
+        // The new text starts counting its lines from the beginning again
+        self.dropped_lines = 0;
+        self.drop_excess_lines();
+        self.format_lines();
+        self.upper_mark = match self.scroll_anchor_on_setdata {
+            ScrollAnchor::Top => self.min_upper_mark(),
+            ScrollAnchor::SamePercent => {
+                let new_min = self.min_upper_mark();
+                let new_range = self.max_upper_mark() - new_min;
+                new_min
+                    + (upper_mark - min)
+                        .saturating_mul(new_range)
+                        .checked_div(max - min)
+                        .unwrap_or(0)
+            }
+            ScrollAnchor::SameLine => self.first_row_of(self.text_line(top_line)),
+        };
+    }
+
+    /// Number that is added to the index of a line in [`PagerState::lines`] to get its number
+    ///
+    /// This is the [`PagerState::line_number_offset`] plus the [`PagerState::dropped_lines`], so
+    /// that the lines keep their numbers when the lines before them are dropped.
+    pub(crate) const fn line_number_base(&self) -> usize {
+        self.line_number_offset + self.dropped_lines
+    }
+
+    /// Number of lines in [`PagerState::lines`] once `text` is appended to it
+    fn line_count_with(&self, text: &str) -> usize {
+        let line_count = self.source_line_count();
+        // The first line of the text continues the last line if that is unterminated
+        let continued =
+            usize::from(!self.lines.is_empty() && !self.lines.ends_with('\n') && !text.is_empty());
+        line_count + text.lines().count() - continued
+    }
+
+    /// Drop the oldest lines of [`PagerState::lines`] that exceed [`PagerState::max_lines`]
+    ///
+    /// The lines are only dropped from the text, which has to be formatted again afterwards. The
+    /// gutter markers move along with their lines, and the markers of the dropped lines are
+    /// removed. Returns the number of lines that were dropped.
+    fn drop_excess_lines(&mut self) -> usize {
+        // The lines of a text source belong to the application
+        if self.text_source.is_some() {
+            return 0;
+        }
+        let excess = self
+            .max_lines
+            .map_or(0, |max| self.source_line_count().saturating_sub(max));
+        if excess == 0 {
+            return 0;
+        }
+        let cut = self
+            .lines
+            .match_indices('\n')
+            .nth(excess - 1)
+            .map_or(self.lines.len(), |(idx, _)| idx + 1);
+        self.lines.drain(..cut);
+        self.line_formatter_cache
+            .drain(..excess.min(self.line_formatter_cache.len()));
+        self.dropped_lines += excess;
+        self.gutter_markers = self
+            .gutter_markers
+            .drain()
+            .filter_map(|(line, marker)| Some((line.checked_sub(excess)?, marker)))
+            .collect();
+        excess
+    }
+
+    /// Drop the lines that exceed [`PagerState::max_lines`] along with their rows
+    ///
+    /// The rows of the other lines are kept as they are, as the lines keep their numbers. Only
+    /// if the gutter disappears along with the last gutter marker, the text is formatted again.
+    /// The line at the top of the screen stays there, unless it has been dropped itself.
+    pub(crate) fn format_lines_within_max(&mut self) {
+        let top_line = self.source_line(self.upper_mark);
+        let had_gutter = !self.gutter_markers.is_empty();
+        let dropped = self.drop_excess_lines();
+        if dropped == 0 {
+            return;
+        }
+        if had_gutter && self.gutter_markers.is_empty() {
+            self.format_lines();
+        } else if self.reverse_lines {
+            // The oldest lines are displayed last
+            let cut = self.first_row_of(self.source_line_count());
+            self.formatted_lines.truncate(cut);
+            self.line_map.truncate(cut);
+            #[cfg(feature = "search")]
+            self.search_idx.split_off(&cut);
+            self.format_prompt();
+        } else {
+            let cut = self.first_row_of(dropped);
+            self.formatted_lines.drain(..cut);
+            self.line_map.drain(..cut);
+            for line in &mut self.line_map {
+                *line -= dropped;
+            }
+            self.unterminated = self.unterminated.min(self.formatted_lines.len());
+            #[cfg(feature = "search")]
+            {
+                let removed = self.search_idx.range(..cut).count();
+                self.search_mark = self.search_mark.saturating_sub(removed);
+                self.search_idx = self
+                    .search_idx
+                    .split_off(&cut)
+                    .into_iter()
+                    .map(|row| row - cut)
+                    .collect();
+            }
+            self.format_prompt();
+        }
+        // The oldest lines are displayed last if the lines are reversed, so the lines above the
+        // top line stay
+        self.upper_mark = if self.reverse_lines {
+            self.first_row_of(top_line)
+        } else {
+            self.first_row_of(top_line.saturating_sub(dropped))
+        };
+    }
+
+    /// Whether appending `text` makes the text longer than [`PagerState::max_lines`]
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) fn overflows_max_lines(&self, text: &str) -> bool {
+        matches!(self.max_lines, Some(max) if self.line_count_with(text) > max)
+    }
+
+    /// Reformat all lines while keeping the same line at the top of the screen
+    ///
+    /// Reformatting can change the number of rows that each line wraps into, for example when
+    /// the line numbers are toggled. Hence [`PagerState::upper_mark`] is moved to the first row
+    /// of the line that was at the top before reformatting.
+    pub(crate) fn format_lines_anchored(&mut self) {
+        let top_line = self.source_line(self.upper_mark);
+        self.format_lines();
+        self.upper_mark = self.first_row_of(top_line);
+    }
+
+    /// Reformat the text after the terminal is resized
+    ///
+    /// If the focused search match is on the screen, it stays at the same place on the screen
+    /// where possible, even though rewrapping moves it to a different row. Otherwise this behaves
+    /// like [`PagerState::format_lines_anchored`].
+    pub(crate) fn format_lines_on_resize(&mut self) {
+        #[cfg(feature = "search")]
+        let focus = self.focused_match();
+        self.format_lines_anchored();
+
+        #[cfg(feature = "search")]
+        if let Some((line, nth, offset)) = focus {
+            let rows = self.first_row_of(line)..self.first_row_of(line + 1);
+            let matches: Vec<(usize, usize)> = self
+                .search_idx
+                .iter()
+                .enumerate()
+                .filter(|(_, row)| rows.contains(row))
+                .map(|(mark, row)| (mark, *row))
+                .collect();
+            // The line may have fewer matching rows now if the matches moved into the same row
+            if let Some(&(mark, row)) = matches.get(nth).or_else(|| matches.last()) {
+                self.search_mark = mark;
+                let offset = offset.min(self.page_rows().saturating_sub(1));
+                self.upper_mark = row.saturating_sub(offset);
+            }
+        }
+    }
+
+    /// Get the position of the focused search match if it is visible on the screen
+    ///
+    /// This returns the source line of the match, the number of matching rows of that line
+    /// before the match, and the distance of the match from the top of the screen
+    #[cfg(feature = "search")]
+    fn focused_match(&self) -> Option<(usize, usize, usize)> {
+        self.search_term.as_ref()?;
+        let row = *self.search_idx.iter().nth(self.search_mark)?;
+        if row < self.upper_mark || row >= self.upper_mark.saturating_add(self.page_rows()) {
+            return None;
+        }
+        let line = self.source_line(row);
+        let nth = self.search_idx.range(self.first_row_of(line)..row).count();
+        Some((line, nth, row - self.upper_mark))
+    }
+
+    /// Wrap `prompt` into the rows of the prompt and push all but the last row to `format_string`
+    ///
+    /// The last row is returned, as the indicators still have to be added to it. It contains all
+    /// of the text that doesn't fit in the rows before it, which is cut off when it is displayed.
+    fn push_upper_prompt_rows(&self, format_string: &mut String, prompt: &str) -> String {
+        let num_rows = self.prompt_rows_shown();
+        if num_rows == 1 || self.cols == 0 {
+            return prompt.to_string();
+        }
+        let mut rows = wrap_str(prompt, self.cols);
+        let last_row = if rows.len() >= num_rows {
+            rows.split_off(num_rows - 1).join(" ")
+        } else {
+            String::new()
+        };
+        rows.resize(num_rows - 1, String::new());
+        for row in rows {
+            let width = textwrap::core::display_width(&row);
+            format_string.push_str(&row);
+            format_string.push_str(&" ".repeat(self.cols.saturating_sub(width)));
+            if self.use_colors {
+                format_string.push_str(&Attribute::Reset.to_string());
+            }
+            format_string.push('\n');
+            if self.use_colors {
+                format_string.push_str(&Attribute::Reverse.to_string());
+            }
+        }
+        last_row
+    }
+
     /// Reformat the inputted prompt to how it should be displayed
     pub(crate) fn format_prompt(&mut self) {
         const SEARCH_BG: &str = "\x1b[34m";
@@ -360,7 +1449,12 @@ impl PagerState {
 
         // Allocate the string. Add extra space in case for the
         // ANSI escape things if we do have characters typed and search showing
-        let mut format_string = String::with_capacity(self.cols + (SEARCH_BG.len() * 2) + 4);
+        let mut format_string = String::with_capacity(self.cols + (SEARCH_BG.len() * 2) + 12);
+
+        // The prompt is displayed in reverse video
+        if self.use_colors {
+            format_string.push_str(&Attribute::Reverse.to_string());
+        }
 
         // Get the string that will contain the search index/match indicator
         #[cfg(feature = "search")]
@@ -383,53 +1477,117 @@ impl PagerState {
         }
 
         // And lastly, the string that contains the prompt or msg
-        let prompt_str = self.message.as_ref().unwrap_or(&self.prompt);
+        let prompt_str = match (&self.message, self.message_style) {
+            (Some(msg), MessageStyle::Append) if !self.prompt.is_empty() => {
+                Cow::Owned(format!("{} - {}", self.prompt, msg))
+            }
+            (Some(msg), _) => Cow::Borrowed(msg.as_str()),
+            (None, _) => Cow::Borrowed(self.prompt.as_str()),
+        };
 
         #[cfg(feature = "search")]
         let search_len = search_str.len();
         #[cfg(not(feature = "search"))]
         let search_len = 0;
 
-        // Calculate how much extra padding in the middle we need between
-        // the prompt/message and the indicators on the right
+        // The indicators are placed in the last row of the prompt
+        let prompt_str = self.push_upper_prompt_rows(&mut format_string, &prompt_str);
+
+        // A prompt/message that is too wide is cut off with an ellipsis to leave room for the
+        // indicators on the right. Then calculate how much extra padding in the middle we need
+        // between the two
         let prefix_len = prefix_str.len();
-        let extra_space = self
-            .cols
-            .saturating_sub(search_len + prefix_len + prompt_str.len());
-        let dsp_prompt: &str = if extra_space == 0 {
-            &prompt_str[..self.cols - search_len - prefix_len]
-        } else {
-            prompt_str
-        };
+        let prompt_cols = self.cols.saturating_sub(search_len + prefix_len);
+        let dsp_prompt = truncate_str(&prompt_str, prompt_cols, Some('…'));
+        let extra_space = prompt_cols.saturating_sub(textwrap::core::display_width(&dsp_prompt));
 
         // push the prompt/msg
-        format_string.push_str(dsp_prompt);
+        format_string.push_str(&dsp_prompt);
+        // Cutting off a styled prompt resets all styling, so reverse the rest of it again
+        if self.use_colors && dsp_prompt.len() < prompt_str.len() && dsp_prompt.contains('\x1b') {
+            format_string.push_str(&Attribute::Reverse.to_string());
+        }
         format_string.push_str(&" ".repeat(extra_space));
 
         // add the prefix_num if it exists
         if prefix_len > 0 {
-            format_string.push_str(INPUT_BG);
+            if self.use_colors {
+                format_string.push_str(INPUT_BG);
+            }
             format_string.push_str(&prefix_str);
         }
 
         // and add the search indicator stuff if it exists
         #[cfg(feature = "search")]
         if search_len > 0 {
-            format_string.push_str(SEARCH_BG);
+            if self.use_colors {
+                format_string.push_str(SEARCH_BG);
+            }
             format_string.push_str(&search_str);
         }
 
+        if self.use_colors {
+            format_string.push_str(&Attribute::Reset.to_string());
+        }
+
         self.displayed_prompt = format_string;
     }
 
-    /// Returns all the text within the bounds, after flattening
-    pub(crate) fn get_flattened_lines_with_bounds(&self, start: usize, end: usize) -> &[String] {
-        if start >= self.num_lines() || start > end {
-            &[]
-        } else if end >= self.num_lines() {
-            &self.formatted_lines[start..]
+    /// Format the list of keybindings that is shown in place of the text
+    ///
+    /// This is only done when the list is opened and again when the terminal is resized or the
+    /// keybindings change while it is open, not on every redraw.
+    pub(crate) fn format_help(&mut self) {
+        let cols = self.cols;
+        self.help_rows = input::help_text(self)
+            .lines()
+            .flat_map(|line| wrap_str(line, cols))
+            .collect();
+
+        let prompt = truncate_str("Keybindings, press any key to close", cols, Some('…'));
+        let extra_space = cols.saturating_sub(textwrap::core::display_width(&prompt));
+        self.help_prompt = if self.use_colors {
+            format!(
+                "{}{}{}{}",
+                Attribute::Reverse,
+                prompt,
+                " ".repeat(extra_space),
+                Attribute::Reset
+            )
         } else {
-            &self.formatted_lines[start..end]
+            format!("{}{}", prompt, " ".repeat(extra_space))
+        };
+    }
+
+    /// Whether the last line of the text is on the screen
+    pub(crate) fn at_end(&self) -> bool {
+        self.upper_mark.saturating_add(self.page_rows()) >= self.num_lines()
+    }
+
+    /// Whether the pager should quit now that the application has dropped its pager
+    ///
+    /// This is only the case with [`PagerState::exit_on_disconnect`] and once the last line of
+    /// the text is on the screen, so that the user gets to read the text up to the end.
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) fn exits_on_disconnect(&self) -> bool {
+        self.exit_on_disconnect && self.at_end()
+    }
+
+    /// Run [`PagerState::on_reach_bottom`] if the user has just scrolled to the bottom
+    ///
+    /// It is run once when the last line comes onto the screen and not again until the user has
+    /// scrolled away from the bottom, or more text has been added below it.
+    pub(crate) fn check_reached_bottom(&mut self) {
+        if !self.at_end() {
+            self.reached_bottom_at = None;
+            return;
+        }
+        let num_lines = self.num_lines();
+        if self.reached_bottom_at != Some(num_lines) {
+            self.reached_bottom_at = Some(num_lines);
+            if let Some(cb) = &mut self.on_reach_bottom {
+                cb();
+            }
         }
     }
 
@@ -441,20 +1599,136 @@ impl PagerState {
     }
 
     pub(crate) fn append_str(&mut self, text: &str) {
-        let (fmt_line, num_unterminated) = self.make_append_str(text);
-        self.append_str_on_unterminated(fmt_line, num_unterminated);
+        // The pager can't add lines to a text source, so it takes over the lines of the source
+        if let Some(source) = self.text_source.take() {
+            for idx in 0..source.line_count() {
+                self.lines.push_str(source.line(idx));
+                self.lines.push('\n');
+            }
+            self.format_lines();
+        }
+        if self.widens_line_numbers(text) {
+            // The numbers of the new lines are wider than the existing ones, they have to be
+            // padded again to keep the text aligned
+            self.lines.push_str(text);
+            self.format_lines();
+        } else if self.reverse_lines {
+            // The new lines are displayed first, so they go in front of the other rows
+            let top_line = self.text_line(self.source_line(self.upper_mark));
+            self.prepend_reversed(text);
+            // Keep showing the newest lines if they were on the screen, otherwise keep showing
+            // the same text
+            if self.upper_mark > self.min_upper_mark() {
+                self.upper_mark = self.first_row_of(self.text_line(top_line));
+            }
+        } else {
+            let append_props = self.make_append_str(text);
+            self.append_str_on_unterminated(append_props.fmt_lines, append_props.num_unterminated);
+        }
+        // The oldest lines are dropped after the new ones are added
+        self.format_lines_within_max();
+    }
+
+    /// Append `text` while the lines are displayed in reverse
+    ///
+    /// The new lines are displayed first, hence their rows are put in front of the rows of the
+    /// other lines, which are moved down without being formatted again. Only a line that `text`
+    /// continues is formatted again along with the new lines, as its rows are replaced.
+    fn prepend_reversed(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let continued = !self.lines.is_empty() && !self.lines.ends_with('\n');
+        // The continued line is the newest one, so its rows are the first rows
+        let removed_rows = if continued { self.first_row_of(1) } else { 0 };
+        let to_format = if continued {
+            self.lines.lines().last().unwrap_or("").to_string() + text
+        } else {
+            text.to_string()
+        };
+        self.lines.push_str(text);
+        self.cache_formatted_lines();
+        let line_count = self.source_line_count();
+        let len_line_number = self.line_number_width();
+
+        let new_lines: Vec<&str> = to_format.lines().collect();
+        let mut rows = Vec::new();
+        let mut line_map = Vec::new();
+        #[cfg(feature = "search")]
+        let mut search_idx = BTreeSet::new();
+        let mut progress = self.take_wrap_progress(new_lines.len());
+        for (idx, line) in new_lines.iter().rev().enumerate() {
+            let new_rows = self.formatted_line(
+                line,
+                len_line_number,
+                line_count - 1 - idx,
+                #[cfg(feature = "search")]
+                rows.len(),
+                #[cfg(feature = "search")]
+                &mut search_idx,
+            );
+            line_map.resize(line_map.len() + new_rows.len(), idx);
+            rows.extend(new_rows);
+            report_wrap_progress(&mut progress, idx, new_lines.len());
+        }
+        self.finish_wrap_progress(progress, new_lines.len());
+
+        // The other lines move down by the number of new lines in the order of display, and their
+        // rows by the number of new rows
+        let shift = new_lines.len() - usize::from(continued);
+        #[cfg(feature = "search")]
+        let num_rows = rows.len();
+        for line in &mut self.line_map[removed_rows..] {
+            *line += shift;
+        }
+        self.line_map.splice(..removed_rows, line_map);
+        self.formatted_lines.splice(..removed_rows, rows);
+        #[cfg(feature = "search")]
+        {
+            search_idx.extend(
+                self.search_idx
+                    .range(removed_rows..)
+                    .map(|row| row - removed_rows + num_rows),
+            );
+            self.search_idx = search_idx;
+        }
+        self.format_prompt();
+    }
+
+    /// Whether appending `text` makes the line numbers take up more columns
+    ///
+    /// The line numbers are padded to the width of the largest one, so this happens when the
+    /// number of lines reaches the next power of ten, like going from 99 to 100 lines.
+    pub(crate) fn widens_line_numbers(&self, text: &str) -> bool {
+        if !matches!(
+            self.line_numbers,
+            LineNumbers::Enabled | LineNumbers::AlwaysOn
+        ) || text.is_empty()
+        {
+            return false;
+        }
+        let line_count = self.source_line_count();
+        let new_line_count = self.line_count_with(text);
+        (self.line_number_base() + new_line_count).to_string().len()
+            > (self.line_number_base() + line_count).to_string().len()
     }
 
     /// Makes the text that will be displayed and appended it to [`self.formatted_lines`]
     ///
-    /// - The first output value is the actual text rows that needs to be appended. This is wrapped
-    ///     based on the available columns
-    /// - The second value is the number of rows that should be truncated from [`self.formatted_lines`]
-    ///     before appending this line. This will be 0 if the given `text` is to be appended to
-    ///     [`self.formatted_lines`] but will be `>0` if the given text is actually part of the
-    ///     last appended line. This function determines this by checking whether self.lines ends with
-    ///     `\n` after appending the text
-    pub(crate) fn make_append_str(&mut self, text: &str) -> (Vec<String>, usize) {
+    /// - [`AppendProps::fmt_lines`] are the actual text rows that needs to be appended. This is
+    ///   wrapped based on the available columns
+    /// - [`AppendProps::num_unterminated`] is the number of rows that should be truncated from
+    ///   [`self.formatted_lines`] before appending this line. This will be 0 if the given `text`
+    ///   is to be appended to [`self.formatted_lines`] but will be `>0` if the given text is
+    ///   actually part of the last appended line. This function determines this by checking
+    ///   whether self.lines ends with `\n` after appending the text
+    /// - [`AppendProps::num_appendable`] is the number of these rows that can be written to the
+    ///   screen right away
+    ///
+    /// [`self.line_map`] is updated here to what it will be once the rows are passed to
+    /// [`self.append_str_on_unterminated`]
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn make_append_str(&mut self, text: &str) -> AppendProps {
         let append = self.lines.ends_with('\n') || self.lines.is_empty();
 
         let to_format = if append {
@@ -463,13 +1737,21 @@ impl PagerState {
             self.lines.lines().last().unwrap_or("").to_string() + text
         };
 
-        let to_skip = self.lines.lines().count();
+        let to_skip = self.source_line_count();
+        // Index of the first line of to_format in self.lines. If we are not appending, the first
+        // line is the last line of self.lines which is being continued
+        let first_idx = if append {
+            to_skip
+        } else {
+            to_skip.saturating_sub(1)
+        };
         // push the text to lines
         self.lines.push_str(text);
+        self.cache_formatted_lines();
         // And get how many lines of text will be shown (not how many rows, how many wrapped
         // lines), and get its string length
-        let line_number = self.lines.lines().count();
-        let len_line_number = line_number.to_string().len();
+        let line_number = self.source_line_count();
+        let len_line_number = (self.line_number_base() + line_number).to_string().len();
         // This will get filled if there is an ongoing search. We just need to append it to
         // self.search_idx at the end
         #[cfg(feature = "search")]
@@ -486,22 +1768,50 @@ impl PagerState {
             .collect::<Vec<(usize, String)>>();
 
         let mut fmtl = Vec::with_capacity(256);
+        let mut progress = self.take_wrap_progress(to_format_len);
+
+        // Row of self.formatted_lines where the formatted text starts. The rows of a continued
+        // line are replaced, along with the matches found in them
+        let mut row =
+            self.formatted_lines
+                .len()
+                .saturating_sub(if append { 0 } else { self.unterminated });
+        #[cfg(feature = "search")]
+        self.search_idx.split_off(&row);
 
         // First line
         let mut first_line = self.formatted_line(
             // TODO: Remove unwrap from here
             &lines.first().unwrap().1,
             len_line_number,
-            to_skip.saturating_sub(1),
+            first_idx,
             #[cfg(feature = "search")]
-            if append {
-                self.formatted_lines.len()
-            } else {
-                self.formatted_lines.len().saturating_sub(1)
-            },
+            row,
             #[cfg(feature = "search")]
             &mut append_search_idx,
         );
+        row += first_line.len();
+
+        // Format all other lines except the first and last line
+        let mid_lines = lines
+            .iter()
+            .skip(1)
+            .take(lines.len().saturating_sub(2))
+            .map(|(idx, line)| {
+                let rows = self.formatted_line(
+                    line,
+                    len_line_number,
+                    first_idx + idx,
+                    #[cfg(feature = "search")]
+                    row,
+                    #[cfg(feature = "search")]
+                    &mut append_search_idx,
+                );
+                row += rows.len();
+                report_wrap_progress(&mut progress, *idx, to_format_len);
+                (*idx, rows)
+            })
+            .collect::<Vec<(usize, Vec<String>)>>();
 
         // Format the last line, only if first line and last line are different. We can check this
         // by seeing whether to_format_len is greater than 1
@@ -509,33 +1819,16 @@ impl PagerState {
             Some(self.formatted_line(
                 &lines.last().unwrap().1,
                 len_line_number,
-                to_format_len + to_skip.saturating_sub(1),
+                first_idx + to_format_len - 1,
                 #[cfg(feature = "search")]
-                self.formatted_lines.len(),
+                row,
                 #[cfg(feature = "search")]
                 &mut append_search_idx,
             ))
         } else {
             None
         };
-
-        // Format all other lines except the first and last line
-        let mut mid_lines = lines
-            .iter()
-            .skip(1)
-            .take(lines.len().saturating_sub(2))
-            .flat_map(|(idx, line)| {
-                self.formatted_line(
-                    line,
-                    len_line_number,
-                    idx + to_skip.saturating_sub(1),
-                    #[cfg(feature = "search")]
-                    self.formatted_lines.len(),
-                    #[cfg(feature = "search")]
-                    &mut append_search_idx,
-                )
-            })
-            .collect::<Vec<String>>();
+        self.finish_wrap_progress(progress, to_format_len);
 
         let unterminated = if self.lines.ends_with('\n') {
             0
@@ -545,15 +1838,50 @@ impl PagerState {
             first_line.len()
         };
 
+        self.line_map
+            .truncate(self.line_map.len().saturating_sub(self.unterminated));
+        self.line_map
+            .resize(self.line_map.len() + first_line.len(), first_idx);
+        for (idx, rows) in &mid_lines {
+            self.line_map
+                .resize(self.line_map.len() + rows.len(), first_idx + idx);
+        }
+        if let Some(ll) = &last_line {
+            self.line_map.resize(
+                self.line_map.len() + ll.len(),
+                first_idx + to_format_len - 1,
+            );
+        }
+
         fmtl.append(&mut first_line);
-        fmtl.append(&mut mid_lines);
+        fmtl.extend(mid_lines.into_iter().flat_map(|(_, rows)| rows));
         if let Some(mut ll) = last_line {
             fmtl.append(&mut ll);
         }
 
         #[cfg(feature = "search")]
         self.search_idx.append(&mut append_search_idx);
-        (fmtl, unterminated)
+
+        AppendProps {
+            num_appendable: self.num_appendable(fmtl.len()),
+            fmt_lines: fmtl,
+            num_unterminated: unterminated,
+        }
+    }
+
+    /// Number of `num_rows` new rows that fit in the unfilled rows of the screen
+    ///
+    /// The rows of the unterminated line are rewritten, so they count as unfilled. For example if
+    /// 20 rows are available for the text with 10 rows already occupied, this will be 3 if 3 rows
+    /// are given and 10 if 15 rows are given. This is `None` if the screen is already filled.
+    fn num_appendable(&self, num_rows: usize) -> Option<usize> {
+        if self.num_lines() > self.content_rows() {
+            return None;
+        }
+        let available_rows = self
+            .content_rows()
+            .saturating_sub(self.num_lines().saturating_sub(self.unterminated));
+        Some(num_rows.min(available_rows))
     }
 
     /// Conditionally appends to [`self.formatted_lines`] or changes the last unterminated rows of
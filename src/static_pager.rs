@@ -2,7 +2,7 @@
 //!
 //! This module provides provides the [`page_all`] function to display static output via minus
 use crate::minus_core::{self, init};
-use crate::{error::MinusError, Pager};
+use crate::{error::MinusError, ExitStrategy, Pager};
 
 /// Display static information to the screen
 ///
@@ -26,11 +26,55 @@ use crate::{error::MinusError, Pager};
 #[cfg_attr(docsrs, doc(cfg(feature = "static_output")))]
 #[allow(clippy::needless_pass_by_value)]
 pub fn page_all(pager: Pager) -> Result<(), MinusError> {
+    run(&pager)
+}
+
+/// Display static information to the screen without consuming the [`Pager`]
+///
+/// This behaves like [`page_all`], but the pager can be used again after the user quits, for
+/// example to change some configuration and page again. Each run starts with a fresh state that
+/// is made from the events sent since the previous run, hence the text and any configuration
+/// need to be sent again before each run.
+///
+/// Quitting the pager must return to the application for it to be paged again, hence this
+/// always uses [`ExitStrategy::PagerQuit`], irrespective of what has been set with
+/// [`set_exit_strategy`](Pager::set_exit_strategy).
+///
+/// ```rust,no_run
+/// use minus::{page_all_ref, Pager};
+///
+/// let mut pager = Pager::new();
+/// pager.set_text("Hello").unwrap();
+/// page_all_ref(&mut pager).unwrap();
+///
+/// pager.set_text("Hello again").unwrap();
+/// pager.set_prompt("Second run").unwrap();
+/// page_all_ref(&mut pager).unwrap();
+/// ```
+///
+/// # Panics
+/// This function will panic if another instance of minus is already running.
+///
+/// # Errors
+/// The function will return with an error if it encounters a error during paging.
+#[cfg_attr(docsrs, doc(cfg(feature = "static_output")))]
+pub fn page_all_ref(pager: &mut Pager) -> Result<(), MinusError> {
+    // This is sent last, so that it overrides any exit strategy sent before
+    pager.set_exit_strategy(ExitStrategy::PagerQuit)?;
+    run(pager)
+}
+
+fn run(pager: &Pager) -> Result<(), MinusError> {
     let mut runmode = init::RUNMODE.lock();
     assert!(runmode.is_uninitialized(), "Failed to set the RUNMODE. This is caused probably bcause another instance of minus is already running");
     *runmode = minus_core::RunMode::Static;
     drop(runmode);
 
-    init::init_core(pager)?;
-    Ok(())
+    pager.exit.reset();
+    let res = init::init_core(&pager.rx, &pager.ps);
+    // init_core can return early without starting the pager, so make sure that the pager can be
+    // started again
+    *init::RUNMODE.lock() = minus_core::RunMode::Uninitialized;
+    pager.exit.notify(&res);
+    res.map(|_| ())
 }
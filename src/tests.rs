@@ -27,7 +27,8 @@ mod fmt_write {
 }
 
 mod pager_append_str {
-    use crate::PagerState;
+    use crate::{LineNumbers, PagerState, ScrollAnchor};
+    use crossterm::style::{Attribute, ContentStyle};
     #[test]
     fn sequential_append_str() {
         const TEXT1: &str = "This is a line.";
@@ -220,6 +221,347 @@ mod pager_append_str {
         );
         assert_eq!(ps.lines, TEST.to_string());
     }
+
+    #[test]
+    fn line_numbers_across_appends() {
+        let mut ps = PagerState::new().unwrap();
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.append_str("first\nsecond\n");
+        ps.append_str("third\nfourth\nfif");
+        ps.append_str("th\n");
+
+        assert_eq!(
+            ps.formatted_lines,
+            vec![
+                "     1. first",
+                "     2. second",
+                "     3. third",
+                "     4. fourth",
+                "     5. fifth",
+            ]
+        );
+    }
+
+    #[test]
+    fn line_numbers_widen_on_append() {
+        let mut ps = PagerState::new().unwrap();
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.append_str(&"line\n".repeat(8));
+        ps.append_str("line");
+        assert_eq!(ps.formatted_lines[0], "     1. line");
+
+        // The 10th line makes all numbers two digits wide
+        ps.append_str("\nline");
+        assert_eq!(ps.formatted_lines[0], "      1. line");
+        assert_eq!(ps.formatted_lines[9], "     10. line");
+
+        let appended = ps.formatted_lines.clone();
+        ps.format_lines();
+        assert_eq!(ps.formatted_lines, appended);
+    }
+
+    #[test]
+    fn max_lines() {
+        let mut ps = PagerState::new().unwrap();
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.max_lines = Some(3);
+        ps.gutter_markers.insert(3, ('+', ContentStyle::default()));
+        ps.append_str("1\n2\n3\n");
+        ps.upper_mark = 1;
+
+        // The oldest lines are dropped and the others keep their numbers
+        ps.append_str("4\n5");
+        assert_eq!(ps.lines, "3\n4\n5");
+        assert_eq!(ps.formatted_lines[0], "      3. 3");
+        assert_eq!(ps.formatted_lines[2], "      5. 5");
+        // The marker and the upper mark stay with their lines
+        assert!(ps.gutter_markers.contains_key(&1));
+        assert_eq!(ps.upper_mark, 0);
+
+        // The unterminated line is continued without dropping another line
+        ps.append_str("5\n");
+        assert_eq!(ps.lines, "3\n4\n55\n");
+
+        // Replacing the text starts the numbers over
+        ps.set_text("a\nb\nc\nd\n".to_string());
+        assert_eq!(ps.lines, "b\nc\nd\n");
+        assert_eq!(ps.formatted_lines[1], "      3. c");
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn max_lines_append_matches_full_format() {
+        let mut ps = PagerState::with_dimensions(10, 20);
+        ps.max_lines = Some(4);
+        ps.search_term = Some(regex::Regex::new("li+ne").unwrap());
+        ps.append_str("a wrapped line of text\nb\nline c\n");
+        ps.append_str("d\nthe liiine e\nf");
+
+        // Only the rows of the dropped lines are removed, which must leave the same rows as
+        // formatting everything again
+        let rows = ps.formatted_lines.clone();
+        let line_map = ps.line_map.clone();
+        let search_idx = ps.search_idx.clone();
+        ps.format_lines();
+        assert_eq!(ps.lines, "line c\nd\nthe liiine e\nf");
+        assert_eq!(rows, ps.formatted_lines);
+        assert_eq!(line_map, ps.line_map);
+        assert_eq!(search_idx, ps.search_idx);
+    }
+
+    /// Compare appending many lines one by one with appending them all at once, as done by
+    /// [`Pager::append_lines_iter`](crate::Pager::append_lines_iter)
+    ///
+    /// Run it with `cargo test --release -- --ignored --nocapture bench_append_lines`.
+    #[test]
+    #[ignore = "benchmark, run it in a release build"]
+    fn bench_append_lines() {
+        let lines: Vec<String> = (1..=20_000).map(|i| format!("Line {}", i)).collect();
+
+        let mut one_by_one = PagerState::with_dimensions(24, 80);
+        let start = std::time::Instant::now();
+        for line in &lines {
+            one_by_one.append_str(&format!("{}\n", line));
+        }
+        let one_by_one_time = start.elapsed();
+
+        let mut at_once = PagerState::with_dimensions(24, 80);
+        let start = std::time::Instant::now();
+        at_once.append_str(&(lines.join("\n") + "\n"));
+        let at_once_time = start.elapsed();
+
+        assert_eq!(one_by_one.formatted_lines, at_once.formatted_lines);
+        println!(
+            "{} lines: {:?} one by one, {:?} at once",
+            lines.len(),
+            one_by_one_time,
+            at_once_time
+        );
+    }
+
+    #[test]
+    fn line_numbers_on_wrapped_lines() {
+        let mut ps = PagerState::with_dimensions(10, 20);
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.append_str("first\nthis line is wrapped into rows\nthi");
+        ps.append_str("rd\n");
+
+        // Only the first row of a line is numbered, the other rows get a blank gutter that is
+        // just as wide
+        let expected = vec![
+            "     1. first",
+            "     2. this line is",
+            "        wrapped into",
+            "        rows",
+            "     3. third",
+        ];
+        assert_eq!(ps.formatted_lines, expected);
+
+        ps.format_lines();
+        assert_eq!(ps.formatted_lines, expected);
+    }
+
+    #[test]
+    fn scroll_anchor_on_set_text() {
+        let mut ps = PagerState::with_dimensions(11, 80);
+        let text = |n: usize| (0..n).map(|i| i.to_string() + "\n").collect::<String>();
+        ps.set_text(text(50));
+        ps.upper_mark = 20;
+
+        ps.set_text(text(40));
+        assert_eq!(ps.upper_mark, 0);
+
+        // Halfway through 50 lines on 10 rows is halfway through 90 lines on 10 rows
+        ps.scroll_anchor_on_setdata = ScrollAnchor::SamePercent;
+        ps.set_text(text(50));
+        ps.upper_mark = 20;
+        ps.set_text(text(90));
+        assert_eq!(ps.upper_mark, 40);
+
+        ps.scroll_anchor_on_setdata = ScrollAnchor::SameLine;
+        ps.set_text(format!("{}{}", "a".repeat(100), text(89)));
+        assert_eq!(ps.upper_mark, 41);
+    }
+
+    #[test]
+    fn line_map_across_appends() {
+        let mut ps = PagerState::with_dimensions(10, 20);
+        ps.append_str("first\n");
+        ps.append_str(&format!("{}\nthird\nfou", "a".repeat(30)));
+        ps.append_str("rth\nfifth");
+        assert_eq!(ps.line_map, vec![0, 1, 1, 2, 3, 4]);
+
+        let appended = ps.line_map.clone();
+        ps.format_lines();
+        assert_eq!(ps.line_map, appended);
+        assert_eq!(ps.source_line(2), 1);
+        assert_eq!(ps.first_row_of(2), 3);
+    }
+
+    #[test]
+    fn line_number_offset() {
+        let mut ps = PagerState::new().unwrap();
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.line_number_offset = 998;
+        ps.append_str("first\nsecond\n");
+
+        // The numbers are padded for the width of 1000
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["      999. first", "     1000. second"]
+        );
+    }
+
+    #[test]
+    fn line_number_formatter() {
+        let mut ps = PagerState::with_dimensions(10, 20);
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.line_number_offset = 8;
+        ps.line_number_formatter = Some(Box::new(|number, width| {
+            format!("{:<width$}", format!("#{}", number), width = width)
+        }));
+        ps.append_str("first\nthis line is wrapped into rows\n");
+
+        assert_eq!(
+            ps.formatted_lines,
+            vec![
+                "#9       first",
+                "#10      this line",
+                "         is wrapped",
+                "         into rows",
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_indicator() {
+        let mut ps = PagerState::with_dimensions(10, 10);
+        ps.wrap_indicator = Some('>');
+        ps.append_str(&format!("short\n{}\n", "a".repeat(20)));
+
+        // The indicator takes up a column of the continuation rows. Words longer than a row are
+        // broken at the width of the continuation rows, hence the first row is a column short
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["short", "aaaaaaaaa", ">aaaaaaaaa", ">aa"]
+        );
+    }
+
+    #[test]
+    fn gutter_markers_follow_lines() {
+        let mut ps = PagerState::new().unwrap();
+        ps.gutter_markers.insert(1, ('+', ContentStyle::default()));
+        ps.append_str("first\nsecond\n");
+        ps.append_str("third\n");
+        ps.gutter_markers.insert(3, ('-', ContentStyle::default()));
+        ps.append_str("fourth\n");
+
+        assert_eq!(
+            ps.formatted_lines,
+            vec![" first", "+second", " third", "-fourth"]
+        );
+
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.format_lines();
+        assert_eq!(
+            ps.formatted_lines,
+            vec![
+                "      1. first",
+                "+     2. second",
+                "      3. third",
+                "-     4. fourth",
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_width_override() {
+        const TEXT: &str = "this is a line which is long enough to be wrapped";
+        let mut ps = PagerState::new().unwrap();
+        ps.wrap_width = Some(20);
+        ps.append_str(TEXT);
+
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["this is a line which", "is long enough to be", "wrapped"]
+        );
+
+        // The terminal width is used if it is less than the wrap width
+        ps.cols = 15;
+        ps.format_lines();
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["this is a line", "which is long", "enough to be", "wrapped"]
+        );
+    }
+
+    #[test]
+    fn gutter_marker_wrapped_rows() {
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 11;
+        ps.gutter_markers.insert(0, ('>', ContentStyle::default()));
+        ps.append_str("this is a long line\n");
+
+        assert_eq!(ps.formatted_lines, vec![">this is a", " long line"]);
+    }
+
+    #[test]
+    fn trailing_whitespace() {
+        let style = ContentStyle {
+            attributes: Attribute::Reverse.into(),
+            ..ContentStyle::default()
+        };
+        let spaces = |n: usize| crate::apply_style(style, &" ".repeat(n));
+        let mut ps = PagerState::with_dimensions(10, 10);
+        ps.trailing_whitespace_style = Some(style);
+        ps.append_str("no space\nfour    \nnine spaces      \n");
+
+        // Whitespace that doesn't fit on the last row continues on the next one, while the
+        // whitespace at the wrapping points isn't highlighted
+        assert_eq!(
+            ps.formatted_lines,
+            vec![
+                "no space".to_string(),
+                "four".to_string() + &spaces(4),
+                "nine".to_string(),
+                "spaces".to_string() + &spaces(4),
+                spaces(2),
+            ]
+        );
+
+        // Without wrapping, only the whitespace that fits is shown
+        ps.line_wrapping = false;
+        ps.truncation_marker = None;
+        ps.format_lines();
+        assert_eq!(ps.formatted_lines[2], "nine space");
+        assert_eq!(ps.formatted_lines[1], "four".to_string() + &spaces(4));
+
+        // Search matches are highlighted over the whitespace
+        #[cfg(feature = "search")]
+        {
+            ps.search_term = Some(regex::Regex::new(r"r\s").unwrap());
+            ps.format_lines();
+            assert!(ps.formatted_lines[1].ends_with(&spaces(3)));
+            assert!(!ps.formatted_lines[1].ends_with(&spaces(4)));
+        }
+    }
+}
+
+#[test]
+fn apply_style() {
+    use crossterm::style::{Attribute, Color, ContentStyle};
+
+    assert_eq!(crate::apply_style(ContentStyle::default(), "text"), "text");
+
+    let style = ContentStyle {
+        foreground_color: Some(Color::Red),
+        attributes: Attribute::Bold.into(),
+        ..ContentStyle::default()
+    };
+    assert_eq!(
+        crate::apply_style(style, "text"),
+        "\x1b[38;5;9m\x1b[1mtext\x1b[0m"
+    );
 }
 
 // Test exit callbacks function
@@ -259,11 +601,162 @@ mod wrapping {
             (result[0].len(), result[1].len(), result[2].len()),
         );
     }
+
+    #[test]
+    fn wrap_str_zero_width() {
+        // An e with a combining acute accent takes up a single column, and zero width joiners
+        // take up none
+        let accented = "e\u{301}".repeat(10);
+        assert_eq!(crate::wrap_str(&accented, 10), vec![accented.clone()]);
+        assert_eq!(
+            crate::wrap_str(&accented, 5),
+            vec!["e\u{301}".repeat(5), "e\u{301}".repeat(5)]
+        );
+
+        let joined = "a\u{200d}b\u{200d}c";
+        assert_eq!(crate::wrap_str(joined, 3), vec![joined]);
+        assert_eq!(
+            crate::truncate_str(&format!("{}d", accented), 10, None),
+            accented
+        );
+    }
+
+    #[test]
+    fn truncate_str() {
+        assert_eq!(
+            crate::truncate_str(&"#".repeat(200), 80, None),
+            "#".repeat(80)
+        );
+        assert_eq!(crate::truncate_str("short", 80, None), "short");
+        assert_eq!(crate::truncate_str("", 80, None), "");
+        // The styling is reset if the escape that ends it is cut off
+        assert_eq!(
+            crate::truncate_str("\x1b[1mbold text\x1b[0m", 4, None),
+            "\x1b[1mbold\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn truncate_str_with_marker() {
+        // The marker takes up the last column
+        assert_eq!(
+            crate::truncate_str(&"#".repeat(200), 80, Some('…')),
+            format!("{}…", "#".repeat(79))
+        );
+        // Lines that fit don't get a marker
+        assert_eq!(crate::truncate_str("short", 5, Some('…')), "short");
+        // The marker comes after the reset, so it isn't styled
+        assert_eq!(
+            crate::truncate_str("\x1b[1mbold text\x1b[0m", 5, Some('…')),
+            "\x1b[1mbold\x1b[0m…"
+        );
+        // Wide markers are accounted for
+        assert_eq!(crate::truncate_str("abcdef", 4, Some('界')), "ab界");
+        // There is no room for the marker
+        assert_eq!(crate::truncate_str("abcdef", 1, Some('…')), "a");
+    }
+
+    #[test]
+    fn line_formatter() {
+        use crate::PagerState;
+
+        let mut ps = PagerState::with_dimensions(10, 10);
+        ps.line_formatter = Some(Box::new(|line| line.replace("**", "")));
+        ps.lines = "**bold**\n".to_string();
+        ps.format_lines();
+        assert_eq!(ps.formatted_lines, vec!["bold"]);
+
+        // The output of the formatter is what gets wrapped
+        ps.set_line_formatter(Box::new(|line| line.repeat(3)));
+        assert_eq!(ps.formatted_lines, vec!["**bold****", "bold****bo", "ld**"]);
+        // Appended lines are formatted too
+        ps.append_str("ab\n");
+        assert_eq!(ps.formatted_lines[3], "ababab");
+    }
+
+    #[test]
+    fn line_formatter_runs_once() {
+        use crate::PagerState;
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls2 = calls.clone();
+        let mut ps = PagerState::with_dimensions(10, 10);
+        ps.set_line_formatter(Box::new(move |line| {
+            calls2.fetch_add(1, Ordering::SeqCst);
+            line.to_uppercase()
+        }));
+        ps.append_str("a\nb\nc");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Only the continued line is formatted again when text is appended
+        ps.append_str("d\n");
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert_eq!(ps.formatted_lines, vec!["A", "B", "CD"]);
+
+        // Rewrapping the text uses the lines that were formatted before
+        ps.cols = 5;
+        ps.format_lines();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // A new formatter runs on all lines
+        ps.set_line_formatter(Box::new(|line| line.repeat(2)));
+        assert_eq!(ps.formatted_lines, vec!["aa", "bb", "cdcd"]);
+    }
+
+    #[test]
+    fn wrap_progress() {
+        use crate::PagerState;
+        use std::sync::{Arc, Mutex};
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let reports2 = reports.clone();
+        let mut ps = PagerState::new().unwrap();
+        ps.wrap_progress_callback = Some(Box::new(move |done, total| {
+            reports2.lock().unwrap().push((done, total));
+        }));
+
+        // Small text is not reported
+        ps.lines = "line\n".repeat(100);
+        ps.format_lines();
+        assert!(reports.lock().unwrap().is_empty());
+
+        ps.lines = "line\n".repeat(25_000);
+        ps.format_lines();
+        assert_eq!(
+            *reports.lock().unwrap(),
+            vec![(10_000, 25_000), (20_000, 25_000), (25_000, 25_000)]
+        );
+
+        // Appended text is reported on its own
+        reports.lock().unwrap().clear();
+        ps.append_str(&"line\n".repeat(10_000));
+        assert_eq!(*reports.lock().unwrap(), vec![(10_000, 10_000)]);
+        reports.lock().unwrap().clear();
+        ps.append_str(&"line\n".repeat(20_001));
+        assert_eq!(
+            *reports.lock().unwrap(),
+            vec![(10_000, 20_001), (20_000, 20_001), (20_001, 20_001)]
+        );
+    }
 }
 
 mod emit_events {
     // Check functions emit correct events on functin calls
-    use crate::{minus_core::events::Event, ExitStrategy, LineNumbers, Pager};
+    use crate::{
+        input::InputEvent, minus_core::events::Event, Clipboard, ColorOutput, CtrlCBehavior,
+        ExitStrategy, LineNumbers, MessageStyle, MinusError, Pager, PagerState, PagerTemplate,
+        ScrollAnchor,
+    };
+    use crossterm::{
+        event::{KeyCode, KeyEvent, KeyModifiers},
+        style::{Color, ContentStyle},
+    };
+    use parking_lot::Mutex;
+    use std::sync::Arc;
 
     const TEST_STR: &str = "This is sample text";
     #[test]
@@ -276,6 +769,26 @@ mod emit_events {
         );
     }
 
+    #[test]
+    fn set_text_source() {
+        struct Line(String);
+        impl crate::TextSource for Line {
+            fn line_count(&self) -> usize {
+                1
+            }
+            fn line(&self, _idx: usize) -> &str {
+                &self.0
+            }
+        }
+
+        let pager = Pager::new();
+        pager.set_text_source(Line(TEST_STR.to_string())).unwrap();
+        assert!(matches!(
+            pager.rx.try_recv().unwrap(),
+            Event::SetTextSource(_)
+        ));
+    }
+
     #[test]
     fn push_str() {
         let pager = Pager::new();
@@ -306,6 +819,16 @@ mod emit_events {
         );
     }
 
+    #[test]
+    fn send_input() {
+        let pager = Pager::new();
+        pager.send_input(InputEvent::UpdateUpperMark(3)).unwrap();
+        assert_eq!(
+            Event::UserInput(InputEvent::UpdateUpperMark(3)),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
     #[test]
     #[cfg(feature = "static_output")]
     fn set_run_no_overflow() {
@@ -315,121 +838,858 @@ mod emit_events {
     }
 
     #[test]
-    fn set_line_numbers() {
+    #[cfg(feature = "dynamic_output")]
+    fn set_inline_until() {
         let pager = Pager::new();
-        pager.set_line_numbers(LineNumbers::Enabled).unwrap();
+        pager.set_inline_until(10).unwrap();
+        assert_eq!(Event::SetInlineUntil(10), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic_output")]
+    fn set_exit_when_fits() {
+        let pager = Pager::new();
+        pager.set_exit_when_fits(true).unwrap();
+        assert_eq!(Event::SetExitWhenFits(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_page_overlap() {
+        let pager = Pager::new();
+        pager.set_page_overlap(1).unwrap();
+        assert_eq!(Event::SetPageOverlap(1), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_scroll_anchor_on_setdata() {
+        let pager = Pager::new();
+        pager
+            .set_scroll_anchor_on_setdata(ScrollAnchor::SamePercent)
+            .unwrap();
         assert_eq!(
-            Event::SetLineNumbers(LineNumbers::Enabled),
+            Event::SetScrollAnchorOnSetData(ScrollAnchor::SamePercent),
             pager.rx.try_recv().unwrap()
         );
     }
 
     #[test]
-    fn set_exit_strategy() {
+    fn batch() {
         let pager = Pager::new();
-        pager.set_exit_strategy(ExitStrategy::PagerQuit).unwrap();
+        pager
+            .batch(|pager| {
+                pager.set_text("text")?;
+                pager.set_prompt("prompt")
+            })
+            .unwrap();
         assert_eq!(
-            Event::SetExitStrategy(ExitStrategy::PagerQuit),
+            Event::Batch(vec![
+                Event::SetData("text".to_string()),
+                Event::SetPrompt("prompt".to_string())
+            ]),
             pager.rx.try_recv().unwrap()
         );
+
+        // Nothing is sent if the closure fails
+        let res = pager.batch(|pager| {
+            pager.set_text("text")?;
+            Err(MinusError::Conversion)
+        });
+        assert!(res.is_err());
+        assert!(pager.rx.try_recv().is_err());
     }
 
     #[test]
-    fn add_exit_callback() {
-        let func = Box::new(|| println!("Hello"));
+    fn set_enter_dismiss_also_scrolls() {
         let pager = Pager::new();
-        pager.add_exit_callback(func.clone()).unwrap();
-
-        assert_eq!(Event::AddExitCallback(func), pager.rx.try_recv().unwrap());
+        pager.set_enter_dismiss_also_scrolls(true).unwrap();
+        assert_eq!(
+            Event::SetEnterDismissAlsoScrolls(true),
+            pager.rx.try_recv().unwrap()
+        );
     }
-}
-
-mod unterminated {
-    use crate::PagerState;
 
     #[test]
-    fn test_single_no_endline() {
-        let mut ps = PagerState::new().unwrap();
-        let (_, unterm) = ps.make_append_str("This is a line");
-        assert_eq!(1, unterm);
+    fn pager_template() {
+        let template = PagerTemplate::new()
+            .with(|pager| pager.set_prompt("template"))
+            .with(|pager| pager.set_line_numbers(LineNumbers::Enabled));
+        let variant = template.clone().with(|pager| pager.set_prompt("variant"));
+
+        // Each pager gets its own channel with all the configuration in it
+        let first = template.build().unwrap();
+        let second = template.build().unwrap();
+        for pager in &[&first, &second] {
+            assert_eq!(
+                Event::SetPrompt("template".to_string()),
+                pager.rx.try_recv().unwrap()
+            );
+            assert_eq!(
+                Event::SetLineNumbers(LineNumbers::Enabled),
+                pager.rx.try_recv().unwrap()
+            );
+            assert!(pager.rx.try_recv().is_err());
+        }
+
+        let pager = variant.build().unwrap();
+        assert_eq!(pager.rx.len(), 3);
     }
 
     #[test]
-    fn test_single_endline() {
-        let mut ps = PagerState::new().unwrap();
-        let (_, unterm) = ps.make_append_str("This is a line\n");
-        assert_eq!(0, unterm);
+    fn set_key_sequence_timeout() {
+        let pager = Pager::new();
+        let timeout = std::time::Duration::from_millis(500);
+        pager.set_key_sequence_timeout(timeout).unwrap();
+        assert_eq!(
+            Event::SetKeySequenceTimeout(timeout),
+            pager.rx.try_recv().unwrap()
+        );
     }
 
     #[test]
-    fn test_single_multi_newline() {
-        let mut ps = PagerState::new().unwrap();
-        let (_, unterm) =
-            ps.make_append_str("This is a line\nThis is another line\nThis is third line");
-        assert_eq!(1, unterm);
+    fn set_resize_debounce() {
+        let pager = Pager::new();
+        let interval = std::time::Duration::from_millis(100);
+        pager.set_resize_debounce(interval).unwrap();
+        assert_eq!(
+            Event::SetResizeDebounce(interval),
+            pager.rx.try_recv().unwrap()
+        );
     }
 
     #[test]
-    fn test_single_multi_endline() {
-        let mut ps = PagerState::new().unwrap();
-        let (_, unterm) = ps.make_append_str("This is a line\nThis is another line\n");
-        assert_eq!(0, unterm);
+    fn set_rewrap_on_resize() {
+        let pager = Pager::new();
+        pager.set_rewrap_on_resize(false).unwrap();
+        assert_eq!(
+            Event::SetRewrapOnResize(false),
+            pager.rx.try_recv().unwrap()
+        );
     }
 
     #[test]
-    fn test_single_line_wrapping() {
-        let mut ps = PagerState::new().unwrap();
-        ps.cols = 20;
-        let (_, unterm) = ps.make_append_str("This is a quite lengthy lint");
-        assert_eq!(2, unterm);
+    fn set_managed_input() {
+        let pager = Pager::new();
+        pager.set_managed_input(false).unwrap();
+        assert_eq!(Event::SetManagedInput(false), pager.rx.try_recv().unwrap());
     }
 
     #[test]
-    fn test_single_mid_newline_wrapping() {
-        let mut ps = PagerState::new().unwrap();
-        ps.cols = 20;
-        let (_, unterm) = ps.make_append_str(
-            "This is a quite lengthy lint\nIt has three lines\nThis is
-third line",
+    fn feed_crossterm_event() {
+        use crate::input::InputEvent;
+        use crossterm::event::{Event as TermEvent, KeyCode, KeyEvent, KeyModifiers};
+
+        let pager = Pager::new();
+        let key = |c| {
+            TermEvent::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        // Discarded before the pager starts
+        assert!(matches!(
+            pager.feed_crossterm_event(key('q')),
+            Err(MinusError::NotStarted)
+        ));
+        assert!(pager.rx.try_recv().is_err());
+
+        share_state(&pager, PagerState::with_dimensions(4, 80));
+        pager.feed_crossterm_event(key('q')).unwrap();
+        assert_eq!(
+            Event::UserInput(InputEvent::Exit),
+            pager.rx.try_recv().unwrap()
         );
-        assert_eq!(1, unterm);
+
+        // Keys without an action don't send anything
+        pager.feed_crossterm_event(key('%')).unwrap();
+        assert!(pager.rx.try_recv().is_err());
     }
 
     #[test]
-    fn test_single_endline_wrapping() {
-        let mut ps = PagerState::new().unwrap();
+    #[cfg(feature = "dynamic_output")]
+    fn set_exit_on_disconnect() {
+        let pager = Pager::new();
+        pager.set_exit_on_disconnect(true).unwrap();
+        assert_eq!(
+            Event::SetExitOnDisconnect(true),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_message_lines() {
+        let pager = Pager::new();
+        pager.set_message_lines(3).unwrap();
+        assert_eq!(Event::SetMessageLines(3), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_overscroll() {
+        let pager = Pager::new();
+        pager.set_overscroll(true).unwrap();
+        assert_eq!(Event::SetOverscroll(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_max_prefix_digits() {
+        let pager = Pager::new();
+        pager.set_max_prefix_digits(4).unwrap();
+        assert_eq!(Event::SetMaxPrefixDigits(4), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_initial_upper_mark() {
+        let pager = Pager::new();
+        pager.set_initial_upper_mark(120).unwrap();
+        assert_eq!(Event::SetUpperMark(120), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_dim_non_matches() {
+        let pager = Pager::new();
+        pager.set_dim_non_matches(true).unwrap();
+        assert_eq!(Event::SetDimNonMatches(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn append_bytes() {
+        let pager = Pager::new();
+        pager.append_bytes(b"valid \xF0\x90\x80 invalid\n").unwrap();
+        assert_eq!(
+            Event::AppendData("valid \u{FFFD} invalid\n".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn with_event_buffer_size() {
+        let pager = Pager::with_event_buffer_size(2);
+        assert_eq!(pager.tx.capacity(), Some(2));
+        assert_eq!(Pager::new().tx.capacity(), None);
+    }
+
+    /// Share `ps` with `pager` like a running pager does
+    fn share_state(pager: &Pager, ps: PagerState) -> Arc<Mutex<PagerState>> {
+        let ps = Arc::new(Mutex::new(ps));
+        *pager.ps.lock() = Some(ps.clone());
+        ps
+    }
+
+    #[test]
+    fn upper_mark() {
+        let pager = Pager::new();
+        // Nothing to read before the pager starts
+        assert_eq!(pager.upper_mark(), None);
+
+        let mut ps = PagerState::with_dimensions(4, 80);
+        ps.lines = "one\ntwo\nthree\nfour\nfive\n".to_string();
+        ps.format_lines();
+        ps.upper_mark = 1;
+        let ps = share_state(&pager, ps);
+        assert_eq!(pager.upper_mark(), Some(1));
+
+        // The upper mark is clamped just like when drawing
+        ps.lock().upper_mark = 100;
+        assert_eq!(pager.upper_mark(), Some(2));
+    }
+
+    #[test]
+    fn prompt() {
+        let pager = Pager::new();
+        assert_eq!(pager.prompt(), PagerState::new().unwrap().prompt);
+        // The prompt that was set is returned before the pager starts
+        pager.set_prompt("queued").unwrap();
+        assert_eq!(pager.prompt(), "queued");
+        // Clones share the state
+        let pager2 = pager.clone();
+        let mut ps = PagerState::with_dimensions(4, 80);
+        ps.prompt = TEST_STR.to_string();
+        share_state(&pager2, ps);
+        assert_eq!(pager.prompt(), TEST_STR);
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    #[allow(clippy::trivial_regex)]
+    fn search_matches() {
+        let pager = Pager::new();
+        assert!(pager.search_matches().is_empty());
+        let mut ps = PagerState::with_dimensions(10, 10);
+        ps.search_term = Some(regex::Regex::new("x").unwrap());
+        // The second line wraps into three rows, each with a match
+        ps.lines = format!("foo\n{}\nbar\nx\n", "x".repeat(25));
+        ps.format_lines();
+        share_state(&pager, ps);
+        assert_eq!(pager.search_matches(), vec![1, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn current_search() {
+        let pager = Pager::new();
+        assert!(!pager.is_search_active());
+        assert_eq!(pager.current_search(), None);
+
+        let ps = share_state(&pager, PagerState::with_dimensions(4, 80));
+        assert!(!pager.is_search_active());
+        ps.lock().search_term = Some(regex::Regex::new(r"fo+\b").unwrap());
+        assert!(pager.is_search_active());
+        assert_eq!(pager.current_search(), Some(r"fo+\b".to_string()));
+
+        ps.lock().search_term = None;
+        assert!(!pager.is_search_active());
+        assert_eq!(pager.current_search(), None);
+    }
+
+    #[test]
+    fn visible_text() {
+        let pager = Pager::new();
+        assert_eq!(pager.visible_text(), None);
+
+        let mut ps = PagerState::with_dimensions(4, 80);
+        ps.lines = "one\ntwo\nthree\nfour\nfive\n".to_string();
+        ps.format_lines();
+        ps.upper_mark = 1;
+        let ps = share_state(&pager, ps);
+        assert_eq!(pager.visible_text().unwrap(), "two\nthree\nfour");
+
+        // The upper mark is clamped just like when drawing
+        ps.lock().upper_mark = 100;
+        assert_eq!(pager.visible_text().unwrap(), "three\nfour\nfive");
+
+        ps.lock().line_numbers = LineNumbers::Enabled;
+        ps.lock().format_lines();
+        assert_eq!(
+            pager.visible_text().unwrap(),
+            "     3. three\n     4. four\n     5. five"
+        );
+    }
+
+    #[test]
+    fn set_color_output() {
+        let pager = Pager::new();
+        pager.set_color_output(ColorOutput::Never).unwrap();
+        assert_eq!(
+            Event::SetColorOutput(ColorOutput::Never),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_number_style() {
+        let pager = Pager::new();
+        let style = ContentStyle {
+            foreground_color: Some(Color::DarkGrey),
+            ..ContentStyle::default()
+        };
+        pager.set_line_number_style(style).unwrap();
+        assert_eq!(
+            Event::SetLineNumberStyle(style),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_highlight_trailing_whitespace() {
+        let pager = Pager::new();
+        let style = ContentStyle {
+            background_color: Some(Color::Red),
+            ..ContentStyle::default()
+        };
+        pager
+            .set_highlight_trailing_whitespace(Some(style))
+            .unwrap();
+        assert_eq!(
+            Event::SetTrailingWhitespaceStyle(Some(style)),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_number_offset() {
+        let pager = Pager::new();
+        pager.set_line_number_offset(999).unwrap();
+        assert_eq!(
+            Event::SetLineNumberOffset(999),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_max_lines() {
+        let pager = Pager::new();
+        pager.set_max_lines(Some(100)).unwrap();
+        assert_eq!(Event::SetMaxLines(Some(100)), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_pinned_header_lines() {
+        let pager = Pager::new();
+        pager.set_pinned_header_lines(2).unwrap();
+        assert_eq!(Event::SetPinnedHeaderLines(2), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_prompt_bottom_padding() {
+        let pager = Pager::new();
+        pager.set_prompt_bottom_padding(1).unwrap();
+        assert_eq!(
+            Event::SetPromptBottomPadding(1),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_prompt_rows() {
+        let pager = Pager::new();
+        pager.set_prompt_rows(2).unwrap();
+        assert_eq!(Event::SetPromptRows(2), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_clipboard() {
+        let pager = Pager::new();
+        pager.set_clipboard(Clipboard::Osc52).unwrap();
+        assert_eq!(
+            Event::SetClipboard(Clipboard::Osc52),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_ctrl_c_behavior() {
+        let pager = Pager::new();
+        pager.set_ctrl_c_behavior(CtrlCBehavior::Ignore).unwrap();
+        assert_eq!(
+            Event::SetCtrlCBehavior(CtrlCBehavior::Ignore),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_message_style() {
+        let pager = Pager::new();
+        pager.set_message_style(MessageStyle::Append).unwrap();
+        assert_eq!(
+            Event::SetMessageStyle(MessageStyle::Append),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_inactive_pane_style() {
+        let pager = Pager::new();
+        let style = ContentStyle {
+            foreground_color: Some(Color::DarkGrey),
+            ..ContentStyle::default()
+        };
+        pager.set_inactive_pane_style(Some(style)).unwrap();
+        assert_eq!(
+            Event::SetInactivePaneStyle(Some(style)),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_auto_scroll() {
+        let pager = Pager::new();
+        let interval = Some(std::time::Duration::from_millis(500));
+        pager.set_auto_scroll(interval).unwrap();
+        assert_eq!(Event::SetAutoScroll(interval), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn scroll_by() {
+        let pager = Pager::new();
+        pager.scroll_by(-3).unwrap();
+        assert_eq!(Event::ScrollBy(-3), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_line_wrapping() {
+        let pager = Pager::new();
+        pager.set_line_wrapping(false).unwrap();
+        assert_eq!(Event::SetLineWrapping(false), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_wrap_width() {
+        let pager = Pager::new();
+        pager.set_wrap_width(Some(40)).unwrap();
+        assert_eq!(Event::SetWrapWidth(Some(40)), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_wrap_indicator() {
+        let pager = Pager::new();
+        pager.set_wrap_indicator(Some('>')).unwrap();
+        assert_eq!(
+            Event::SetWrapIndicator(Some('>')),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn append_lines_iter() {
+        let pager = Pager::new();
+        pager.append_lines_iter(vec!["one", "two"]).unwrap();
+        assert_eq!(
+            Event::AppendData("one\ntwo\n".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+
+        // Nothing is sent without any lines
+        pager.append_lines_iter(Vec::<String>::new()).unwrap();
+        assert!(pager.rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn set_empty_placeholder() {
+        let pager = Pager::new();
+        pager
+            .set_empty_placeholder(Some("Waiting".to_string()))
+            .unwrap();
+        assert_eq!(
+            Event::SetEmptyPlaceholder(Some("Waiting".to_string())),
+            pager.rx.try_recv().unwrap()
+        );
+        pager.set_empty_placeholder(None).unwrap();
+        assert_eq!(
+            Event::SetEmptyPlaceholder(None),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_reverse_lines() {
+        let pager = Pager::new();
+        pager.set_reverse_lines(true).unwrap();
+        assert_eq!(Event::SetReverseLines(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_split() {
+        let pager = Pager::new();
+        pager.set_split(true).unwrap();
+        assert_eq!(Event::SetSplit(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_show_scrollbar() {
+        let pager = Pager::new();
+        pager.set_show_scrollbar(true).unwrap();
+        assert_eq!(Event::SetShowScrollbar(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_truncation_marker() {
+        let pager = Pager::new();
+        pager.set_truncation_marker(None).unwrap();
+        assert_eq!(
+            Event::SetTruncationMarker(None),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_number_toggle_key() {
+        let pager = Pager::new();
+        let key = KeyEvent {
+            code: KeyCode::Char('#'),
+            modifiers: KeyModifiers::NONE,
+        };
+        pager.set_line_number_toggle_key(key).unwrap();
+        assert_eq!(
+            Event::SetLineNumberToggleKey(key),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_redraw_key() {
+        let pager = Pager::new();
+        let key = KeyEvent {
+            code: KeyCode::Char('r'),
+            modifiers: KeyModifiers::CONTROL,
+        };
+        pager.set_redraw_key(key).unwrap();
+        assert_eq!(Event::SetRedrawKey(key), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_wrap_toggle_key() {
+        let pager = Pager::new();
+        let key = KeyEvent {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::NONE,
+        };
+        pager.set_wrap_toggle_key(key).unwrap();
+        assert_eq!(Event::SetWrapToggleKey(key), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_show_help_key() {
+        let pager = Pager::new();
+        pager.set_show_help_key(None).unwrap();
+        assert_eq!(Event::SetHelpKey(None), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn add_remove_exit_key() {
+        let pager = Pager::new();
+        let key = KeyEvent {
+            code: KeyCode::F(10),
+            modifiers: KeyModifiers::NONE,
+        };
+        pager.add_exit_key(key).unwrap();
+        pager.remove_exit_key(key).unwrap();
+        assert_eq!(Event::AddExitKey(key), pager.rx.try_recv().unwrap());
+        assert_eq!(Event::RemoveExitKey(key), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_clear_highlights_key() {
+        let pager = Pager::new();
+        let key = KeyEvent {
+            code: KeyCode::Char('h'),
+            modifiers: KeyModifiers::CONTROL,
+        };
+        pager.set_clear_highlights_key(key).unwrap();
+        assert_eq!(
+            Event::SetClearHighlightsKey(key),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_gutter_marker() {
+        let pager = Pager::new();
+        let style = ContentStyle::default();
+        pager.set_gutter_marker(3, '+', style).unwrap();
+        assert_eq!(
+            Event::SetGutterMarker(3, Some(('+', style))),
+            pager.rx.try_recv().unwrap()
+        );
+        pager.remove_gutter_marker(3).unwrap();
+        assert_eq!(
+            Event::SetGutterMarker(3, None),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_numbers() {
+        let pager = Pager::new();
+        pager.set_line_numbers(LineNumbers::Enabled).unwrap();
+        assert_eq!(
+            Event::SetLineNumbers(LineNumbers::Enabled),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_exit_strategy() {
+        let pager = Pager::new();
+        pager.set_exit_strategy(ExitStrategy::PagerQuit).unwrap();
+        assert_eq!(
+            Event::SetExitStrategy(ExitStrategy::PagerQuit),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn add_exit_callback() {
+        let func = Box::new(|| println!("Hello"));
+        let pager = Pager::new();
+        pager.add_exit_callback(func.clone()).unwrap();
+
+        assert_eq!(Event::AddExitCallback(func), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_wrap_progress_callback() {
+        let pager = Pager::new();
+        pager
+            .set_wrap_progress_callback(Box::new(|_, _| {}))
+            .unwrap();
+        assert_eq!(
+            Event::SetWrapProgressCallback(Box::new(|_, _| {})),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_on_line_numbers_changed() {
+        let pager = Pager::new();
+        pager.set_on_line_numbers_changed(Box::new(|_| {})).unwrap();
+        pager.set_on_wrap_changed(Box::new(|_| {})).unwrap();
+        assert_eq!(
+            Event::SetOnLineNumbersChanged(Box::new(|_| {})),
+            pager.rx.try_recv().unwrap()
+        );
+        assert_eq!(
+            Event::SetOnWrapChanged(Box::new(|_| {})),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_on_reach_bottom() {
+        let pager = Pager::new();
+        pager.set_on_reach_bottom(Box::new(|| {})).unwrap();
+        assert_eq!(
+            Event::SetOnReachBottom(Box::new(|| {})),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_formatter() {
+        let pager = Pager::new();
+        pager
+            .set_line_formatter(Box::new(ToString::to_string))
+            .unwrap();
+        assert_eq!(
+            Event::SetLineFormatter(Box::new(ToString::to_string)),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_number_formatter() {
+        let pager = Pager::new();
+        pager
+            .set_line_number_formatter(Box::new(|number, _| number.to_string()))
+            .unwrap();
+        assert_eq!(
+            Event::SetLineNumberFormatter(Box::new(|number, _| number.to_string())),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_render_transform() {
+        let pager = Pager::new();
+        pager
+            .set_render_transform(Box::new(|row, _| row.to_string()))
+            .unwrap();
+        assert_eq!(
+            Event::SetRenderTransform(Box::new(|row, _| row.to_string())),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_input_trace() {
+        let pager = Pager::new();
+        pager.set_input_trace(Box::new(|_, _| {})).unwrap();
+        assert_eq!(
+            Event::SetInputTrace(Box::new(|_, _| {})),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+}
+
+mod unterminated {
+    use crate::PagerState;
+
+    #[test]
+    fn test_single_no_endline() {
+        let mut ps = PagerState::new().unwrap();
+        let unterm = ps.make_append_str("This is a line").num_unterminated;
+        assert_eq!(1, unterm);
+    }
+
+    #[test]
+    fn test_single_endline() {
+        let mut ps = PagerState::new().unwrap();
+        let unterm = ps.make_append_str("This is a line\n").num_unterminated;
+        assert_eq!(0, unterm);
+    }
+
+    #[test]
+    fn test_single_multi_newline() {
+        let mut ps = PagerState::new().unwrap();
+        let unterm = ps
+            .make_append_str("This is a line\nThis is another line\nThis is third line")
+            .num_unterminated;
+        assert_eq!(1, unterm);
+    }
+
+    #[test]
+    fn test_single_multi_endline() {
+        let mut ps = PagerState::new().unwrap();
+        let unterm = ps
+            .make_append_str("This is a line\nThis is another line\n")
+            .num_unterminated;
+        assert_eq!(0, unterm);
+    }
+
+    #[test]
+    fn test_single_line_wrapping() {
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 20;
+        let unterm = ps
+            .make_append_str("This is a quite lengthy lint")
+            .num_unterminated;
+        assert_eq!(2, unterm);
+    }
+
+    #[test]
+    fn test_single_mid_newline_wrapping() {
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 20;
+        let unterm = ps
+            .make_append_str(
+                "This is a quite lengthy lint\nIt has three lines\nThis is
+third line",
+            )
+            .num_unterminated;
+        assert_eq!(1, unterm);
+    }
+
+    #[test]
+    fn test_single_endline_wrapping() {
+        let mut ps = PagerState::new().unwrap();
         ps.cols = 20;
-        let (_, unterm) = ps.make_append_str(
-            "This is a quite lengthy lint\nIt has three lines\nThis is
+        let unterm = ps
+            .make_append_str(
+                "This is a quite lengthy lint\nIt has three lines\nThis is
 third line\n",
-        );
+            )
+            .num_unterminated;
         assert_eq!(0, unterm);
     }
 
     #[test]
     fn test_multi_no_endline() {
         let mut ps = PagerState::new().unwrap();
-        let (_, unterm) = ps.make_append_str("This is a line");
+        let unterm = ps.make_append_str("This is a line").num_unterminated;
         assert_eq!(1, unterm);
-        let (_, unterm) = ps.make_append_str("This is another line");
+        let unterm = ps.make_append_str("This is another line").num_unterminated;
         assert_eq!(1, unterm);
     }
 
     #[test]
     fn test_multi_endline() {
         let mut ps = PagerState::new().unwrap();
-        let (_, unterm) = ps.make_append_str("This is a line ");
+        let unterm = ps.make_append_str("This is a line ").num_unterminated;
         assert_eq!(1, unterm);
-        let (_, unterm) = ps.make_append_str("This is another line\n");
+        let unterm = ps
+            .make_append_str("This is another line\n")
+            .num_unterminated;
         assert_eq!(0, unterm);
     }
 
     #[test]
     fn test_multi_multiple_newline() {
         let mut ps = PagerState::new().unwrap();
-        let (_, unterm) = ps.make_append_str("This is a line\n");
+        let unterm = ps.make_append_str("This is a line\n").num_unterminated;
         assert_eq!(0, unterm);
-        let (_, unterm) = ps.make_append_str("This is another line\n");
+        let unterm = ps
+            .make_append_str("This is another line\n")
+            .num_unterminated;
         assert_eq!(0, unterm);
     }
 
@@ -437,9 +1697,13 @@ third line\n",
     fn test_multi_wrapping() {
         let mut ps = PagerState::new().unwrap();
         ps.cols = 20;
-        let (_, unterm) = ps.make_append_str("This is a line. This is second line");
+        let unterm = ps
+            .make_append_str("This is a line. This is second line")
+            .num_unterminated;
         assert_eq!(2, unterm);
-        let (_, unterm) = ps.make_append_str("This is another line\n");
+        let unterm = ps
+            .make_append_str("This is another line\n")
+            .num_unterminated;
         assert_eq!(0, unterm);
     }
 
@@ -447,9 +1711,13 @@ third line\n",
     fn test_multi_wrapping_continued() {
         let mut ps = PagerState::new().unwrap();
         ps.cols = 20;
-        let (_, unterm) = ps.make_append_str("This is a line. This is second line. ");
+        let unterm = ps
+            .make_append_str("This is a line. This is second line. ")
+            .num_unterminated;
         assert_eq!(2, unterm);
-        let (_, unterm) = ps.make_append_str("This is the third line");
+        let unterm = ps
+            .make_append_str("This is the third line")
+            .num_unterminated;
         assert_eq!(3, unterm);
     }
 
@@ -457,9 +1725,13 @@ third line\n",
     fn test_multi_wrapping_last_continued() {
         let mut ps = PagerState::new().unwrap();
         ps.cols = 20;
-        let (_, unterm) = ps.make_append_str("This is a line.\nThis is second line. ");
+        let unterm = ps
+            .make_append_str("This is a line.\nThis is second line. ")
+            .num_unterminated;
         assert_eq!(1, unterm);
-        let (_, unterm) = ps.make_append_str("This is the third line");
+        let unterm = ps
+            .make_append_str("This is the third line")
+            .num_unterminated;
         assert_eq!(3, unterm);
     }
 
@@ -467,11 +1739,79 @@ third line\n",
     fn test_multi_wrapping_additive() {
         let mut ps = PagerState::new().unwrap();
         ps.cols = 20;
-        let (_, unterm) = ps.make_append_str("This is a line.");
+        let unterm = ps.make_append_str("This is a line.").num_unterminated;
         assert_eq!(1, unterm);
-        let (_, unterm) = ps.make_append_str("This is second line. ");
+        let unterm = ps.make_append_str("This is second line. ").num_unterminated;
         assert_eq!(2, unterm);
-        let (_, unterm) = ps.make_append_str("This is third line");
+        let unterm = ps.make_append_str("This is third line").num_unterminated;
         assert_eq!(3, unterm);
     }
+
+    #[test]
+    fn test_num_appendable() {
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        let props = ps.make_append_str("1\n2\n3");
+        assert_eq!(Some(3), props.num_appendable);
+        ps.append_str_on_unterminated(props.fmt_lines, props.num_unterminated);
+
+        // The unterminated row is rewritten, so only two of these fit in the four rows
+        let props = ps.make_append_str("\n4\n5\n");
+        assert_eq!(Some(2), props.num_appendable);
+        ps.append_str_on_unterminated(props.fmt_lines, props.num_unterminated);
+
+        // The screen was already full
+        let props = ps.make_append_str("6\n");
+        assert_eq!(None, props.num_appendable);
+    }
+}
+
+mod wait_for_exit {
+    use crate::{
+        error::{MinusError, SetupError},
+        ExitReason, Pager,
+    };
+    use futures_lite::future::{block_on, poll_once};
+
+    #[test]
+    fn completes_on_exit() {
+        let pager = Pager::new();
+        let mut exit = Box::pin(pager.wait_for_exit());
+        assert!(block_on(poll_once(&mut exit)).is_none());
+
+        pager.exit.notify(&Ok(ExitReason::Quit));
+        assert_eq!(block_on(exit).unwrap(), ExitReason::Quit);
+        // The pager has already stopped
+        assert_eq!(block_on(pager.wait_for_exit()).unwrap(), ExitReason::Quit);
+    }
+
+    #[test]
+    fn wakes_up_waiting_task() {
+        let pager = Pager::new();
+        let exit = pager.wait_for_exit();
+        let handle = std::thread::spawn(move || block_on(exit));
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        pager.exit.notify(&Ok(ExitReason::Finished));
+        assert_eq!(handle.join().unwrap().unwrap(), ExitReason::Finished);
+    }
+
+    #[test]
+    fn gives_the_error() {
+        let pager = Pager::new();
+        let exit = pager.wait_for_exit();
+        pager
+            .exit
+            .notify(&Err(MinusError::from(SetupError::TerminalTooSmall)));
+
+        // Every waiting task gets the error
+        for res in [block_on(exit), block_on(pager.wait_for_exit())] {
+            match res {
+                Err(MinusError::Exited(msg)) => assert_eq!(
+                    msg,
+                    "Failed to initialize the terminal: The terminal is too small to display the pager"
+                ),
+                _ => panic!("expected the error of the pager"),
+            }
+        }
+    }
 }